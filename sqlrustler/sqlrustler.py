@@ -1,6 +1,6 @@
 from dataclasses import dataclass
 from enum import Enum
-from typing import Any, Dict, List
+from typing import Any, Awaitable, Callable, Dict, List
 
 
 class DatabaseType(Enum):
@@ -9,29 +9,559 @@ class DatabaseType(Enum):
     SQLite: str
 
 
+class IsolationLevel(Enum):
+    ReadUncommitted: str
+    ReadCommitted: str
+    RepeatableRead: str
+    Serializable: str
+
+
 @dataclass
 class DatabaseConfig:
     driver: DatabaseType
     url: str
+    # Pool settings, readable and settable (only pools created afterwards are
+    # affected). An acquire waits up to acquire_timeout_secs for a free
+    # connection once max_connections (at least 1) are in use, then raises
+    # RuntimeError; unused connections are closed after idle_timeout seconds.
+    # min_connections may not exceed max_connections (ValueError).
     max_connections: int = 10
     min_connections: int = 1
     idle_timeout: int = 30
+    acquire_timeout_secs: float = 30.0
 
     options: Dict[str, Any] = {}
+    # Statements run directly on a DatabaseConnection are wrapped in their own
+    # transaction and committed on success. When False they must go through
+    # DatabaseConnection.transaction().
+    autocommit: bool = False
+    # Read-only statements run on a DatabaseConnection are spread across these
+    # replicas; transactions always use the primary.
+    replica_urls: List[str] | None = None
+    # Python range parameters bind as int4range/int8range ("range") or as a
+    # bigint[] of their values ("array").
+    range_binding: str = "range"
+    # Session time zone (SET TIME ZONE) for every pooled connection; not
+    # supported on SQLite. timestamp columns are returned as naive datetimes
+    # and timestamptz as UTC-aware datetimes regardless of this setting; it
+    # governs now(), timestamp <-> timestamptz casts and text output.
+    timezone: str | None = None
+    # Prepared statements cached per pooled connection and reused across
+    # transactions (None keeps the default of 100; must be at least 1). Behind
+    # pgbouncer in transaction mode enable its prepared statement support
+    # (max_prepared_statements, 1.21+) or run statements with prepared=False.
+    statement_cache_capacity: int | None = None
+    # Python date parameters bind as date ("date") or as a timestamp at
+    # midnight ("timestamp"); datetime parameters are unaffected.
+    date_binding: str = "date"
+    # Encoding the client and database sides must both use (e.g. "UTF8"),
+    # checked when the DatabaseConnection is created; a mismatch raises
+    # ConnectionError. "UTF8", "UTF-8" and MySQL's utf8mb4 are equivalent.
+    encoding: str | None = None
+    # PostgreSQL type name (lowercase, e.g. "numeric", "timestamptz", "int4")
+    # -> "str", "epoch" (date/timestamp/timestamptz as whole seconds, naive
+    # values as UTC), "bool" (text/varchar/char(n) holding t, f, true or false
+    # in any case, as bool; anything else raises ValueError) or a
+    # callable applied to the default decoded value.
+    type_overrides: Dict[str, str | Callable[[Any], Any]] | None = None
+    # Register a Rust-backed regexp() on SQLite connections so
+    # "col REGEXP $1" works (regex crate syntax); SQLite only.
+    sqlite_regexp: bool = False
+    # Upper bound in milliseconds on every statement: statement_timeout on
+    # PostgreSQL, and enforced client-side on MySQL and SQLite the way
+    # timeout_secs is. A per-call timeout_secs replaces it for that call.
+    default_statement_timeout_ms: int | None = None
+    # insert/update/bulk_upsert raise ValueError for a str longer than its
+    # varchar(n)/char(n) column (looked up once per table in
+    # information_schema) instead of letting the server reject it or, on
+    # non-strict MySQL, truncate it. Raw execute() statements are not checked;
+    # SQLite does not enforce declared lengths.
+    check_string_lengths: bool = False
+    # Called with a NewConnection for every connection a pool opens (after
+    # timezone and the statement timeout are applied), e.g. to set session
+    # variables or create temp functions. If it raises, the connection is
+    # discarded and retried until the acquire timeout (acquire_timeout_secs),
+    # which then fails with the callback's error.
+    on_connect: Callable[["NewConnection"], None] | None = None
+    # TLS for PostgreSQL and MySQL (SQLite raises ValueError), overriding the
+    # URL's sslmode/sslrootcert/sslcert/sslkey. ssl_mode is "disable",
+    # "prefer", "require", "verify-ca" or "verify-full" (MySQL's DISABLED ...
+    # VERIFY_IDENTITY are accepted and mapped). The verify modes need a root
+    # certificate, from ssl_root_cert or the URL, and raise ValueError without
+    # one; the client certificate and key go together. Missing files raise
+    # FileNotFoundError.
+    ssl_mode: str | None = None
+    ssl_root_cert: str | None = None
+    ssl_client_cert: str | None = None
+    ssl_client_key: str | None = None
+    # execute, fetch_all and execute_batch on a DatabaseConnection run in
+    # transactions of their own; one failing with a SQLSTATE in retry_on
+    # (default serialization failure 40001, also MySQL's deadlock, and
+    # deadlock 40P01) is rolled back and run again up to max_retries times,
+    # waiting retry_backoff_ms and doubling it each time. Other errors raise
+    # at once. Statements in an explicit transaction() are never retried.
+    max_retries: int = 0
+    retry_on: List[str] | None = None
+    retry_backoff_ms: int = 50
+    # SQLite columns declared DATETIME/TIMESTAMP, DATE or TIME are returned as
+    # datetime (UTC-aware when the stored text has an offset or Z, or for
+    # Unix-second and Julian-day numbers; naive otherwise), date and time.
+    # Values that do not parse, and expressions such as CAST(col AS TEXT),
+    # keep the stored text; False returns every such column as stored.
+    # datetime, date and time parameters bind as SQLite's text format, aware
+    # datetimes converted to UTC with a +00:00 offset. JSON columns are still
+    # returned as their text, as on PostgreSQL and MySQL.
+    sqlite_datetimes: bool = True
+    # A PostgreSQL column whose type has no decoding (cidr, ltree, ...) is
+    # returned as the bytes the server sent (the text form with
+    # prepared=False, the binary one otherwise), never as None. strict_types
+    # raises TypeError naming the column and its type instead.
+    strict_types: bool = False
+    # A PostgreSQL str parameter that parses as a UUID (any form uuid.UUID
+    # accepts) binds as uuid when uuid_strings is set; its placeholder is cast
+    # to uuid, so compare it with uuid columns only. By default strings bind
+    # as text, which a uuid column will not compare with; write $1::uuid or
+    # pass uuid.UUID. A NULL in a batch takes the type of that column's
+    # strings. Other backends have no uuid type and keep binding text.
+    uuid_strings: bool = False
+
+
+# Connection passed to DatabaseConfig.on_connect; only usable during that call.
+class NewConnection:
+    def execute(self, query: str, params: List[Any] = []) -> int: ...
+    def fetch_all(
+        self, query: str, params: List[Any] = []
+    ) -> List[Dict[str, Any]]: ...
+
+
+# PostgreSQL range value returned for every range column and accepted as a
+# parameter. A None bound is unbounded; the range type is inferred from the
+# bounds (int, Decimal, date, naive or aware datetime).
+class Range:
+    lower: Any
+    upper: Any
+    lower_inc: bool
+    upper_inc: bool
+    is_empty: bool
+    def __init__(
+        self,
+        lower: Any = None,
+        upper: Any = None,
+        lower_inc: bool = True,
+        upper_inc: bool = False,
+        is_empty: bool = False,
+    ) -> None: ...
+
+
+# A dict parameter bound as the named PostgreSQL composite type instead of
+# JSON: keys name the type's attributes (absent ones are NULL; nested dicts and
+# lists fill nested composites and arrays). The placeholder is rewritten to
+# jsonb_populate_record(NULL::type_name, $n), so each value is read with its
+# attribute type's input function. Composite columns are returned as dicts of
+# their attributes (prepared statements only); wrap one again to bind it back.
+class Composite:
+    type_name: str
+    fields: Dict[str, Any]
+    def __init__(self, type_name: str, fields: Dict[str, Any]) -> None: ...
+
+
+# A PostgreSQL enum label for the named type (optionally schema-qualified). A
+# plain str binds as text, which the server will not assign to an enum column;
+# this casts its placeholder to type_name instead (as does writing $1::type_name
+# in the SQL). Enum columns, and arrays of them, are returned as str labels.
+class EnumValue:
+    type_name: str
+    label: str
+    def __init__(self, type_name: str, label: str) -> None: ...
+
+
+class NdjsonReader:
+    def readable(self) -> bool: ...
+    @property
+    def closed(self) -> bool: ...
+    def read(self, size: int | None = -1) -> bytes: ...
+    def readline(self) -> bytes: ...
+    def close(self) -> None: ...
+    def __iter__(self) -> "NdjsonReader": ...
+    def __next__(self) -> bytes: ...
+    def __enter__(self) -> "NdjsonReader": ...
+    def __exit__(self, *args: Any) -> None: ...
+
+
+class RowStream:
+    def close(self) -> None: ...
+    def __iter__(self) -> "RowStream": ...
+    def __next__(self) -> List[Dict[str, Any]]: ...
+    def __enter__(self) -> "RowStream": ...
+    def __exit__(self, *args: Any) -> None: ...
+
+
+# LISTEN/NOTIFY on its own PostgreSQL connection. A dropped connection is
+# reopened and its channels listened to again on the next recv(); any
+# notifications sent while it was down are lost.
+class Listener:
+    def listen(self, channel: str) -> None: ...
+    def unlisten(self, channel: str) -> None: ...
+    def unlisten_all(self) -> None: ...
+    # (channel, payload), or None once timeout_secs pass; waits with the GIL
+    # released, forever when timeout_secs is None.
+    def recv(self, timeout_secs: float | None = None) -> tuple[str, str] | None: ...
+    def close(self) -> None: ...
+    # Yields (channel, payload) as notifications arrive, until closed.
+    def __iter__(self) -> "Listener": ...
+    def __next__(self) -> tuple[str, str]: ...
+    def __enter__(self) -> "Listener": ...
+    def __exit__(self, *args: Any) -> None: ...
 
 
 @dataclass
 class DatabaseTransaction:
-    def execute(self, query: str, params: List[Any]) -> int: ...
-    def fetch_all(self, query: str, params: List[Any]) -> List[Dict[str, Any]]: ...
+    # timeout_secs (also taken by fetch_one, fetch_optional, fetch_all_tuples,
+    # execute_returning and bulk_change, where it bounds each statement) bounds
+    # this one statement and raises TimeoutError when it runs over. PostgreSQL cancels it on the server (a local statement_timeout,
+    # so the transaction must then be rolled back) and SQLite interrupts it;
+    # MySQL kills it from a separate connection and rolls the transaction
+    # back, leaving none active until rollback() or commit() begins the next
+    # one. Statement timeouts from
+    # default_statement_timeout_ms raise TimeoutError too.
+    # params must supply every $n up to the highest one in the query
+    # (placeholders inside strings, quoted identifiers and comments do not
+    # count), or ValueError is raised before anything is sent.
+    def execute(
+        self,
+        query: str,
+        params: List[Any],
+        prepared: bool = True,
+        timeout_secs: float | None = None,
+    ) -> int: ...
+    # Aware datetime parameters bind as timestamptz (the instant, converted to
+    # UTC) and naive ones as timestamp on PostgreSQL. MySQL binds the same
+    # scalars (bool, datetime, date, time, Decimal) and returns TIMESTAMP as a
+    # UTC-aware datetime (assuming the UTC session time zone sqlx sets),
+    # DATETIME naive, TINYINT(1) as bool, DECIMAL as Decimal and JSON as text.
+    # bytes/bytearray parameters bind as bytea (BLOB on MySQL and SQLite), and
+    # binary columns are returned as bytes.
+    # timedelta parameters bind as interval on PostgreSQL and interval columns
+    # are returned as timedelta, counting a month as 30 days (as justify_days
+    # does), so '1 mon' comes back as 30 days; OverflowError past timedelta's
+    # range. With prepared=False interval columns come back as raw bytes.
+    # numeric columns are returned as exact decimal.Decimal values (including
+    # NaN and Infinity), never as floats; Decimal parameters bind as numeric.
+    # ints bind as bigint; ones past its range bind as an exact numeric on
+    # PostgreSQL (their placeholder is cast to numeric) and as decimal text on
+    # MySQL, so 2**70 reads back unchanged from a numeric/DECIMAL column.
+    # uuid.UUID parameters bind as uuid on PostgreSQL (as the hyphenated string
+    # on MySQL) and uuid columns are returned as uuid.UUID. Strings that look
+    # like UUIDs bind as text unless DatabaseConfig.uuid_strings is set.
+    # ipaddress addresses, interfaces and networks bind as inet on PostgreSQL
+    # (assignable to cidr). inet columns are returned as an ipaddress address,
+    # or an interface when they carry a shorter prefix, cidr columns as an
+    # ipaddress network and macaddr as its lowercase "08:00:2b:01:02:03" form.
+    # On PostgreSQL a flat list whose non-None items are all int, all float,
+    # all str or all bool binds as bigint[], double precision[], text[] or
+    # boolean[] (so "id = ANY($1)" works); rectangular lists of lists bind as
+    # multidimensional arrays. Write to_jsonb($1) to store one as JSON.
+    # dict parameters, and lists that do not bind as PostgreSQL arrays, are
+    # serialized to JSON (JSON text on MySQL and SQLite); nested values, bools
+    # and None round-trip.
+    # prepared also selects the PostgreSQL result format: prepared statements
+    # return binary results (faster to decode, especially numeric-heavy rows),
+    # while prepared=False uses the simple protocol, which only returns text.
+    def fetch_all(
+        self,
+        query: str,
+        params: List[Any],
+        fetch_size: int | None = None,
+        prepared: bool = True,
+        timeout_secs: float | None = None,
+    ) -> List[Dict[str, Any]]: ...
+    # execute/fetch_all with :name placeholders taken from the params dict. A
+    # name used more than once binds a single value; a missing one raises
+    # KeyError. Quoted strings, comments and :: casts are not placeholders.
+    def execute_named(
+        self,
+        query: str,
+        params: Dict[str, Any],
+        prepared: bool = True,
+        timeout_secs: float | None = None,
+    ) -> int: ...
+    def fetch_all_named(
+        self,
+        query: str,
+        params: Dict[str, Any],
+        fetch_size: int | None = None,
+        prepared: bool = True,
+        timeout_secs: float | None = None,
+    ) -> List[Dict[str, Any]]: ...
+    # (name, type_name, nullable) for each column query returns, read from the
+    # prepared statement without running it, so it also works for queries
+    # that return no rows. type_name is lowercase ("int4", "text"); nullable
+    # is None when the driver cannot tell (e.g. computed columns).
+    def describe(self, query: str) -> List[tuple[str, str, bool | None]]: ...
+    # Rows as tuples of values in column order. Unlike the dicts of fetch_all
+    # this keeps every column when several share a name (a join selecting
+    # a.id and b.id).
+    def fetch_all_tuples(
+        self, query: str, params: List[Any], timeout_secs: float | None = None
+    ) -> List[tuple[Any, ...]]: ...
+    # The first row of query; fetch_one raises RuntimeError when there is none
+    # and fetch_optional returns None.
+    def fetch_one(
+        self, query: str, params: List[Any], timeout_secs: float | None = None
+    ) -> Dict[str, Any]: ...
+    def fetch_optional(
+        self, query: str, params: List[Any], timeout_secs: float | None = None
+    ) -> Dict[str, Any] | None: ...
+    # Awaitable execute, fetch_all and fetch_one for asyncio code: the
+    # statement runs without blocking the event loop, which must be running in
+    # the calling thread. Parameters are bound when the method is called;
+    # statements of one transaction still run one at a time.
+    def execute_async(self, query: str, params: List[Any]) -> Awaitable[int]: ...
+    def fetch_all_async(
+        self, query: str, params: List[Any]
+    ) -> Awaitable[List[Dict[str, Any]]]: ...
+    def fetch_one_async(
+        self, query: str, params: List[Any]
+    ) -> Awaitable[Dict[str, Any]]: ...
+    # Rows of an INSERT/UPDATE/DELETE ... RETURNING statement (one dict per
+    # row written). Raises NotImplementedError on MySQL, which has no
+    # RETURNING; MariaDB 10.5+ supports it for INSERT and DELETE.
+    def execute_returning(
+        self, query: str, params: List[Any], timeout_secs: float | None = None
+    ) -> List[Dict[str, Any]]: ...
+    # msgpack array of row maps: bytes -> bin, datetime/date/time -> ISO 8601 str,
+    # complex -> [re, im], other values -> str().
+    def fetch_all_msgpack(
+        self, query: str, params: List[Any], prepared: bool = True
+    ) -> bytes: ...
+    # (rows, total) for one page of query, which keeps its ORDER BY but must
+    # not have its own LIMIT/OFFSET.
+    def fetch_page(
+        self,
+        query: str,
+        params: List[Any],
+        limit: int,
+        offset: int = 0,
+        prepared: bool = True,
+    ) -> tuple[List[Dict[str, Any]], int]: ...
+    # Hex SHA-256 over all rows in result order (use ORDER BY), or of each row.
+    # Values are hashed in column order, names excluded, in a canonical form:
+    # equal Decimals (1.5, 1.50), -0.0/0.0 and all NaNs hash alike, so the same
+    # data read from different databases gives the same checksum.
+    def checksum(self, query: str, params: List[Any]) -> str: ...
+    def row_hashes(self, query: str, params: List[Any]) -> List[str]: ...
+    # Iterator over the rows in lists of up to chunk_size dicts, fetched as it
+    # advances so only one chunk is in memory. The transaction is busy until
+    # the iterator is exhausted or closed: any other call on it until then
+    # raises RuntimeError.
     def stream_data(
         self, query: str, params: List[Any], chunk_size: int
-    ) -> Dict[str, Any]: ...
+    ) -> RowStream: ...
+    # Binary file-like object of the rows as newline-delimited JSON, encoded as
+    # it is read. bytes are written as "\\x..." hex and values JSON cannot
+    # represent as str(). The transaction is busy, as for stream_data, until
+    # the reader is exhausted or closed.
+    def stream_ndjson(self, query: str, params: List[Any]) -> NdjsonReader: ...
+    # Calls callback(row) for each row as it is fetched, never holding more than
+    # one; returns the number of rows passed. An exception from the callback
+    # stops the query and propagates, with the transaction still usable.
+    def for_each(
+        self,
+        query: str,
+        params: List[Any],
+        callback: Callable[[Dict[str, Any]], Any],
+    ) -> int: ...
+    # Streaming reduce: reducer(accumulator, row) is called for each row as it
+    # is fetched, starting from initial; returns the final accumulator.
+    def fold(
+        self,
+        query: str,
+        params: List[Any],
+        initial: Any,
+        reducer: Callable[[Any, Dict[str, Any]], Any],
+    ) -> Any: ...
+    # params may also be a 2-D numpy array, a pandas DataFrame (one set per
+    # row) or a 1-D array/Series (one single-parameter set per value). Their
+    # values are converted to Python ones and NaN/NaT/pd.NA bind as NULL.
+    # On PostgreSQL a None binds with the type of the first non-None value at
+    # the same position in the batch, so NULLs in any row fit typed columns.
+    # A single-row "INSERT INTO ... VALUES (...)" with nothing after the row
+    # runs as one multi-row INSERT per batch_size sets; other statements run
+    # once per set. Returns the total affected row count either way. With
+    # returning=True it returns {"rows_affected": total, "statement_counts":
+    # [count of each statement sent], "rows": [rows of their RETURNING
+    # clauses]} instead, fetching each statement's rows as it runs.
     def bulk_change(
-        self, query: str, params: List[List[Any]], batch_size: int
-    ) -> int | None: ...
+        self,
+        query: str,
+        params: Any,
+        batch_size: int,
+        timeout_secs: float | None = None,
+        returning: bool = False,
+    ) -> int | Dict[str, Any]: ...
+    # returning works on PostgreSQL, SQLite and MariaDB 10.5+, not on MySQL.
+    def insert(
+        self, table: str, data: Dict[str, Any], returning: bool = False
+    ) -> int | Dict[str, Any] | None: ...
+    def update(
+        self,
+        table: str,
+        values: Dict[str, Any],
+        where_: Dict[str, Any],
+        returning: bool = False,
+    ) -> int | List[Dict[str, Any]]: ...
+    def bulk_upsert(
+        self,
+        table: str,
+        columns: List[str],
+        conflict_columns: List[str],
+        update_columns: List[str],
+        rows: List[List[Any]],
+    ) -> int: ...
+    def copy_from_csv(
+        self,
+        table: str,
+        file_obj: Any,
+        columns: List[str] | None = None,
+        options: Dict[str, Any] | None = None,
+        chunk_size: int = 65536,
+    ) -> int: ...
+    def set_constraints(
+        self, deferred: bool, constraints: List[str] | None = None
+    ) -> None: ...
+    # {"statements", "rows_affected", "rows_returned", "elapsed" (seconds)}
+    # accumulated over the life of this object, across commits.
+    def stats(self) -> Dict[str, Any]: ...
+    # (name, collation) per result column of query, without running it; PostgreSQL
+    # only. Table columns report their declared collation and expressions their
+    # type's (a COLLATE clause on an expression is not visible); "default" is
+    # resolved to the database collation and non-text columns report None.
+    def column_collations(self, query: str) -> List[tuple[str, str | None]]: ...
+    # Every statement, commit and rollback on a transaction object uses the
+    # same pooled connection (id shown here; None on SQLite), so temp tables,
+    # session settings and prepared statements persist across commit().
+    def connection_id(self) -> int | None: ...
+    # Auto-increment id of the last row inserted on this transaction's
+    # connection (the first one of a multi-row INSERT on MySQL), or None before
+    # any insert. Raises NotImplementedError on PostgreSQL; use RETURNING.
+    def last_insert_id(self) -> int | None: ...
+    # False once the server has closed this transaction's connection.
+    def ping(self) -> bool: ...
+    # Number of prepared statements cached on this transaction's connection.
+    # Prepared queries are cached per connection by query text (least recently
+    # used evicted past statement_cache_capacity), so running the same SQL again
+    # skips the prepare; the cache is dropped with its connection.
+    def cached_statements(self) -> int: ...
+    # Raises when the COMMIT fails (say a constraint deferred with
+    # set_constraints); the work is rolled back and the next transaction starts
+    # on a fresh connection.
     def commit(self) -> None: ...
+    # Always rolls back, including after a failed read, which leaves a
+    # PostgreSQL transaction aborted.
     def rollback(self) -> None: ...
+    # Savepoints nest inside the transaction. rollback_to(name) undoes the work
+    # since the savepoint (which stays open) and release_savepoint(name) keeps
+    # it; both also drop the savepoints set after it, and raise ValueError for
+    # a name that is not open. Names must be plain identifiers. commit() and
+    # rollback() end all of them.
+    def savepoint(self, name: str) -> None: ...
+    def rollback_to(self, name: str) -> None: ...
+    def release_savepoint(self, name: str) -> None: ...
+    savepoint_depth: int
+    # `with db.transaction() as tx:` (or `async with`) commits when the block
+    # completes and rolls back when it raises; the exception still propagates,
+    # and a failed commit raises. Either way the transaction ends and its
+    # connection returns to the pool; entering the block again begins a new one.
+    def __enter__(self) -> "DatabaseTransaction": ...
+    def __exit__(self, *args: Any) -> bool: ...
+    def __aenter__(self) -> Awaitable["DatabaseTransaction"]: ...
+    def __aexit__(self, *args: Any) -> Awaitable[bool]: ...
+
+
+@dataclass
+class DatabaseConnection:
+    def __init__(self, config: DatabaseConfig) -> None: ...
+    # isolation (an IsolationLevel or its name) applies to this transaction and
+    # to the ones commit() and rollback() start next. SQLite transactions are
+    # always serializable, which also satisfies the weaker levels.
+    def transaction(
+        self, isolation: IsolationLevel | str | None = None
+    ) -> DatabaseTransaction: ...
+    # route: None classifies the statement; "primary" or "replica" overrides it.
+    def execute(
+        self,
+        query: str,
+        params: List[Any],
+        prepared: bool = True,
+        route: str | None = None,
+    ) -> int: ...
+    def fetch_all(
+        self,
+        query: str,
+        params: List[Any],
+        fetch_size: int | None = None,
+        prepared: bool = True,
+        route: str | None = None,
+    ) -> List[Dict[str, Any]]: ...
+    # Closes every pool immediately and kills sessions that are still busy; returns how many.
+    # Runs (query, params) statements in one transaction on the primary and
+    # commits only if all succeed; returns rows affected per statement.
+    # isolation: an IsolationLevel or "read uncommitted", "read committed",
+    # "repeatable read" or "serializable". SQLite transactions are always serializable, and
+    # read_only uses its query_only pragma there.
+    def execute_batch(
+        self,
+        statements: List[tuple[str, List[Any]]],
+        isolation: IsolationLevel | str | None = None,
+        read_only: bool = False,
+    ) -> List[int]: ...
+    # Readiness check: True when the primary and every replica hand out a live
+    # connection; False (never an exception) otherwise, including when no
+    # connection frees up within acquire_timeout_secs.
+    def ping(self) -> bool: ...
+    def abort_all(self) -> int: ...
+    # PostgreSQL only; raises NotImplementedError on other drivers.
+    def listener(self, channels: List[str] = []) -> Listener: ...
 
 
 def get_session_database(context_id: str) -> DatabaseTransaction: ...
+def order_by(
+    column: str,
+    allowed: List[str],
+    direction: str = "asc",
+    driver: DatabaseType = DatabaseType.Postgres,
+) -> str: ...
+# Quoted, comma-separated SELECT list; every column must be in allowed.
+def select_list(
+    columns: List[str],
+    allowed: List[str],
+    driver: DatabaseType = DatabaseType.Postgres,
+) -> str: ...
+# Returns (match condition, ts_rank expression, params) for a full-text search
+# over the columns; placeholders start at $parameter. mode is "plain",
+# "phrase", "websearch" or "raw" (to_tsquery syntax).
+def text_search(
+    columns: List[str],
+    query: str,
+    config: str = "english",
+    mode: str = "plain",
+    parameter: int = 1,
+) -> tuple[str, str, List[str]]: ...
+# Date part of a datetime (an aware one keeps the date of its own time zone);
+# dates and None pass through. Use it for datetime values compared with a date
+# column, which PostgreSQL would otherwise compare as timestamps.
+def as_date(value: Any) -> Any: ...
+# (query, params) with :name placeholders rewritten to positional ones, for
+# the methods that take a parameter list.
+def named_parameters(
+    query: str,
+    params: Dict[str, Any],
+    driver: DatabaseType = DatabaseType.Postgres,
+) -> tuple[str, List[Any]]: ...
+# translator(sqlstate, message) returns the exception to raise for a database
+# error, or None to raise the default RuntimeError. Pass None to unregister.
+# Either way the raised exception carries the code as its sqlstate attribute
+# (None for errors that did not come from the server).
+def set_error_translator(
+    translator: Callable[[str | None, str], BaseException | None] | None = None,
+) -> None: ...
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use pyo3::prelude::*;
+use sqlx::{Database, Executor, IntoArguments};
+use tokio::sync::Mutex;
+
+use super::{
+    db_trait::{active, DynamicParameterBinder},
+    errors::{database_error, fetch_one_error},
+};
+
+// The futures behind the awaitable transaction methods. Parameters are bound before the
+// future is created, which owns the SQL and arguments; it then runs on the async-std executor
+// without the GIL, taking it only to convert the rows, so the asyncio event loop keeps running
+// meanwhile.
+
+pub async fn execute<DB, A>(
+    transaction: Arc<Mutex<Option<sqlx::Transaction<'static, DB>>>>,
+    sql: String,
+    arguments: A,
+) -> PyResult<DB::QueryResult>
+where
+    DB: Database,
+    A: for<'q> IntoArguments<'q, DB> + Send + 'static,
+    for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+{
+    let mut guard = transaction.lock().await;
+    sqlx::query_with(&sql, arguments)
+        .execute(&mut **active(&mut guard)?)
+        .await
+        .map_err(database_error)
+}
+
+pub async fn fetch_all<DB, B>(
+    transaction: Arc<Mutex<Option<sqlx::Transaction<'static, DB>>>>,
+    binder: B,
+    sql: String,
+    arguments: B::Arguments,
+) -> PyResult<Vec<PyObject>>
+where
+    DB: Database,
+    B: DynamicParameterBinder<Database = DB, Row = DB::Row>,
+    B::Arguments: for<'q> IntoArguments<'q, DB> + Send + 'static,
+    for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+{
+    let rows = {
+        let mut guard = transaction.lock().await;
+        sqlx::query_with(&sql, arguments)
+            .fetch_all(&mut **active(&mut guard)?)
+            .await
+            .map_err(database_error)?
+    };
+    Python::with_gil(|py| rows.iter().map(|row| binder.bind_result(py, row)).collect())
+}
+
+pub async fn fetch_one<DB, B>(
+    transaction: Arc<Mutex<Option<sqlx::Transaction<'static, DB>>>>,
+    binder: B,
+    sql: String,
+    arguments: B::Arguments,
+) -> PyResult<PyObject>
+where
+    DB: Database,
+    B: DynamicParameterBinder<Database = DB, Row = DB::Row>,
+    B::Arguments: for<'q> IntoArguments<'q, DB> + Send + 'static,
+    for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+{
+    let row = {
+        let mut guard = transaction.lock().await;
+        sqlx::query_with(&sql, arguments)
+            .fetch_one(&mut **active(&mut guard)?)
+            .await
+            .map_err(fetch_one_error)?
+    };
+    Python::with_gil(|py| binder.bind_result(py, &row))
+}
@@ -0,0 +1,130 @@
+use pyo3::{
+    prelude::*,
+    types::{
+        PyBool, PyByteArray, PyBytes, PyDate, PyDateTime, PyDelta, PyDeltaAccess, PyDict, PyFloat,
+        PyInt, PyList, PyString, PyTime, PyTuple,
+    },
+};
+
+// Canonical byte form of a decoded row, used for checksums. Only the values are encoded, in
+// column order (not the column names), each as a type tag followed by its payload; strings,
+// bytes and sequences are length-prefixed, so no two rows share an encoding. Values are
+// normalised where databases differ in representation only: Decimals drop trailing zeros,
+// -0.0 equals 0.0 and every NaN is the same value.
+pub fn encode_row(row: &PyAny) -> PyResult<Vec<u8>> {
+    let values = row.downcast::<PyDict>()?.values();
+    let mut out = Vec::new();
+    out.push(b'r');
+    put_len(&mut out, values.len());
+    for value in values {
+        encode_value(&mut out, value)?;
+    }
+    Ok(out)
+}
+
+pub fn to_hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn put_len(out: &mut Vec<u8>, len: usize) {
+    out.extend_from_slice(&(len as u64).to_be_bytes());
+}
+
+fn put_tagged(out: &mut Vec<u8>, tag: u8, payload: &[u8]) {
+    out.push(tag);
+    put_len(out, payload.len());
+    out.extend_from_slice(payload);
+}
+
+fn put_text(out: &mut Vec<u8>, tag: u8, value: &PyAny) -> PyResult<()> {
+    put_tagged(out, tag, value.str()?.to_str()?.as_bytes());
+    Ok(())
+}
+
+fn is_a(value: &PyAny, module: &str, name: &str) -> PyResult<bool> {
+    value.is_instance(value.py().import(module)?.getattr(name)?)
+}
+
+fn encode_value(out: &mut Vec<u8>, value: &PyAny) -> PyResult<()> {
+    match value {
+        v if v.is_none() => out.push(b'n'),
+        // bool before int: `True` is also an `int`
+        v if v.is_instance_of::<PyBool>() => {
+            out.push(b'b');
+            out.push(v.is_true()? as u8);
+        }
+        v if v.is_instance_of::<PyInt>() => put_text(out, b'i', v)?,
+        v if v.is_instance_of::<PyFloat>() => {
+            let f = v.downcast::<PyFloat>()?.value();
+            let f = if f.is_nan() { f64::NAN } else { f + 0.0 };
+            out.push(b'f');
+            out.extend_from_slice(&f.to_bits().to_be_bytes());
+        }
+        v if v.is_instance_of::<PyString>() => put_text(out, b's', v)?,
+        v if v.is_instance_of::<PyBytes>() => {
+            put_tagged(out, b'x', v.downcast::<PyBytes>()?.as_bytes())
+        }
+        v if v.is_instance_of::<PyByteArray>() => {
+            put_tagged(out, b'x', &v.downcast::<PyByteArray>()?.to_vec())
+        }
+        // datetime before date: a `datetime` is also a `date`
+        v if v.is_instance_of::<PyDateTime>() => put_tagged(
+            out,
+            b'T',
+            v.call_method0("isoformat")?.extract::<&str>()?.as_bytes(),
+        ),
+        v if v.is_instance_of::<PyDate>() => put_tagged(
+            out,
+            b'D',
+            v.call_method0("isoformat")?.extract::<&str>()?.as_bytes(),
+        ),
+        v if v.is_instance_of::<PyTime>() => put_tagged(
+            out,
+            b't',
+            v.call_method0("isoformat")?.extract::<&str>()?.as_bytes(),
+        ),
+        v if v.is_instance_of::<PyDelta>() => {
+            let delta = v.downcast::<PyDelta>()?;
+            let micros = (delta.get_days() as i128 * 86_400 + delta.get_seconds() as i128)
+                * 1_000_000
+                + delta.get_microseconds() as i128;
+            out.push(b'd');
+            out.extend_from_slice(&micros.to_be_bytes());
+        }
+        v if v.is_instance_of::<PyList>() || v.is_instance_of::<PyTuple>() => {
+            let items: Vec<&PyAny> = v.extract()?;
+            out.push(b'l');
+            put_len(out, items.len());
+            for item in items {
+                encode_value(out, item)?;
+            }
+        }
+        v if v.is_instance_of::<PyDict>() => {
+            let mut entries = v
+                .downcast::<PyDict>()?
+                .iter()
+                .map(|(key, value)| Ok((key.str()?.to_str()?.to_string(), value)))
+                .collect::<PyResult<Vec<_>>>()?;
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            out.push(b'm');
+            put_len(out, entries.len());
+            for (key, value) in entries {
+                put_tagged(out, b's', key.as_bytes());
+                encode_value(out, value)?;
+            }
+        }
+        v if is_a(v, "decimal", "Decimal")? => {
+            // `normalize` would write 100 as 1E+2; a fixed-point form without trailing zeros
+            // keeps 1.50 and 1.5 (and 100 and 100.0) equal
+            let text: String = v.call_method1("__format__", ("f",))?.extract()?;
+            let text = match text.contains('.') {
+                true => text.trim_end_matches('0').trim_end_matches('.'),
+                false => text.as_str(),
+            };
+            put_tagged(out, b'N', text.as_bytes())
+        }
+        v if is_a(v, "uuid", "UUID")? => put_text(out, b'u', v)?,
+        v => put_text(out, b'o', v)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use pyo3::{prelude::*, types::PyDict};
+
+// A dict parameter meant as a PostgreSQL composite value rather than JSON: the keys name the
+// type's attributes. Composite columns are returned as plain dicts, so wrap one in this to
+// bind it back.
+#[pyclass(module = "sqlrustler")]
+#[derive(Debug, Clone)]
+pub struct Composite {
+    #[pyo3(get)]
+    pub type_name: String,
+    #[pyo3(get)]
+    pub fields: Py<PyDict>,
+}
+
+#[pymethods]
+impl Composite {
+    #[new]
+    fn new(type_name: String, fields: Py<PyDict>) -> Self {
+        Composite { type_name, fields }
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "Composite({:?}, {})",
+            self.type_name,
+            self.fields.as_ref(py).repr()?
+        ))
+    }
+}
@@ -1,26 +1,132 @@
-use pyo3::prelude::*;
+use pyo3::{prelude::*, types::PyDict};
 use sqlx::{
-    mysql::{MySqlConnectOptions, MySqlPoolOptions},
-    postgres::{PgConnectOptions, PgPoolOptions},
+    mysql::{MySqlConnectOptions, MySqlPoolOptions, MySqlSslMode},
+    postgres::{PgConnectOptions, PgPoolOptions, PgSslMode},
     sqlite::{SqliteConnectOptions, SqlitePoolOptions},
-    ConnectOptions, Pool,
+    ConnectOptions, Executor, Pool,
 };
+
+use super::on_connect;
+use super::query_builder::quote_literal;
+use super::sessions::{Session, SessionRegistry};
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::log::LevelFilter;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 #[pyclass]
 pub enum DatabaseType {
+    #[default]
     Postgres,
     Mysql,
     Sqlite,
 }
 
-impl Default for DatabaseType {
-    fn default() -> Self {
-        DatabaseType::Postgres
+// How a Python `range` parameter is bound on PostgreSQL
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RangeBinding {
+    // `int4range` / `int8range` covering `[start, stop)`
+    #[default]
+    Range,
+    // `bigint[]` of the range's values
+    Array,
+}
+
+// How a Python `date` parameter is bound on PostgreSQL
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DateBinding {
+    // `date`
+    #[default]
+    Date,
+    // `timestamp` at midnight, for drivers that never distinguished the two
+    Timestamp,
+}
+
+// TLS requirement for PostgreSQL and MySQL connections, named as libpq's sslmode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SslMode {
+    Disable,
+    Prefer,
+    Require,
+    // Require a certificate signed by the root certificate
+    VerifyCa,
+    // VerifyCa, and the certificate must name the host (MySQL's VERIFY_IDENTITY)
+    VerifyFull,
+}
+
+impl SslMode {
+    // libpq's names, MySQL's ssl-mode names and either spelling of the separator
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().replace('_', "-").as_str() {
+            "disable" | "disabled" => Some(SslMode::Disable),
+            "prefer" | "preferred" => Some(SslMode::Prefer),
+            "require" | "required" => Some(SslMode::Require),
+            "verify-ca" => Some(SslMode::VerifyCa),
+            "verify-full" | "verify-identity" => Some(SslMode::VerifyFull),
+            _ => None,
+        }
     }
+
+    fn postgres(self) -> PgSslMode {
+        match self {
+            SslMode::Disable => PgSslMode::Disable,
+            SslMode::Prefer => PgSslMode::Prefer,
+            SslMode::Require => PgSslMode::Require,
+            SslMode::VerifyCa => PgSslMode::VerifyCa,
+            SslMode::VerifyFull => PgSslMode::VerifyFull,
+        }
+    }
+
+    fn mysql(self) -> MySqlSslMode {
+        match self {
+            SslMode::Disable => MySqlSslMode::Disabled,
+            SslMode::Prefer => MySqlSslMode::Preferred,
+            SslMode::Require => MySqlSslMode::Required,
+            SslMode::VerifyCa => MySqlSslMode::VerifyCa,
+            SslMode::VerifyFull => MySqlSslMode::VerifyIdentity,
+        }
+    }
+}
+
+// Replacement representation for every non-NULL value of one SQL type, applied to the value
+// the default extraction produced
+#[derive(Debug, Clone)]
+pub enum TypeOverride {
+    // `str()` of the value, e.g. numeric as "1.10"
+    Str,
+    // Whole seconds since the Unix epoch for date / timestamp / timestamptz (naive values as UTC)
+    Epoch,
+    // bool from "t"/"f"/"true"/"false" text (any case, padding ignored), for legacy schemas
+    Bool,
+    // Any callable taking the default value
+    Callable(PyObject),
+}
+
+// Per-connection options consulted when binding parameters and decoding results
+#[derive(Debug, Clone, Default)]
+pub struct TypeSettings {
+    pub range_binding: RangeBinding,
+    pub date_binding: DateBinding,
+    // Keyed by lowercase type name (`numeric`, `timestamptz`, `int4`, user-defined names)
+    pub type_overrides: HashMap<String, TypeOverride>,
+    // Decode SQLite columns declared DATETIME/TIMESTAMP, DATE or TIME as datetime objects
+    pub sqlite_datetimes: bool,
+    // Raise for PostgreSQL columns of a type with no decoding instead of returning raw bytes
+    pub strict_types: bool,
+    // Bind str parameters that parse as a UUID as PostgreSQL uuid rather than text
+    pub uuid_strings: bool,
+}
+
+// When statements a connection runs in transactions of its own are retried: errors whose
+// SQLSTATE is in `codes` roll back and run again up to `max_retries` times, waiting `backoff`
+// before the first retry and twice as long before each one after
+#[derive(Debug, Clone, Default)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub codes: Vec<String>,
+    pub backoff: Duration,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -30,19 +136,96 @@ pub struct DatabaseConfig {
     pub url: String,
 
     // Connection pool settings
+    #[pyo3(get)]
     pub max_connections: u32,
 
+    #[pyo3(get)]
     pub min_connections: u32,
 
+    // Seconds an unused pooled connection is kept open
+    #[pyo3(get, set)]
     pub idle_timeout: u64,
 
+    // Seconds an acquire waits for a free connection once `max_connections` are in use
+    #[pyo3(get)]
+    pub acquire_timeout_secs: f64,
+
     // Additional database-specific options
     pub options: Option<HashMap<String, String>>,
+
+    // Wrap statements issued directly on a connection in their own transaction
+    pub autocommit: bool,
+
+    // Read replicas; read-only statements on a connection are spread across them
+    pub replica_urls: Vec<String>,
+
+    // Bind Python `range` as a range type or as an array
+    pub range_binding: RangeBinding,
+
+    // Bind Python `date` as `date` or as a midnight `timestamp`
+    pub date_binding: DateBinding,
+
+    // Per-type replacements for decoded PostgreSQL values
+    pub type_overrides: HashMap<String, TypeOverride>,
+
+    // Session time zone set on every pooled connection (`SET TIME ZONE`). timestamptz values
+    // are always returned as UTC-aware datetimes; this governs `now()`, casts to/from plain
+    // timestamp and the text rendering of timestamptz.
+    pub timezone: Option<String>,
+
+    // Prepared statements kept per pooled connection and reused across transactions (sqlx
+    // default 100). sqlx always prepares named statements, so behind transaction-mode
+    // pgbouncer either enable its prepared statement tracking or use `prepared=False`.
+    pub statement_cache_capacity: Option<usize>,
+
+    // Character encoding every pool must use end to end (client and server side), checked when
+    // the connection is created; `None` skips the check.
+    pub encoding: Option<String>,
+
+    // Register a Rust `regexp(pattern, text)` on SQLite connections so `col REGEXP $1` works
+    pub sqlite_regexp: bool,
+
+    // Return SQLite DATETIME/TIMESTAMP, DATE and TIME columns as datetime, date and time
+    // rather than the text (or number) stored in them
+    pub sqlite_datetimes: bool,
+
+    // Raise TypeError for PostgreSQL columns whose type has no decoding, rather than
+    // returning the raw bytes the server sent
+    pub strict_types: bool,
+
+    // Bind PostgreSQL str parameters that parse as a UUID as uuid rather than text
+    pub uuid_strings: bool,
+
+    // Upper bound on every statement: `SET statement_timeout` on each PostgreSQL connection,
+    // and enforced client-side on MySQL (killing the statement) and SQLite (interrupting it).
+    pub default_statement_timeout_ms: Option<u64>,
+
+    // Check strings given to insert/update/bulk_upsert against the declared length of their
+    // varchar(n)/char(n) column before sending them
+    pub check_string_lengths: bool,
+
+    // Python callable run with a `NewConnection` for every connection a pool opens, after the
+    // settings above are applied
+    pub on_connect: Option<PyObject>,
+
+    // TLS settings, overriding the URL's sslmode / sslrootcert / sslcert / sslkey (ssl-mode /
+    // ssl-ca / ssl-cert / ssl-key on MySQL). SQLite has none.
+    pub ssl_mode: Option<SslMode>,
+    pub ssl_root_cert: Option<String>,
+    pub ssl_client_cert: Option<String>,
+    pub ssl_client_key: Option<String>,
+
+    // Retries of transient errors (serialization failures, deadlocks); see `RetryPolicy`
+    pub max_retries: u32,
+    pub retry_on: Vec<String>,
+    pub retry_backoff_ms: u64,
 }
 
 #[pymethods]
 impl DatabaseConfig {
     #[new]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (driver, url, max_connections=10, min_connections=1, idle_timeout=30, options=None, autocommit=false, replica_urls=None, range_binding="range", timezone=None, statement_cache_capacity=None, date_binding="date", encoding=None, type_overrides=None, sqlite_regexp=false, default_statement_timeout_ms=None, check_string_lengths=false, on_connect=None, acquire_timeout_secs=30.0, ssl_mode=None, ssl_root_cert=None, ssl_client_cert=None, ssl_client_key=None, max_retries=0, retry_on=None, retry_backoff_ms=50, sqlite_datetimes=true, strict_types=false, uuid_strings=false))]
     fn new(
         driver: DatabaseType,
         url: &str,
@@ -50,70 +233,484 @@ impl DatabaseConfig {
         min_connections: u32,
         idle_timeout: u64,
         options: Option<HashMap<String, String>>,
-    ) -> Self {
-        DatabaseConfig {
+        autocommit: bool,
+        replica_urls: Option<Vec<String>>,
+        range_binding: &str,
+        timezone: Option<String>,
+        statement_cache_capacity: Option<usize>,
+        date_binding: &str,
+        encoding: Option<String>,
+        type_overrides: Option<&PyDict>,
+        sqlite_regexp: bool,
+        default_statement_timeout_ms: Option<u64>,
+        check_string_lengths: bool,
+        on_connect: Option<PyObject>,
+        acquire_timeout_secs: f64,
+        ssl_mode: Option<&str>,
+        ssl_root_cert: Option<String>,
+        ssl_client_cert: Option<String>,
+        ssl_client_key: Option<String>,
+        max_retries: u32,
+        retry_on: Option<Vec<String>>,
+        retry_backoff_ms: u64,
+        sqlite_datetimes: bool,
+        strict_types: bool,
+        uuid_strings: bool,
+    ) -> PyResult<Self> {
+        check_pool_size(max_connections, min_connections)?;
+        check_acquire_timeout(acquire_timeout_secs)?;
+        if statement_cache_capacity == Some(0) {
+            // With no cache sqlx still prepares named statements, they just never get reused
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "statement_cache_capacity must be at least 1",
+            ));
+        }
+        if let (DatabaseType::Sqlite, Some(_)) = (&driver, &timezone) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "SQLite has no session time zone",
+            ));
+        }
+        if default_statement_timeout_ms == Some(0) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "default_statement_timeout_ms must be at least 1; use None for no timeout",
+            ));
+        }
+        if sqlite_regexp && !matches!(driver, DatabaseType::Sqlite) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "sqlite_regexp only applies to SQLite; PostgreSQL and MySQL have built-in regular expressions",
+            ));
+        }
+        if let Some(on_connect) = &on_connect {
+            if !Python::with_gil(|py| on_connect.as_ref(py).is_callable()) {
+                return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                    "on_connect must be callable",
+                ));
+            }
+        }
+        let range_binding = match range_binding {
+            "range" => RangeBinding::Range,
+            "array" => RangeBinding::Array,
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Invalid range_binding {:?}; expected 'range' or 'array'",
+                    other
+                )))
+            }
+        };
+        let date_binding = match date_binding {
+            "date" => DateBinding::Date,
+            "timestamp" => DateBinding::Timestamp,
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Invalid date_binding {:?}; expected 'date' or 'timestamp'",
+                    other
+                )))
+            }
+        };
+        let type_overrides = match type_overrides {
+            Some(overrides) => parse_type_overrides(overrides)?,
+            None => HashMap::new(),
+        };
+        let ssl_mode = ssl_mode
+            .map(|name| {
+                SslMode::parse(name).ok_or_else(|| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Invalid ssl_mode {:?}; expected 'disable', 'prefer', 'require', 'verify-ca' or 'verify-full'",
+                        name
+                    ))
+                })
+            })
+            .transpose()?;
+        check_ssl(
+            &driver,
+            url,
+            ssl_mode,
+            &ssl_root_cert,
+            &ssl_client_cert,
+            &ssl_client_key,
+        )?;
+        Ok(DatabaseConfig {
             driver,
             url: url.to_string(),
             max_connections,
             min_connections,
             idle_timeout,
             options,
+            autocommit,
+            replica_urls: replica_urls.unwrap_or_default(),
+            range_binding,
+            date_binding,
+            type_overrides,
+            timezone,
+            statement_cache_capacity,
+            encoding,
+            sqlite_regexp,
+            default_statement_timeout_ms,
+            check_string_lengths,
+            on_connect,
+            acquire_timeout_secs,
+            ssl_mode,
+            ssl_root_cert,
+            ssl_client_cert,
+            ssl_client_key,
+            max_retries,
+            retry_on: retry_on.unwrap_or_else(default_retry_codes),
+            retry_backoff_ms,
+            sqlite_datetimes,
+            strict_types,
+            uuid_strings,
+        })
+    }
+
+    #[setter]
+    fn set_max_connections(&mut self, max_connections: u32) -> PyResult<()> {
+        check_pool_size(max_connections, self.min_connections)?;
+        self.max_connections = max_connections;
+        Ok(())
+    }
+
+    #[setter]
+    fn set_min_connections(&mut self, min_connections: u32) -> PyResult<()> {
+        check_pool_size(self.max_connections, min_connections)?;
+        self.min_connections = min_connections;
+        Ok(())
+    }
+
+    #[setter]
+    fn set_acquire_timeout_secs(&mut self, acquire_timeout_secs: f64) -> PyResult<()> {
+        check_acquire_timeout(acquire_timeout_secs)?;
+        self.acquire_timeout_secs = acquire_timeout_secs;
+        Ok(())
+    }
+}
+
+// serialization_failure (also MySQL's deadlock) and PostgreSQL's deadlock_detected
+fn default_retry_codes() -> Vec<String> {
+    vec!["40001".to_string(), "40P01".to_string()]
+}
+
+// A pool without connections could never hand one out, and one kept above its maximum would
+// hold connections it may never use
+fn check_pool_size(max_connections: u32, min_connections: u32) -> PyResult<()> {
+    if max_connections == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "max_connections must be at least 1",
+        ));
+    }
+    if min_connections > max_connections {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "min_connections ({}) must not exceed max_connections ({})",
+            min_connections, max_connections
+        )));
+    }
+    Ok(())
+}
+
+// Value of the first of `names` among the URL's query parameters
+fn url_parameter<'u>(url: &'u str, names: &[&str]) -> Option<&'u str> {
+    let (_, query) = url.split_once('?')?;
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| names.contains(key))
+        .map(|(_, value)| value)
+}
+
+// TLS settings that cannot work: any on SQLite, half a client key pair, missing files, and
+// certificate verification without a root certificate to verify against (sqlx would fall back
+// to its bundled public roots, which a private CA is not among)
+fn check_ssl(
+    driver: &DatabaseType,
+    url: &str,
+    ssl_mode: Option<SslMode>,
+    root_cert: &Option<String>,
+    client_cert: &Option<String>,
+    client_key: &Option<String>,
+) -> PyResult<()> {
+    let paths = [
+        ("ssl_root_cert", root_cert),
+        ("ssl_client_cert", client_cert),
+        ("ssl_client_key", client_key),
+    ];
+    if matches!(driver, DatabaseType::Sqlite) {
+        if ssl_mode.is_some() || paths.iter().any(|(_, path)| path.is_some()) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "SSL options only apply to PostgreSQL and MySQL",
+            ));
         }
+        return Ok(());
     }
+    if client_cert.is_some() != client_key.is_some() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "ssl_client_cert and ssl_client_key must be given together",
+        ));
+    }
+    for (name, path) in paths {
+        if let Some(path) = path {
+            if !std::path::Path::new(path).is_file() {
+                return Err(PyErr::new::<pyo3::exceptions::PyFileNotFoundError, _>(
+                    format!("{} {:?} is not a file", name, path),
+                ));
+            }
+        }
+    }
+
+    let mode =
+        ssl_mode.or_else(|| url_parameter(url, &["sslmode", "ssl-mode"]).and_then(SslMode::parse));
+    let has_root = root_cert.is_some()
+        || url_parameter(url, &["sslrootcert", "ssl-root-cert", "ssl-ca", "sslca"]).is_some();
+    if let (Some(SslMode::VerifyCa | SslMode::VerifyFull), false) = (mode, has_root) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "ssl_mode {:?} needs a root certificate: pass ssl_root_cert or set it in the URL",
+            match mode {
+                Some(SslMode::VerifyCa) => "verify-ca",
+                _ => "verify-full",
+            }
+        )));
+    }
+    Ok(())
+}
+
+fn check_acquire_timeout(acquire_timeout_secs: f64) -> PyResult<()> {
+    if !(acquire_timeout_secs.is_finite() && acquire_timeout_secs > 0.0) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "acquire_timeout_secs must be a positive number of seconds, not {}",
+            acquire_timeout_secs
+        )));
+    }
+    Ok(())
+}
+
+// `{"numeric": "str", "timestamptz": "epoch", "money": callable}`
+fn parse_type_overrides(overrides: &PyDict) -> PyResult<HashMap<String, TypeOverride>> {
+    let mut parsed = HashMap::with_capacity(overrides.len());
+    for (type_name, value) in overrides.iter() {
+        let type_name = type_name.extract::<String>()?.to_ascii_lowercase();
+        let type_override = match value.extract::<&str>() {
+            Ok("str") => TypeOverride::Str,
+            Ok("epoch") if matches!(type_name.as_str(), "date" | "timestamp" | "timestamptz") => {
+                TypeOverride::Epoch
+            }
+            Ok("bool") if matches!(type_name.as_str(), "text" | "varchar" | "char" | "name") => {
+                TypeOverride::Bool
+            }
+            Ok("bool") => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "'bool' only applies to text, varchar, char and name, not {:?}",
+                    type_name
+                )))
+            }
+            Ok("epoch") => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "'epoch' only applies to date, timestamp and timestamptz, not {:?}",
+                    type_name
+                )))
+            }
+            Ok(other) => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Invalid override {:?} for {:?}; expected 'str', 'epoch', 'bool' or a callable",
+                    other, type_name
+                )))
+            }
+            Err(_) if value.is_callable() => TypeOverride::Callable(value.into()),
+            Err(_) => {
+                return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                    "Override for {:?} must be 'str', 'epoch', 'bool' or a callable",
+                    type_name
+                )))
+            }
+        };
+        parsed.insert(type_name, type_override);
+    }
+    Ok(parsed)
 }
 
 impl DatabaseConfig {
     // Create PostgreSQL connection pool
-    pub async fn create_postgres_pool(&self) -> Result<Pool<sqlx::Postgres>, sqlx::Error> {
+    pub async fn create_postgres_pool(
+        &self,
+        sessions: &SessionRegistry,
+    ) -> Result<Pool<sqlx::Postgres>, sqlx::Error> {
         // Parse connection options
 
         let mut connect_options = self.url.parse::<PgConnectOptions>()?;
         connect_options = connect_options.log_statements(LevelFilter::Debug);
+        if let Some(mode) = self.ssl_mode {
+            connect_options = connect_options.ssl_mode(mode.postgres());
+        }
+        if let Some(path) = &self.ssl_root_cert {
+            connect_options = connect_options.ssl_root_cert(path);
+        }
+        if let (Some(cert), Some(key)) = (&self.ssl_client_cert, &self.ssl_client_key) {
+            connect_options = connect_options.ssl_client_cert(cert).ssl_client_key(key);
+        }
+        if let Some(capacity) = self.statement_cache_capacity {
+            connect_options = connect_options.statement_cache_capacity(capacity);
+        }
 
         // Create pool with configured options
-        PgPoolOptions::new()
+        let mut pool_options = PgPoolOptions::new()
             .max_connections(self.max_connections)
             .min_connections(self.min_connections)
             .idle_timeout(Some(Duration::from_secs(self.idle_timeout)))
-            .acquire_timeout(Duration::from_secs(self.idle_timeout))
-            .connect_with(connect_options)
-            .await
+            .acquire_timeout(self.acquire_timeout());
+        let timezone = self
+            .timezone
+            .as_ref()
+            .map(|timezone| format!("SET TIME ZONE {}", quote_literal(timezone)));
+        let statement_timeout = self
+            .default_statement_timeout_ms
+            .map(|ms| format!("SET statement_timeout = {}", ms));
+        let sessions = sessions.clone();
+        let limit = self.max_connections as usize * 2;
+        let on_connect = self.on_connect.clone();
+        let settings = Arc::new(self.type_settings());
+        pool_options = pool_options.after_connect(move |conn, _meta| {
+            let timezone = timezone.clone();
+            let statement_timeout = statement_timeout.clone();
+            let sessions = sessions.clone();
+            let on_connect = on_connect.clone();
+            let settings = settings.clone();
+            Box::pin(async move {
+                for statement in timezone.iter().chain(&statement_timeout) {
+                    conn.execute(statement.as_str()).await?;
+                }
+                let (pid, started): (i32, DateTime<Utc>) = sqlx::query_as(
+                    "SELECT pid, backend_start FROM pg_stat_activity WHERE pid = pg_backend_pid()",
+                )
+                .fetch_one(&mut *conn)
+                .await?;
+                let session = Session {
+                    id: pid as i64,
+                    started: Some(started),
+                };
+                if sessions.register(session, limit) {
+                    let pids: Vec<i32> = sessions.snapshot().iter().map(|s| s.id as i32).collect();
+                    let live: Vec<(i32, DateTime<Utc>)> = sqlx::query_as(
+                        "SELECT pid, backend_start FROM pg_stat_activity WHERE pid = ANY($1)",
+                    )
+                    .bind(pids)
+                    .fetch_all(&mut *conn)
+                    .await?;
+                    let live: Vec<Session> = live
+                        .into_iter()
+                        .map(|(pid, started)| Session {
+                            id: pid as i64,
+                            started: Some(started),
+                        })
+                        .collect();
+                    sessions.retain_live(&live);
+                }
+                if let Some(on_connect) = &on_connect {
+                    on_connect::run_postgres(on_connect, &settings, conn)?;
+                }
+                Ok(())
+            })
+        });
+        pool_options.connect_with(connect_options).await
     }
 
     // Create MySQL connection pool
-    pub async fn create_mysql_pool(&self) -> Result<Pool<sqlx::MySql>, sqlx::Error> {
-        let connect_options = self.url.parse::<MySqlConnectOptions>()?;
+    pub async fn create_mysql_pool(
+        &self,
+        sessions: &SessionRegistry,
+    ) -> Result<Pool<sqlx::MySql>, sqlx::Error> {
+        let mut connect_options = self.url.parse::<MySqlConnectOptions>()?;
+        if let Some(mode) = self.ssl_mode {
+            connect_options = connect_options.ssl_mode(mode.mysql());
+        }
+        if let Some(path) = &self.ssl_root_cert {
+            connect_options = connect_options.ssl_ca(path);
+        }
+        if let (Some(cert), Some(key)) = (&self.ssl_client_cert, &self.ssl_client_key) {
+            connect_options = connect_options.ssl_client_cert(cert).ssl_client_key(key);
+        }
+        if let Some(capacity) = self.statement_cache_capacity {
+            connect_options = connect_options.statement_cache_capacity(capacity);
+        }
 
-        MySqlPoolOptions::new()
+        let mut pool_options = MySqlPoolOptions::new()
             .max_connections(self.max_connections)
             .min_connections(self.min_connections)
             .idle_timeout(Some(Duration::from_secs(self.idle_timeout)))
-            .acquire_timeout(Duration::from_secs(self.idle_timeout))
-            .connect_with(connect_options)
-            .await
+            .acquire_timeout(self.acquire_timeout());
+        let timezone = self
+            .timezone
+            .as_ref()
+            .map(|timezone| format!("SET time_zone = {}", quote_literal(timezone)));
+        let sessions = sessions.clone();
+        let limit = self.max_connections as usize * 2;
+        let on_connect = self.on_connect.clone();
+        pool_options = pool_options.after_connect(move |conn, _meta| {
+            let timezone = timezone.clone();
+            let sessions = sessions.clone();
+            let on_connect = on_connect.clone();
+            Box::pin(async move {
+                if let Some(statement) = timezone {
+                    conn.execute(statement.as_str()).await?;
+                }
+                let id: i64 = sqlx::query_scalar("SELECT CAST(CONNECTION_ID() AS SIGNED)")
+                    .fetch_one(&mut *conn)
+                    .await?;
+                let session = Session { id, started: None };
+                if sessions.register(session, limit) {
+                    let live: Vec<i64> = sqlx::query_scalar(
+                        "SELECT CAST(ID AS SIGNED) FROM information_schema.PROCESSLIST",
+                    )
+                    .fetch_all(&mut *conn)
+                    .await?;
+                    let live: Vec<Session> = live
+                        .into_iter()
+                        .map(|id| Session { id, started: None })
+                        .collect();
+                    sessions.retain_live(&live);
+                }
+                if let Some(on_connect) = &on_connect {
+                    on_connect::run_mysql(on_connect, conn)?;
+                }
+                Ok(())
+            })
+        });
+        pool_options.connect_with(connect_options).await
     }
 
     // Create SQLite connection pool
     pub async fn create_sqlite_pool(&self) -> Result<Pool<sqlx::Sqlite>, sqlx::Error> {
-        let connect_options = self.url.parse::<SqliteConnectOptions>()?;
+        let mut connect_options = self.url.parse::<SqliteConnectOptions>()?;
+        if let Some(capacity) = self.statement_cache_capacity {
+            connect_options = connect_options.statement_cache_capacity(capacity);
+        }
+        if self.sqlite_regexp {
+            connect_options = connect_options.with_regexp();
+        }
 
-        SqlitePoolOptions::new()
+        let mut pool_options = SqlitePoolOptions::new()
             .max_connections(self.max_connections)
             .min_connections(self.min_connections)
             .idle_timeout(Some(Duration::from_secs(self.idle_timeout)))
-            .connect_with(connect_options)
-            .await
+            .acquire_timeout(self.acquire_timeout());
+        if let Some(on_connect) = self.on_connect.clone() {
+            let settings = Arc::new(self.type_settings());
+            pool_options = pool_options.after_connect(move |conn, _meta| {
+                let on_connect = on_connect.clone();
+                let settings = settings.clone();
+                Box::pin(async move { on_connect::run_sqlite(&on_connect, &settings, conn) })
+            });
+        }
+        pool_options.connect_with(connect_options).await
     }
 
     // Dynamic pool creation based on database type
     pub async fn create_pool(&self) -> Result<Box<dyn DatabasePoolTrait>, sqlx::Error> {
         match self.driver {
             DatabaseType::Postgres => {
-                let pool = self.create_postgres_pool().await?;
+                let pool = self
+                    .create_postgres_pool(&SessionRegistry::default())
+                    .await?;
                 Ok(Box::new(pool))
             }
             DatabaseType::Mysql => {
-                let pool = self.create_mysql_pool().await?;
+                let pool = self.create_mysql_pool(&SessionRegistry::default()).await?;
                 Ok(Box::new(pool))
             }
             DatabaseType::Sqlite => {
@@ -132,6 +729,65 @@ impl DatabaseConfig {
             min_connections: 1,
             idle_timeout: 600,
             options: None,
+            autocommit: false,
+            replica_urls: Vec::new(),
+            range_binding: RangeBinding::Range,
+            date_binding: DateBinding::Date,
+            type_overrides: HashMap::new(),
+            timezone: None,
+            statement_cache_capacity: None,
+            encoding: None,
+            sqlite_regexp: false,
+            default_statement_timeout_ms: None,
+            check_string_lengths: false,
+            on_connect: None,
+            acquire_timeout_secs: 30.0,
+            ssl_mode: None,
+            ssl_root_cert: None,
+            ssl_client_cert: None,
+            ssl_client_key: None,
+            max_retries: 0,
+            retry_on: default_retry_codes(),
+            retry_backoff_ms: 50,
+            sqlite_datetimes: true,
+            strict_types: false,
+            uuid_strings: false,
+        }
+    }
+
+    pub fn acquire_timeout(&self) -> Duration {
+        Duration::from_secs_f64(self.acquire_timeout_secs)
+    }
+
+    pub fn statement_timeout(&self) -> Option<Duration> {
+        self.default_statement_timeout_ms.map(Duration::from_millis)
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_retries: self.max_retries,
+            codes: self.retry_on.clone(),
+            backoff: Duration::from_millis(self.retry_backoff_ms),
+        }
+    }
+
+    pub fn type_settings(&self) -> TypeSettings {
+        TypeSettings {
+            range_binding: self.range_binding,
+            date_binding: self.date_binding,
+            type_overrides: self.type_overrides.clone(),
+            sqlite_datetimes: self.sqlite_datetimes,
+            strict_types: self.strict_types,
+            uuid_strings: self.uuid_strings,
+        }
+    }
+
+    // Same settings pointed at a replica
+    pub fn for_replica(&self, url: &str) -> Self {
+        DatabaseConfig {
+            url: url.to_string(),
+            replica_urls: Vec::new(),
+            ..self.clone()
         }
     }
 }
@@ -1,101 +1,642 @@
-use std::sync::Arc;
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use super::{
-    config::DatabaseConfig,
-    postgresql::PostgresDatabase,
+    config::{DatabaseConfig, DatabaseType as Driver, RetryPolicy, TypeSettings},
+    context::set_sql_connect,
+    db_trait::DatabaseOperations,
+    errors::{database_error, sqlstate_of},
+    listener::Listener,
     mysql::MySqlDatabase,
+    on_connect,
+    postgresql::PostgresDatabase,
+    routing::Route,
+    sessions::SessionRegistry,
     sqlite::SqliteDatabase,
-    transaction::{DatabaseTransaction, DatabaseTransactionType},
+    transaction::{
+        DatabaseTransaction, DatabaseTransactionType, IsolationLevel, ModeStatements,
+        TransactionMode,
+    },
 };
+use futures::FutureExt;
+use pyo3::prelude::*;
+use sqlx::{mysql::MySqlConnection, postgres::PgConnection, Connection, Executor};
 use sqlx::{Error as SqlxError, Pool};
 use sqlx::{MySql, Postgres, Sqlite};
 use tokio::sync::Mutex;
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 enum DatabaseType {
-    Postgres(Arc<Pool<sqlx::Postgres>>),
-    MySql(Arc<Pool<sqlx::MySql>>),
+    Postgres(Arc<Pool<sqlx::Postgres>>, SessionRegistry),
+    MySql(Arc<Pool<sqlx::MySql>>, SessionRegistry),
     Sqlite(Arc<Pool<sqlx::Sqlite>>),
 }
 
-
-#[derive(Clone)]
+#[pyclass]
+#[derive(Clone, Debug)]
 pub struct DatabaseConnection {
     connection: DatabaseType,
+    replicas: Vec<DatabaseType>,
+    next_replica: Arc<AtomicUsize>,
+    autocommit: bool,
+    settings: Arc<TypeSettings>,
+    // Enforced client-side on SQLite; PostgreSQL and MySQL apply it per session
+    statement_timeout: Option<Duration>,
+    check_string_lengths: bool,
+    retry: Arc<RetryPolicy>,
 }
 
-impl DatabaseConnection {
-    pub async fn new(config: DatabaseConfig) -> Self {
-        let connection = match config.driver {
+impl DatabaseType {
+    async fn connect(config: &DatabaseConfig) -> Result<Self, SqlxError> {
+        Ok(match config.driver {
             super::config::DatabaseType::Postgres => {
-                let pool = config.create_postgres_pool().await.unwrap();
-                Ok::<DatabaseType, SqlxError>(DatabaseType::Postgres(Arc::new(pool)))
+                let sessions = SessionRegistry::default();
+                let pool = config.create_postgres_pool(&sessions).await?;
+                DatabaseType::Postgres(Arc::new(pool), sessions)
             }
             super::config::DatabaseType::Mysql => {
-                let pool = config.create_mysql_pool().await.unwrap();
-                Ok::<DatabaseType, SqlxError>(DatabaseType::MySql(Arc::new(pool)))
+                let sessions = SessionRegistry::default();
+                let pool = config.create_mysql_pool(&sessions).await?;
+                DatabaseType::MySql(Arc::new(pool), sessions)
             }
             super::config::DatabaseType::Sqlite => {
-                let pool = config.create_sqlite_pool().await.unwrap();
-                Ok::<DatabaseType, SqlxError>(DatabaseType::Sqlite(Arc::new(pool)))
+                let pool = config.create_sqlite_pool().await?;
+                DatabaseType::Sqlite(Arc::new(pool))
+            }
+        })
+    }
+
+    // Fail fast when the session or database encoding differs from `expected`; a mismatch
+    // would otherwise silently mangle text on its way in or out.
+    async fn verify_encoding(&self, expected: &str) -> Result<(), SqlxError> {
+        let encodings: Vec<(&str, String)> = match self {
+            DatabaseType::Postgres(pool, _) => {
+                let (client, server): (String, String) = sqlx::query_as(
+                    "SELECT current_setting('client_encoding'), current_setting('server_encoding')",
+                )
+                .fetch_one(pool.as_ref())
+                .await?;
+                vec![("client_encoding", client), ("server_encoding", server)]
+            }
+            DatabaseType::MySql(pool, _) => {
+                let (client, connection, database): (String, String, String) = sqlx::query_as(
+                    "SELECT @@character_set_client, @@character_set_connection, \
+                     @@character_set_database",
+                )
+                .fetch_one(pool.as_ref())
+                .await?;
+                // MySQL's "utf8" is the 3-byte utf8mb3 subset, not full UTF-8
+                let charset = |name: String| match name.as_str() {
+                    "utf8" => "utf8mb3".to_string(),
+                    _ => name,
+                };
+                vec![
+                    ("character_set_client", charset(client)),
+                    ("character_set_connection", charset(connection)),
+                    ("character_set_database", charset(database)),
+                ]
+            }
+            DatabaseType::Sqlite(pool) => {
+                let encoding: String = sqlx::query_scalar("PRAGMA encoding")
+                    .fetch_one(pool.as_ref())
+                    .await?;
+                vec![("encoding", encoding)]
+            }
+        };
+        for (setting, actual) in encodings {
+            if normalize_encoding(&actual) != normalize_encoding(expected) {
+                return Err(SqlxError::Configuration(
+                    format!(
+                        "{} is {:?} but {:?} was expected; fix the database or client \
+                         configuration, or change the expected encoding",
+                        setting, actual, expected
+                    )
+                    .into(),
+                ));
             }
         }
-        .unwrap();
+        Ok(())
+    }
 
-        Self { connection }
+    // Whether a pooled connection can be acquired and answers a ping (COM_PING on MySQL, an
+    // empty round trip on PostgreSQL)
+    async fn ping(&self) -> bool {
+        match self {
+            DatabaseType::Postgres(pool, _) => match pool.acquire().await {
+                Ok(mut conn) => conn.ping().await.is_ok(),
+                Err(_) => false,
+            },
+            DatabaseType::MySql(pool, _) => match pool.acquire().await {
+                Ok(mut conn) => conn.ping().await.is_ok(),
+                Err(_) => false,
+            },
+            DatabaseType::Sqlite(pool) => match pool.acquire().await {
+                Ok(mut conn) => conn.ping().await.is_ok(),
+                Err(_) => false,
+            },
+        }
+    }
+
+    // Close the pool without waiting for busy connections, then kill their server sessions
+    // from a dedicated connection. Returns how many sessions were terminated.
+    async fn abort_all(&self) -> Result<u64, SqlxError> {
+        match self {
+            DatabaseType::Postgres(pool, sessions) => {
+                pool.close().now_or_never();
+                let sessions = sessions.snapshot();
+                let pids: Vec<i32> = sessions.iter().map(|s| s.id as i32).collect();
+                let started: Vec<_> = sessions.iter().map(|s| s.started).collect();
+                let mut conn = PgConnection::connect_with(&pool.connect_options()).await?;
+                // Terminating a backend also cancels the statement it is running.
+                let terminated: Vec<bool> = sqlx::query_scalar(
+                    "SELECT pg_terminate_backend(a.pid) FROM pg_stat_activity a \
+                     JOIN unnest($1::int4[], $2::timestamptz[]) AS s(pid, backend_start) \
+                     USING (pid, backend_start)",
+                )
+                .bind(pids)
+                .bind(started)
+                .fetch_all(&mut conn)
+                .await?;
+                conn.close().await.ok();
+                Ok(terminated
+                    .into_iter()
+                    .filter(|terminated| *terminated)
+                    .count() as u64)
+            }
+            DatabaseType::MySql(pool, sessions) => {
+                pool.close().now_or_never();
+                let mut conn = MySqlConnection::connect_with(&pool.connect_options()).await?;
+                let mut terminated = 0;
+                for session in sessions.snapshot() {
+                    // Sessions that already ended fail with "Unknown thread id"; skip them.
+                    let kill = format!("KILL {}", session.id);
+                    if conn.execute(kill.as_str()).await.is_ok() {
+                        terminated += 1;
+                    }
+                }
+                conn.close().await.ok();
+                Ok(terminated)
+            }
+            DatabaseType::Sqlite(pool) => {
+                pool.close().now_or_never();
+                Ok(0)
+            }
+        }
+    }
+}
+
+impl DatabaseConnection {
+    pub async fn new(config: DatabaseConfig) -> Result<Self, SqlxError> {
+        let connection = DatabaseType::connect(&config).await?;
+        let mut replicas = Vec::with_capacity(config.replica_urls.len());
+        for url in &config.replica_urls {
+            replicas.push(DatabaseType::connect(&config.for_replica(url)).await?);
+        }
+        if let Some(expected) = &config.encoding {
+            for pool in std::iter::once(&connection).chain(&replicas) {
+                pool.verify_encoding(expected).await?;
+            }
+        }
+
+        Ok(Self {
+            connection,
+            replicas,
+            next_replica: Arc::new(AtomicUsize::new(0)),
+            autocommit: config.autocommit,
+            settings: Arc::new(config.type_settings()),
+            statement_timeout: config.statement_timeout(),
+            check_string_lengths: config.check_string_lengths,
+            retry: Arc::new(config.retry_policy()),
+        })
+    }
+
+    // Pool for a statement: reads go round-robin to the replicas when there are any,
+    // everything else (and every transaction) uses the primary.
+    fn route(&self, query: &str, route: Option<&str>) -> PyResult<&DatabaseType> {
+        if self.replicas.is_empty() || Route::resolve(query, route)? == Route::Primary {
+            return Ok(&self.connection);
+        }
+        let index = self.next_replica.fetch_add(1, Ordering::Relaxed) % self.replicas.len();
+        Ok(&self.replicas[index])
+    }
+
+    pub fn check_string_lengths(&self) -> bool {
+        self.check_string_lengths
     }
 
     // get transaction
-    pub async fn transaction(&self) -> DatabaseTransaction {
-        match &self.connection {
-            DatabaseType::Postgres(pool) => {
-                let transaction = pool
-                    .begin()
-                    .await
-                    .map_err(|e| SqlxError::Configuration(e.to_string().into()));
+    pub async fn transaction(
+        &self,
+        mode: TransactionMode,
+    ) -> Result<DatabaseTransaction, SqlxError> {
+        let mode = mode.statements(self.driver());
+        let transaction = match &self.connection {
+            DatabaseType::Postgres(pool, _) => {
+                let transaction = begin_in_mode(pool, &mode).await?;
                 DatabaseTransaction::from_transaction(DatabaseTransactionType::Postgres(
-                    PostgresDatabase,
-                    Arc::new(Mutex::new(Some(transaction.unwrap()))),
+                    PostgresDatabase::new(self.settings.clone()),
+                    Arc::new(Mutex::new(Some(transaction))),
                 ))
             }
-            DatabaseType::MySql(pool) => {
-                let transaction = pool
-                    .begin()
-                    .await
-                    .map_err(|e| SqlxError::Configuration(e.to_string().into()));
+            DatabaseType::MySql(pool, _) => {
+                let transaction = begin_in_mode(pool, &mode).await?;
                 DatabaseTransaction::from_transaction(DatabaseTransactionType::MySql(
-                    MySqlDatabase,
-                    Arc::new(Mutex::new(Some(transaction.unwrap()))),
+                    MySqlDatabase::new(pool.connect_options(), self.statement_timeout),
+                    Arc::new(Mutex::new(Some(transaction))),
                 ))
             }
             DatabaseType::Sqlite(pool) => {
-                let transaction = pool
-                    .begin()
-                    .await
-                    .map_err(|e| SqlxError::Configuration(e.to_string().into()));
+                let transaction = begin_in_mode(pool, &mode).await?;
                 DatabaseTransaction::from_transaction(DatabaseTransactionType::SQLite(
-                    SqliteDatabase,
-                    Arc::new(Mutex::new(Some(transaction.unwrap()))),
+                    SqliteDatabase::new(self.settings.clone(), self.statement_timeout),
+                    Arc::new(Mutex::new(Some(transaction))),
                 ))
             }
-        }
+        };
+        Ok(transaction.with_connection(self.clone()).with_mode(mode))
     }
 
-    pub async fn begin_transaction(&self) -> Option<Box<dyn std::any::Any + Send>> {
-        
+    pub async fn begin_transaction(
+        &self,
+        mode: &ModeStatements,
+    ) -> Option<Box<dyn std::any::Any + Send>> {
         match &self.connection {
-            DatabaseType::Postgres(pool) => {
-                let transaction: sqlx::Transaction<Postgres> = pool.begin().await.ok()?;
+            DatabaseType::Postgres(pool, _) => {
+                let transaction: sqlx::Transaction<Postgres> =
+                    begin_in_mode(pool, mode).await.ok()?;
                 Some(Box::new(transaction))
             }
-            DatabaseType::MySql(pool) => {
-                let transaction: sqlx::Transaction<MySql> = pool.begin().await.ok()?;
+            DatabaseType::MySql(pool, _) => {
+                let transaction: sqlx::Transaction<MySql> = begin_in_mode(pool, mode).await.ok()?;
                 Some(Box::new(transaction))
             }
             DatabaseType::Sqlite(pool) => {
-                let transaction: sqlx::Transaction<Sqlite> = pool.begin().await.ok()?;
+                let transaction: sqlx::Transaction<Sqlite> =
+                    begin_in_mode(pool, mode).await.ok()?;
                 Some(Box::new(transaction))
             }
         }
     }
+
+    fn driver(&self) -> Driver {
+        match &self.connection {
+            DatabaseType::Postgres(..) => Driver::Postgres,
+            DatabaseType::MySql(..) => Driver::Mysql,
+            DatabaseType::Sqlite(..) => Driver::Sqlite,
+        }
+    }
+
+    // Run `attempt`, which must roll back whatever it did when it fails, again after each
+    // transient error the retry policy covers; any other error is returned at once
+    fn with_retries<T>(
+        &self,
+        py: Python<'_>,
+        mut attempt: impl FnMut() -> PyResult<T>,
+    ) -> PyResult<T> {
+        let mut backoff = self.retry.backoff;
+        for _ in 0..self.retry.max_retries {
+            match attempt() {
+                Err(e)
+                    if sqlstate_of(py, &e).is_some_and(|code| self.retry.codes.contains(&code)) =>
+                {
+                    py.allow_threads(|| std::thread::sleep(backoff));
+                    backoff *= 2;
+                }
+                result => return result,
+            }
+        }
+        attempt()
+    }
+
+    fn ensure_autocommit(&self) -> PyResult<()> {
+        if !self.autocommit {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "autocommit is disabled for this connection; run statements through transaction()",
+            ));
+        }
+        Ok(())
+    }
+}
+
+// "UTF-8", "utf8" and MySQL's "utf8mb4" all name full UTF-8
+fn normalize_encoding(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_uppercase();
+    match name.as_str() {
+        "UTF8MB4" => "UTF8".to_string(),
+        _ => name,
+    }
+}
+
+// Run a single operation in its own transaction: commit when it succeeds, roll back when it fails.
+async fn run_autocommit<DB, T, F, Fut>(pool: &Pool<DB>, operation: F) -> PyResult<T>
+where
+    DB: sqlx::Database,
+    for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+    F: FnOnce(Arc<Mutex<Option<sqlx::Transaction<'static, DB>>>>) -> Fut,
+    Fut: Future<Output = PyResult<T>>,
+{
+    run_in_transaction(pool, &ModeStatements::default(), operation).await
+}
+
+// Begin a transaction on a pooled connection in the mode `mode` sets up
+async fn begin_in_mode<DB>(
+    pool: &Pool<DB>,
+    mode: &ModeStatements,
+) -> Result<sqlx::Transaction<'static, DB>, SqlxError>
+where
+    DB: sqlx::Database,
+    for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+{
+    let mut connection = pool.acquire().await?;
+    if let Some(statement) = &mode.before_begin {
+        connection.execute(sqlx::raw_sql(statement)).await?;
+    }
+    let mut transaction = sqlx::Transaction::begin(connection).await?;
+    if let Some(statement) = &mode.after_begin {
+        transaction.execute(sqlx::raw_sql(statement)).await?;
+    }
+    Ok(transaction)
+}
+
+// As `run_autocommit`, with the transaction started in the mode `mode` sets up.
+async fn run_in_transaction<DB, T, F, Fut>(
+    pool: &Pool<DB>,
+    mode: &ModeStatements,
+    operation: F,
+) -> PyResult<T>
+where
+    DB: sqlx::Database,
+    for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+    F: FnOnce(Arc<Mutex<Option<sqlx::Transaction<'static, DB>>>>) -> Fut,
+    Fut: Future<Output = PyResult<T>>,
+{
+    let transaction = begin_in_mode(pool, mode).await.map_err(database_error)?;
+    let transaction = Arc::new(Mutex::new(Some(transaction)));
+
+    let result = operation(transaction.clone()).await;
+
+    if let Some(mut transaction) = transaction.lock().await.take() {
+        if let Some(statement) = &mode.before_end {
+            let reset = transaction.execute(sqlx::raw_sql(statement)).await;
+            if result.is_ok() {
+                reset.map_err(database_error)?;
+            }
+        }
+        match &result {
+            Ok(_) => transaction.commit().await.map_err(database_error)?,
+            Err(_) => {
+                transaction.rollback().await.ok();
+            }
+        }
+    }
+    result
+}
+
+#[pymethods]
+impl DatabaseConnection {
+    #[new]
+    fn py_new(config: DatabaseConfig) -> PyResult<Self> {
+        let connection = futures::executor::block_on(Self::new(config)).map_err(|e| {
+            let e = on_connect::explain(e);
+            PyErr::new::<pyo3::exceptions::PyConnectionError, _>(e.to_string())
+        })?;
+        set_sql_connect(connection.clone());
+        Ok(connection)
+    }
+
+    // isolation is an IsolationLevel or its name, kept for the transactions commit and
+    // rollback open next
+    #[pyo3(name = "transaction", signature = (isolation=None))]
+    fn py_transaction(
+        &self,
+        py: Python<'_>,
+        isolation: Option<&PyAny>,
+    ) -> PyResult<DatabaseTransaction> {
+        let mode = TransactionMode {
+            isolation: isolation.map(IsolationLevel::from_py).transpose()?,
+            read_only: false,
+        };
+        // Waiting for a pooled connection without the GIL: returning one to the pool can
+        // depend on an awaitable's task that needs the GIL to finish
+        py.allow_threads(|| futures::executor::block_on(self.transaction(mode)))
+            .map_err(database_error)
+    }
+
+    // Health check for readiness probes: True when the primary and every replica hand out a
+    // live connection. Failures, including an acquire timing out, give False rather than raise.
+    fn ping(&self) -> bool {
+        futures::executor::block_on(async {
+            for pool in std::iter::once(&self.connection).chain(&self.replicas) {
+                if !pool.ping().await {
+                    return false;
+                }
+            }
+            true
+        })
+    }
+
+    // Emergency shutdown: close the primary and replica pools without waiting and kill the
+    // sessions still running statements or holding transactions.
+    fn abort_all(&self) -> PyResult<u64> {
+        futures::executor::block_on(async {
+            let mut terminated = 0;
+            let mut first_error = None;
+            for pool in std::iter::once(&self.connection).chain(&self.replicas) {
+                match pool.abort_all().await {
+                    Ok(count) => terminated += count,
+                    Err(e) => {
+                        first_error.get_or_insert(e);
+                    }
+                }
+            }
+            match first_error {
+                Some(e) => Err(database_error(e)),
+                None => Ok(terminated),
+            }
+        })
+    }
+
+    // A LISTEN/NOTIFY subscriber on a new connection to the primary, already listening on
+    // `channels`; PostgreSQL only
+    #[pyo3(signature = (channels=Vec::new()))]
+    fn listener(&self, channels: Vec<String>) -> PyResult<Listener> {
+        let DatabaseType::Postgres(pool, _) = &self.connection else {
+            return Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                "listener is only supported on PostgreSQL",
+            ));
+        };
+        futures::executor::block_on(Listener::connect(&pool.connect_options(), &channels))
+            .map_err(database_error)
+    }
+
+    #[pyo3(signature = (query, params, prepared=true, route=None))]
+    fn execute(
+        &self,
+        py: Python<'_>,
+        query: &str,
+        params: Vec<&PyAny>,
+        prepared: bool,
+        route: Option<&str>,
+    ) -> PyResult<u64> {
+        self.ensure_autocommit()?;
+        let connection = self.route(query, route)?;
+        self.with_retries(py, || {
+            let params = params.clone();
+            futures::executor::block_on(async move {
+                match connection {
+                    DatabaseType::Postgres(pool, _) => {
+                        run_autocommit(pool, |transaction| async move {
+                            PostgresDatabase::new(self.settings.clone())
+                                .execute(transaction, query, params, prepared)
+                                .await
+                        })
+                        .await
+                    }
+                    DatabaseType::MySql(pool, _) => {
+                        run_autocommit(pool, |transaction| async move {
+                            MySqlDatabase::new(pool.connect_options(), self.statement_timeout)
+                                .execute(transaction, query, params, prepared)
+                                .await
+                        })
+                        .await
+                    }
+                    DatabaseType::Sqlite(pool) => {
+                        run_autocommit(pool, |transaction| async move {
+                            SqliteDatabase::new(self.settings.clone(), self.statement_timeout)
+                                .execute(transaction, query, params, prepared)
+                                .await
+                        })
+                        .await
+                    }
+                }
+            })
+        })
+    }
+
+    // Run (query, params) statements in one transaction on the primary, committing only if
+    // all of them succeed. Returns the rows affected by each statement.
+    #[pyo3(signature = (statements, isolation=None, read_only=false))]
+    fn execute_batch(
+        &self,
+        py: Python<'_>,
+        statements: Vec<(String, Vec<&PyAny>)>,
+        isolation: Option<&PyAny>,
+        read_only: bool,
+    ) -> PyResult<Vec<u64>> {
+        let mode = TransactionMode {
+            isolation: isolation.map(IsolationLevel::from_py).transpose()?,
+            read_only,
+        };
+        self.with_retries(py, || {
+            let statements = statements.clone();
+            futures::executor::block_on(async move {
+                match &self.connection {
+                    DatabaseType::Postgres(pool, _) => {
+                        let mode = mode.statements(Driver::Postgres);
+                        run_in_transaction(pool, &mode, |transaction| async move {
+                            let mut db = PostgresDatabase::new(self.settings.clone());
+                            let mut affected = Vec::with_capacity(statements.len());
+                            for (query, params) in statements {
+                                affected.push(
+                                    db.execute(transaction.clone(), &query, params, true)
+                                        .await?,
+                                );
+                            }
+                            Ok(affected)
+                        })
+                        .await
+                    }
+                    DatabaseType::MySql(pool, _) => {
+                        let mode = mode.statements(Driver::Mysql);
+                        run_in_transaction(pool, &mode, |transaction| async move {
+                            let mut db =
+                                MySqlDatabase::new(pool.connect_options(), self.statement_timeout);
+                            let mut affected = Vec::with_capacity(statements.len());
+                            for (query, params) in statements {
+                                affected.push(
+                                    db.execute(transaction.clone(), &query, params, true)
+                                        .await?,
+                                );
+                            }
+                            Ok(affected)
+                        })
+                        .await
+                    }
+                    DatabaseType::Sqlite(pool) => {
+                        let mode = mode.statements(Driver::Sqlite);
+                        run_in_transaction(pool, &mode, |transaction| async move {
+                            let mut affected = Vec::with_capacity(statements.len());
+                            for (query, params) in statements {
+                                affected.push(
+                                    SqliteDatabase::new(
+                                        self.settings.clone(),
+                                        self.statement_timeout,
+                                    )
+                                    .execute(transaction.clone(), &query, params, true)
+                                    .await?,
+                                );
+                            }
+                            Ok(affected)
+                        })
+                        .await
+                    }
+                }
+            })
+        })
+    }
+
+    #[pyo3(signature = (query, params, fetch_size=None, prepared=true, route=None))]
+    fn fetch_all(
+        &self,
+        py: Python<'_>,
+        query: &str,
+        params: Vec<&PyAny>,
+        fetch_size: Option<usize>,
+        prepared: bool,
+        route: Option<&str>,
+    ) -> PyResult<Vec<PyObject>> {
+        self.ensure_autocommit()?;
+        let connection = self.route(query, route)?;
+        self.with_retries(py, || {
+            let params = params.clone();
+            futures::executor::block_on(async move {
+                match connection {
+                    DatabaseType::Postgres(pool, _) => {
+                        run_autocommit(pool, |transaction| async move {
+                            PostgresDatabase::new(self.settings.clone())
+                                .fetch_all(py, transaction, query, params, fetch_size, prepared)
+                                .await
+                        })
+                        .await
+                    }
+                    DatabaseType::MySql(pool, _) => {
+                        run_autocommit(pool, |transaction| async move {
+                            MySqlDatabase::new(pool.connect_options(), self.statement_timeout)
+                                .fetch_all(py, transaction, query, params, fetch_size, prepared)
+                                .await
+                        })
+                        .await
+                    }
+                    DatabaseType::Sqlite(pool) => {
+                        run_autocommit(pool, |transaction| async move {
+                            SqliteDatabase::new(self.settings.clone(), self.statement_timeout)
+                                .fetch_all(py, transaction, query, params, fetch_size, prepared)
+                                .await
+                        })
+                        .await
+                    }
+                }
+            })
+        })
+    }
 }
@@ -13,10 +13,13 @@ pub fn get_sql_session_mapping() -> &'static DashMap<String, DatabaseTransaction
     &SQL_SESSION_MAPPING
 }
 
+// Session and connection registration hooks for the embedding application.
+#[allow(dead_code)]
 pub fn insert_sql_session(session_id: &str, database: DatabaseTransaction) {
     SQL_SESSION_MAPPING.insert(session_id.to_string(), database);
 }
 
+#[allow(dead_code)]
 pub fn remove_sql_session(session_id: &str) {
     SQL_SESSION_MAPPING.remove(session_id);
 }
@@ -1,22 +1,31 @@
-use std::sync::Arc;
+use std::{borrow::Cow, sync::Arc};
 
+use futures::{Stream, StreamExt};
 use pyo3::prelude::*;
-use sqlx::Database;
+use sqlx::{query::Query, Column, Database, Describe, Either, Executor, IntoArguments, TypeInfo};
 use tokio::sync::Mutex;
 
+use super::errors::database_error;
+
 // Trait for dynamic parameter binding
 pub trait DynamicParameterBinder {
     type Arguments;
     type Database: Database;
     type Row;
 
+    // The SQL to send for `query`, whose placeholders may be rewritten for `params` (tuples
+    // expanded, typed casts, `?` for MySQL and SQLite), and the arguments bound for it; run
+    // them with `sqlx::query_with`
     fn bind_parameters<'q>(
         &self,
         query: &'q str,
         params: Vec<&PyAny>,
-    ) -> Result<sqlx::query::Query<'q, Self::Database, Self::Arguments>, PyErr>;
+    ) -> Result<(Cow<'q, str>, Self::Arguments), PyErr>;
 
     fn bind_result(&self, py: Python<'_>, row: &Self::Row) -> Result<PyObject, PyErr>;
+
+    // The row's values as a tuple in column order, keeping columns that share a name
+    fn bind_result_tuple(&self, py: Python<'_>, row: &Self::Row) -> Result<PyObject, PyErr>;
 }
 
 // Base trait for database operations with dynamic parameters
@@ -29,11 +38,14 @@ pub trait DatabaseOperations {
         Database = Self::DatabaseType,
     >;
 
+    fn binder(&self) -> Self::ParameterBinder;
+
     async fn execute(
         &mut self,
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
         query: &str,
         params: Vec<&PyAny>,
+        prepared: bool,
     ) -> Result<u64, PyErr>;
 
     async fn fetch_all(
@@ -42,22 +54,204 @@ pub trait DatabaseOperations {
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
         query: &str,
         params: Vec<&PyAny>,
+        fetch_size: Option<usize>,
+        prepared: bool,
     ) -> Result<Vec<PyObject>, PyErr>;
 
-    async fn stream_data(
+    // Exactly one row expected: no rows raises an error, extra rows are ignored
+    async fn fetch_one(
+        &mut self,
+        py: Python<'_>,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
+        query: &str,
+        params: Vec<&PyAny>,
+    ) -> PyResult<PyObject>;
+
+    // At most one row expected: None when there are none, extra rows are ignored
+    async fn fetch_optional(
+        &mut self,
+        py: Python<'_>,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
+        query: &str,
+        params: Vec<&PyAny>,
+    ) -> PyResult<Option<PyObject>>;
+
+    // A data-modifying statement with a RETURNING clause: the returned rows, one per row
+    // written
+    async fn execute_returning(
+        &mut self,
+        py: Python<'_>,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
+        query: &str,
+        params: Vec<&PyAny>,
+    ) -> PyResult<Vec<PyObject>> {
+        self.fetch_all(py, transaction, query, params, None, true)
+            .await
+    }
+
+    // As `fetch_all`, with each row a tuple of its values in column order
+    async fn fetch_all_tuples(
         &mut self,
         py: Python<'_>,
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
         query: &str,
         params: Vec<&PyAny>,
-        chunk_size: usize,
-    ) -> PyResult<Vec<Vec<PyObject>>>;
+    ) -> PyResult<Vec<PyObject>>;
+
+    // Name, lowercase type name and nullability (None when unknown) of each column the query
+    // returns, read from the prepared statement without running it
+    async fn describe(
+        &mut self,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
+        query: &str,
+    ) -> PyResult<Vec<ColumnDescription>>;
 
+    // With `returning` each statement's rows are fetched and kept (its RETURNING output);
+    // otherwise it is only executed
     async fn bulk_change(
         &mut self,
+        py: Python<'_>,
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
         query: &str,
         params: Vec<Vec<&PyAny>>,
         batch_size: usize,
-    ) -> Result<u64, PyErr>;
+        returning: bool,
+    ) -> Result<BulkChanges, PyErr>;
+}
+
+// What a `bulk_change` did: the rows each statement it sent affected (one statement per
+// batch, or per parameter set when the query is not batched) and the rows returned
+#[derive(Default)]
+pub struct BulkChanges {
+    pub statement_counts: Vec<u64>,
+    pub rows: Vec<PyObject>,
+}
+
+impl BulkChanges {
+    pub fn rows_affected(&self) -> u64 {
+        self.statement_counts.iter().sum()
+    }
+
+    // The results of one statement run with `fetch_many`: its affected row count is recorded
+    // and its rows converted and kept
+    pub fn add_results<Q, R>(
+        &mut self,
+        results: Vec<Either<Q, R>>,
+        rows_affected: impl Fn(&Q) -> u64,
+        mut convert: impl FnMut(&R) -> PyResult<PyObject>,
+    ) -> PyResult<()> {
+        let mut affected = 0;
+        for result in results {
+            match result {
+                Either::Left(done) => affected += rows_affected(&done),
+                Either::Right(row) => self.rows.push(convert(&row)?),
+            }
+        }
+        self.statement_counts.push(affected);
+        Ok(())
+    }
+}
+
+pub type ColumnDescription = (String, String, Option<bool>);
+
+pub fn column_descriptions<DB: Database>(describe: &Describe<DB>) -> Vec<ColumnDescription> {
+    describe
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            (
+                column.name().to_string(),
+                column.type_info().name().to_ascii_lowercase(),
+                describe.nullable(i),
+            )
+        })
+        .collect()
+}
+
+// `prepared = false` sends the statement over the simple query protocol (no server-side
+// prepare), which suits DDL, multi-statement scripts and transaction-pooling proxies such as
+// pgbouncer. That protocol has no bind parameters.
+pub fn ensure_no_parameters(params: &[&PyAny]) -> PyResult<()> {
+    if !params.is_empty() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unprepared statements cannot take parameters ({} provided)",
+            params.len()
+        )));
+    }
+    Ok(())
+}
+
+// Drain a row stream `fetch_size` rows at a time, converting each batch to Python objects
+// before reading the next. sqlx has no server-side fetch size, so this bounds how many
+// decoded rows are held in Rust at once; the socket applies backpressure to the server.
+pub async fn fetch_in_batches<R, S, F>(
+    mut stream: S,
+    fetch_size: usize,
+    mut convert: F,
+) -> PyResult<Vec<PyObject>>
+where
+    S: Stream<Item = Result<R, sqlx::Error>> + Unpin,
+    F: FnMut(&R) -> PyResult<PyObject>,
+{
+    if fetch_size == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "fetch_size must be greater than 0",
+        ));
+    }
+
+    let mut result: Vec<PyObject> = Vec::new();
+    let mut batch: Vec<R> = Vec::with_capacity(fetch_size);
+
+    while let Some(row_result) = stream.next().await {
+        let row = row_result.map_err(database_error)?;
+        batch.push(row);
+
+        if batch.len() >= fetch_size {
+            for row in batch.drain(..) {
+                result.push(convert(&row)?);
+            }
+        }
+    }
+
+    for row in batch.drain(..) {
+        result.push(convert(&row)?);
+    }
+    Ok(result)
+}
+
+// The transaction held in a handle's slot. The slot is empty once the handle's connection
+// could not begin a new transaction, which is an error rather than a panic.
+pub fn active<'s, DB: Database>(
+    slot: &'s mut Option<sqlx::Transaction<'static, DB>>,
+) -> PyResult<&'s mut sqlx::Transaction<'static, DB>> {
+    slot.as_mut()
+        .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("No active transaction"))
+}
+
+// Run `query` and hand each row to `visit` as it arrives, so only one decoded row exists at
+// a time. Returns how many rows were visited.
+pub async fn visit_rows<'q, DB, B>(
+    py: Python<'_>,
+    transaction: Arc<Mutex<Option<sqlx::Transaction<'static, DB>>>>,
+    binder: &B,
+    query: Query<'q, DB, B::Arguments>,
+    visit: &mut dyn FnMut(&PyAny) -> PyResult<()>,
+) -> PyResult<u64>
+where
+    DB: Database,
+    B: DynamicParameterBinder<Database = DB, Row = DB::Row>,
+    B::Arguments: IntoArguments<'q, DB> + 'q,
+    for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+{
+    let mut guard = transaction.lock().await;
+    let connection = active(&mut guard)?;
+    let mut rows = query.fetch(&mut **connection);
+    let mut visited = 0;
+    while let Some(row) = rows.next().await {
+        let row = binder.bind_result(py, &row.map_err(database_error)?)?;
+        visit(row.as_ref(py))?;
+        visited += 1;
+    }
+    Ok(visited)
 }
@@ -0,0 +1,25 @@
+use pyo3::prelude::*;
+
+// A string parameter meant as a label of the named PostgreSQL enum type rather than text, which
+// the server will not assign to an enum column. Enum columns are returned as plain strings, so
+// wrap one in this to bind it back.
+#[pyclass(module = "sqlrustler")]
+#[derive(Debug, Clone)]
+pub struct EnumValue {
+    #[pyo3(get)]
+    pub type_name: String,
+    #[pyo3(get)]
+    pub label: String,
+}
+
+#[pymethods]
+impl EnumValue {
+    #[new]
+    fn new(type_name: String, label: String) -> Self {
+        EnumValue { type_name, label }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("EnumValue({:?}, {:?})", self.type_name, self.label)
+    }
+}
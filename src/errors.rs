@@ -0,0 +1,107 @@
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+use pyo3::{exceptions::PyBaseException, prelude::*};
+
+use super::on_connect;
+
+lazy_static! {
+    static ref ERROR_TRANSLATOR: RwLock<Option<PyObject>> = RwLock::new(None);
+}
+
+// Register `translator(sqlstate, message)` to turn database errors into application
+// exceptions. It returns the exception instance to raise, or None to keep the default
+// RuntimeError; passing None unregisters it.
+#[pyfunction]
+#[pyo3(signature = (translator=None))]
+pub fn set_error_translator(py: Python<'_>, translator: Option<PyObject>) -> PyResult<()> {
+    if let Some(translator) = &translator {
+        if !translator.as_ref(py).is_callable() {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "error translator must be callable",
+            ));
+        }
+    }
+    *ERROR_TRANSLATOR.write().unwrap() = translator;
+    Ok(())
+}
+
+// Convert a sqlx error into the exception raised to Python, consulting the registered
+// translator. `sqlstate` is None for errors that did not come from the server (I/O, pool
+// timeouts, decoding); it is also set as the exception's `sqlstate` attribute.
+pub fn database_error(error: sqlx::Error) -> PyErr {
+    let error = on_connect::explain(error);
+    let default = match is_statement_timeout(&error) {
+        true => PyErr::new::<pyo3::exceptions::PyTimeoutError, _>(error.to_string()),
+        false => PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(error.to_string()),
+    };
+    let (sqlstate, message) = match error.as_database_error() {
+        Some(db_error) => (
+            db_error.code().map(|code| code.into_owned()),
+            db_error.message().to_string(),
+        ),
+        None => (None, error.to_string()),
+    };
+
+    Python::with_gil(|py| {
+        let translated = match ERROR_TRANSLATOR.read().unwrap().clone() {
+            None => default,
+            Some(translator) => match translator.call1(py, (sqlstate.clone(), message)) {
+                Ok(result) if result.is_none(py) => default,
+                Ok(result) if result.as_ref(py).is_instance_of::<PyBaseException>() => {
+                    PyErr::from_value(result.as_ref(py))
+                }
+                Ok(result) => {
+                    return PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                        "error translator must return an exception or None, not {}",
+                        result.as_ref(py).get_type().name().unwrap_or("?")
+                    ))
+                }
+                Err(err) => return err,
+            },
+        };
+        // best effort: a translated exception type may refuse new attributes
+        let _ = translated.value(py).setattr("sqlstate", sqlstate);
+        translated
+    })
+}
+
+// The SQLSTATE `database_error` attached to `err`, if it came from the server
+pub fn sqlstate_of(py: Python<'_>, err: &PyErr) -> Option<String> {
+    err.value(py).getattr("sqlstate").ok()?.extract().ok()
+}
+
+// A statement stopped for running past its timeout: PostgreSQL's statement_timeout cancel,
+// MySQL's max_execution_time (3024), MariaDB's max_statement_time (1969), or the progress-handler
+// interrupt used for SQLite.
+pub fn is_statement_timeout(error: &sqlx::Error) -> bool {
+    let Some(db_error) = error.as_database_error() else {
+        return false;
+    };
+    if let Some(mysql) = db_error.try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>() {
+        return matches!(mysql.number(), 3024 | 1969);
+    }
+    match db_error.code().as_deref() {
+        Some("57014") => db_error.message().contains("statement timeout"),
+        Some("9") => db_error
+            .try_downcast_ref::<sqlx::sqlite::SqliteError>()
+            .is_some(),
+        _ => false,
+    }
+}
+
+// `fetch_one` on a query without rows gets its own message rather than sqlx's generic one.
+pub fn fetch_one_error(error: sqlx::Error) -> PyErr {
+    match error {
+        sqlx::Error::RowNotFound => PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+            "fetch_one: the query returned no rows",
+        ),
+        error => database_error(error),
+    }
+}
+
+// A parameter value the driver could not encode, reported as sqlx would have when running the
+// query
+pub fn encode_error(error: sqlx::error::BoxDynError) -> PyErr {
+    database_error(sqlx::Error::Encode(error))
+}
@@ -0,0 +1,108 @@
+use pyo3::{
+    prelude::*,
+    types::{
+        PyBool, PyByteArray, PyBytes, PyDate, PyDateTime, PyDict, PyFloat, PyInt, PyList, PyString,
+        PyTime, PyTuple,
+    },
+};
+use serde_json::{Map, Number, Value};
+
+fn is_instance_of_named(value: &PyAny, module: &str, name: &str) -> PyResult<bool> {
+    value.is_instance(value.py().import(module)?.getattr(name)?)
+}
+
+// Convert a dict/list parameter into a JSON document. None, bools, ints, floats and strings map
+// onto their JSON counterparts, lists and tuples become arrays and dicts become objects. UUIDs,
+// Decimals and datetimes/dates/times are written as their string forms (`str()` /
+// `isoformat()`), which is how they would round-trip through the column.
+pub fn py_to_json(value: &PyAny) -> PyResult<Value> {
+    to_json(value, false)
+}
+
+// Row values for NDJSON output: as `py_to_json`, except bytes are written in PostgreSQL's
+// `\x...` hex form (as its `to_json` does) and values JSON has no form for (complex,
+// timedelta, NaN, ...) as their `str()`.
+pub fn row_value_to_json(value: &PyAny) -> PyResult<Value> {
+    to_json(value, true)
+}
+
+// The fields of a `Composite` parameter, for `jsonb_populate_record`, which reads each value
+// with its attribute type's text input: bytes as `\x...` hex, NaN and the like as their
+// `str()`, as in `row_value_to_json`.
+pub fn record_to_json(fields: &PyAny) -> PyResult<Value> {
+    to_json(fields, true)
+}
+
+fn to_json(value: &PyAny, lenient: bool) -> PyResult<Value> {
+    Ok(if value.is_none() {
+        Value::Null
+    } else if let Ok(b) = value.downcast::<PyBool>() {
+        Value::Bool(b.is_true())
+    } else if value.is_instance_of::<PyInt>() {
+        match value.extract::<i64>() {
+            Ok(i) => Value::from(i),
+            Err(_) => Value::from(value.extract::<u64>()?),
+        }
+    } else if let Ok(f) = value.downcast::<PyFloat>() {
+        if lenient && !f.value().is_finite() {
+            return Ok(Value::String(value.str()?.to_str()?.to_string()));
+        }
+        Value::Number(Number::from_f64(f.value()).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "{} is not valid in JSON",
+                f.value()
+            ))
+        })?)
+    } else if let Ok(s) = value.downcast::<PyString>() {
+        Value::String(s.to_str()?.to_string())
+    } else if value.is_instance_of::<PyDateTime>()
+        || value.is_instance_of::<PyDate>()
+        || value.is_instance_of::<PyTime>()
+    {
+        Value::String(value.call_method0("isoformat")?.extract()?)
+    } else if is_instance_of_named(value, "uuid", "UUID")?
+        || is_instance_of_named(value, "decimal", "Decimal")?
+    {
+        Value::String(value.str()?.to_str()?.to_string())
+    } else if let Ok(d) = value.downcast::<PyDict>() {
+        let mut map = Map::with_capacity(d.len());
+        for (key, item) in d.iter() {
+            let key = match key.downcast::<PyString>() {
+                Ok(key) => key.to_str()?.to_string(),
+                Err(_) => {
+                    return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                        "JSON object keys must be str, not {}",
+                        key.get_type().name()?
+                    )))
+                }
+            };
+            map.insert(key, to_json(item, lenient)?);
+        }
+        Value::Object(map)
+    } else if let Ok(l) = value.downcast::<PyList>() {
+        Value::Array(
+            l.iter()
+                .map(|item| to_json(item, lenient))
+                .collect::<PyResult<_>>()?,
+        )
+    } else if let Ok(t) = value.downcast::<PyTuple>() {
+        Value::Array(
+            t.iter()
+                .map(|item| to_json(item, lenient))
+                .collect::<PyResult<_>>()?,
+        )
+    } else if lenient
+        && (value.is_instance_of::<PyBytes>() || value.is_instance_of::<PyByteArray>())
+    {
+        let bytes: Vec<u8> = value.extract()?;
+        let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        Value::String(format!("\\x{}", hex))
+    } else if lenient {
+        Value::String(value.str()?.to_str()?.to_string())
+    } else {
+        return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "Object of type {} is not JSON serializable",
+            value.get_type().name()?
+        )));
+    })
+}
@@ -1,10 +1,30 @@
+// pyo3 0.20's `#[pymethods]` expands to impls that newer rustc flags as non-local.
+#![allow(non_local_definitions)]
+
 use pyo3::prelude::*;
 
+mod awaitable;
+mod checksum;
+mod composite;
 mod config;
 mod connection;
 mod db_trait;
+mod enum_value;
+mod errors;
+mod json;
+mod listener;
+mod msgpack;
 mod mysql;
+mod ndjson;
+mod numpy;
+mod on_connect;
+mod pg_types;
 mod postgresql;
+mod query_builder;
+mod range;
+mod routing;
+mod row_stream;
+mod sessions;
 mod sqlite;
 mod transaction;
 mod context;
@@ -15,6 +35,22 @@ fn sqlrustler(_py: Python, module: &PyModule) -> PyResult<()>  {
     module.add_class::<config::DatabaseType>()?;
     module.add_class::<config::DatabaseConfig>()?;
     module.add_class::<transaction::DatabaseTransaction>()?;
+    module.add_class::<transaction::IsolationLevel>()?;
+    module.add_class::<connection::DatabaseConnection>()?;
+    module.add_class::<range::Range>()?;
+    module.add_class::<composite::Composite>()?;
+    module.add_class::<enum_value::EnumValue>()?;
+    module.add_class::<ndjson::NdjsonReader>()?;
+    module.add_class::<row_stream::RowStream>()?;
+    module.add_class::<listener::Listener>()?;
+    module.add_class::<on_connect::NewConnection>()?;
+    module.add_function(wrap_pyfunction!(context::get_session_database, module)?)?;
+    module.add_function(wrap_pyfunction!(query_builder::order_by, module)?)?;
+    module.add_function(wrap_pyfunction!(query_builder::select_list, module)?)?;
+    module.add_function(wrap_pyfunction!(query_builder::text_search, module)?)?;
+    module.add_function(wrap_pyfunction!(query_builder::as_date, module)?)?;
+    module.add_function(wrap_pyfunction!(query_builder::named_parameters, module)?)?;
+    module.add_function(wrap_pyfunction!(errors::set_error_translator, module)?)?;
 
     pyo3::prepare_freethreaded_python();
     Ok(())
@@ -0,0 +1,146 @@
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+use pyo3::prelude::*;
+use sqlx::{
+    pool::PoolOptions,
+    postgres::{PgConnectOptions, PgListener},
+    Postgres,
+};
+
+use super::errors::database_error;
+
+lazy_static! {
+    // Drives the timers bounding a wait or a MySQL statement; the socket I/O itself stays on
+    // async-std.
+    pub static ref TIMER: tokio::runtime::Runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_time()
+        .build()
+        .expect("failed to start the listener timer");
+}
+
+// How long a wait runs with the GIL released before checking for signals such as Ctrl-C
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+// LISTEN/NOTIFY subscriber on its own PostgreSQL connection, outside the pool. A lost connection
+// is reopened, and its channels listened to again, on the next wait; notifications sent while
+// it was down are lost.
+#[pyclass]
+pub struct Listener {
+    listener: Option<PgListener>,
+}
+
+impl Listener {
+    pub async fn connect(
+        options: &PgConnectOptions,
+        channels: &[String],
+    ) -> Result<Self, sqlx::Error> {
+        let pool = PoolOptions::<Postgres>::new()
+            .max_connections(1)
+            .max_lifetime(None)
+            .idle_timeout(None)
+            .connect_with(options.clone())
+            .await?;
+        let mut listener = PgListener::connect_with(&pool).await?;
+        listener.ignore_pool_close_event(true);
+        for channel in channels {
+            listener.listen(channel).await?;
+        }
+        Ok(Self {
+            listener: Some(listener),
+        })
+    }
+
+    fn listener(&mut self) -> PyResult<&mut PgListener> {
+        self.listener
+            .as_mut()
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Listener is closed"))
+    }
+}
+
+#[pymethods]
+impl Listener {
+    fn listen(&mut self, channel: &str) -> PyResult<()> {
+        let listener = self.listener()?;
+        futures::executor::block_on(listener.listen(channel)).map_err(database_error)
+    }
+
+    fn unlisten(&mut self, channel: &str) -> PyResult<()> {
+        let listener = self.listener()?;
+        futures::executor::block_on(listener.unlisten(channel)).map_err(database_error)
+    }
+
+    fn unlisten_all(&mut self) -> PyResult<()> {
+        let listener = self.listener()?;
+        futures::executor::block_on(listener.unlisten_all()).map_err(database_error)
+    }
+
+    // The next (channel, payload), waiting up to timeout_secs (forever when None) with the GIL
+    // released; None when the wait runs out.
+    #[pyo3(signature = (timeout_secs=None))]
+    fn recv(
+        &mut self,
+        py: Python<'_>,
+        timeout_secs: Option<f64>,
+    ) -> PyResult<Option<(String, String)>> {
+        let deadline = timeout_secs
+            .map(|secs| {
+                Duration::try_from_secs_f64(secs).map_err(|_| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                        "timeout_secs must be a non-negative number",
+                    )
+                })
+            })
+            .transpose()?
+            .map(|timeout| Instant::now() + timeout);
+        let listener = self.listener()?;
+        loop {
+            let wait = match deadline {
+                Some(deadline) => {
+                    SIGNAL_CHECK_INTERVAL.min(deadline.saturating_duration_since(Instant::now()))
+                }
+                None => SIGNAL_CHECK_INTERVAL,
+            };
+            let received = py.allow_threads(|| {
+                TIMER.block_on(async { tokio::time::timeout(wait, listener.recv()).await.ok() })
+            });
+            if let Some(notification) = received {
+                let notification = notification.map_err(database_error)?;
+                return Ok(Some((
+                    notification.channel().to_string(),
+                    notification.payload().to_string(),
+                )));
+            }
+            py.check_signals()?;
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Ok(None);
+            }
+        }
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    // Waits for the next notification; iteration ends only when the listener is closed
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<(String, String)>> {
+        if self.listener.is_none() {
+            return Ok(None);
+        }
+        self.recv(py, None)
+    }
+
+    // Drops the connection, and with it every subscription
+    fn close(&mut self) {
+        self.listener = None;
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(&mut self, _exc_type: &PyAny, _exc_value: &PyAny, _traceback: &PyAny) {
+        self.close();
+    }
+}
@@ -0,0 +1,93 @@
+use pyo3::{
+    prelude::*,
+    types::{
+        PyBool, PyByteArray, PyBytes, PyComplex, PyDate, PyDateTime, PyDict, PyFloat, PyFrozenSet,
+        PyInt, PyList, PySet, PyString, PyTime, PyTuple,
+    },
+};
+use rmp::encode;
+
+fn encode_error(e: impl std::fmt::Display) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("msgpack encoding failed: {}", e))
+}
+
+fn write_sequence<'a>(
+    buf: &mut Vec<u8>,
+    len: usize,
+    items: impl Iterator<Item = &'a PyAny>,
+) -> PyResult<()> {
+    encode::write_array_len(buf, len as u32).map_err(encode_error)?;
+    for item in items {
+        write_value(buf, item)?;
+    }
+    Ok(())
+}
+
+// Encode a decoded column value. Integers, floats, strings, booleans and NULL map onto the
+// native msgpack types; `bytes` become msgpack `bin`; datetimes, dates and times are written
+// as ISO 8601 strings (timestamptz keeps its UTC offset); complex numbers become `[re, im]`;
+// lists, tuples and sets become arrays and dicts become maps. Anything else (e.g. Decimal)
+// is written as its `str()`.
+fn write_value(buf: &mut Vec<u8>, value: &PyAny) -> PyResult<()> {
+    if value.is_none() {
+        encode::write_nil(buf).map_err(encode_error)?;
+    } else if let Ok(b) = value.downcast::<PyBool>() {
+        encode::write_bool(buf, b.is_true()).map_err(encode_error)?;
+    } else if value.is_instance_of::<PyInt>() {
+        match value.extract::<i64>() {
+            Ok(i) => encode::write_sint(buf, i).map(|_| ()),
+            Err(_) => encode::write_uint(buf, value.extract::<u64>()?).map(|_| ()),
+        }
+        .map_err(encode_error)?;
+    } else if let Ok(f) = value.downcast::<PyFloat>() {
+        encode::write_f64(buf, f.value()).map_err(encode_error)?;
+    } else if let Ok(s) = value.downcast::<PyString>() {
+        encode::write_str(buf, s.to_str()?).map_err(encode_error)?;
+    } else if let Ok(b) = value.downcast::<PyBytes>() {
+        encode::write_bin(buf, b.as_bytes()).map_err(encode_error)?;
+    } else if let Ok(b) = value.downcast::<PyByteArray>() {
+        encode::write_bin(buf, &b.to_vec()).map_err(encode_error)?;
+    } else if value.is_instance_of::<PyDateTime>()
+        || value.is_instance_of::<PyDate>()
+        || value.is_instance_of::<PyTime>()
+    {
+        let iso: String = value.call_method0("isoformat")?.extract()?;
+        encode::write_str(buf, &iso).map_err(encode_error)?;
+    } else if let Ok(c) = value.downcast::<PyComplex>() {
+        encode::write_array_len(buf, 2).map_err(encode_error)?;
+        encode::write_f64(buf, c.real()).map_err(encode_error)?;
+        encode::write_f64(buf, c.imag()).map_err(encode_error)?;
+    } else if let Ok(d) = value.downcast::<PyDict>() {
+        write_map(buf, d)?;
+    } else if let Ok(l) = value.downcast::<PyList>() {
+        write_sequence(buf, l.len(), l.iter())?;
+    } else if let Ok(t) = value.downcast::<PyTuple>() {
+        write_sequence(buf, t.len(), t.iter())?;
+    } else if let Ok(s) = value.downcast::<PySet>() {
+        write_sequence(buf, s.len(), s.iter())?;
+    } else if let Ok(s) = value.downcast::<PyFrozenSet>() {
+        write_sequence(buf, s.len(), s.iter())?;
+    } else {
+        encode::write_str(buf, value.str()?.to_str()?).map_err(encode_error)?;
+    }
+    Ok(())
+}
+
+fn write_map(buf: &mut Vec<u8>, dict: &PyDict) -> PyResult<()> {
+    encode::write_map_len(buf, dict.len() as u32).map_err(encode_error)?;
+    for (key, value) in dict.iter() {
+        write_value(buf, key)?;
+        write_value(buf, value)?;
+    }
+    Ok(())
+}
+
+// Serialize fetched rows as a msgpack array of maps (column name -> value).
+pub fn rows_to_msgpack<'py>(py: Python<'py>, rows: &[PyObject]) -> PyResult<&'py PyBytes> {
+    let mut buf = Vec::new();
+    encode::write_array_len(&mut buf, rows.len() as u32).map_err(encode_error)?;
+    for row in rows {
+        write_value(&mut buf, row.as_ref(py))?;
+    }
+    Ok(PyBytes::new(py, &buf))
+}
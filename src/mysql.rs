@@ -1,15 +1,44 @@
+use futures::TryStreamExt;
+use lazy_static::lazy_static;
 use regex::Regex;
-use std::sync::Arc;
+use std::{borrow::Cow, future::Future, sync::Arc, time::Duration};
 use tokio::sync::Mutex;
 
-use futures::StreamExt;
-use pyo3::{prelude::*, types::PyDict};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use pyo3::{
+    prelude::*,
+    types::{
+        timezone_utc, PyBool, PyByteArray, PyBytes, PyDate, PyDateTime, PyDict, PyFrozenSet, PyInt,
+        PyList, PySet, PyTime, PyTuple,
+    },
+};
+use rust_decimal::Decimal;
 use sqlx::{
-    mysql::{MySqlArguments, MySqlRow},
-    Column, Row, ValueRef,
+    mysql::{
+        MySqlArguments, MySqlConnectOptions, MySqlConnection, MySqlQueryResult, MySqlRow,
+        MySqlTypeInfo,
+    },
+    types::{Json, JsonValue},
+    Column, Connection, Execute, Executor, Row, TypeInfo, ValueRef,
 };
 
-use super::db_trait::{DatabaseOperations, DynamicParameterBinder};
+use super::{
+    config::DatabaseType,
+    db_trait::{
+        active, column_descriptions, ensure_no_parameters, fetch_in_batches, BulkChanges,
+        ColumnDescription, DatabaseOperations, DynamicParameterBinder,
+    },
+    errors::{database_error, encode_error, fetch_one_error},
+    json::py_to_json,
+    listener::TIMER,
+    postgresql::{
+        decimal_to_py, extract_date, extract_datetime, extract_decimal, extract_time,
+        extract_utc_datetime, is_decimal, is_naive, new_py_datetime,
+    },
+    query_builder::{
+        bulk_statements, check_parameter_count, expand_tuple_parameters, replace_placeholders,
+    },
+};
 // Similarly implement for other database types...
 pub struct MySqlParameterBinder;
 
@@ -19,20 +48,23 @@ impl MySqlParameterBinder {
         query: &str,
         params: Vec<&'q PyAny>,
     ) -> Result<(String, Vec<&'q PyAny>), PyErr> {
-        let re = Regex::new(r"\$(\d+)").unwrap();
-
-        let params_extracted: Vec<String> = re
-            .find_iter(query)
-            .filter_map(|mat| Some(mat.as_str().to_string()))
-            .collect();
-
-        let mut converted_query = query.to_string();
         let mut param_values: Vec<&PyAny> = Vec::new();
+        let mut out_of_range: Option<usize> = None;
 
-        for p in params_extracted {
-            converted_query = converted_query.replace(&p, "?");
-            let index = p[1..].parse::<usize>().unwrap();
-            param_values.push(params[index - 1]);
+        let converted_query = replace_placeholders(&DatabaseType::Mysql, query, |index| {
+            match index.checked_sub(1).and_then(|i| params.get(i)) {
+                Some(param) => param_values.push(*param),
+                None => out_of_range = Some(index),
+            }
+            "?".to_string()
+        });
+
+        if let Some(index) = out_of_range {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Placeholder ${} has no matching parameter ({} provided)",
+                index,
+                params.len()
+            )));
         }
 
         Ok((converted_query, param_values))
@@ -48,19 +80,82 @@ impl DynamicParameterBinder for MySqlParameterBinder {
         &self,
         query: &'q str,
         params: Vec<&PyAny>,
-    ) -> Result<sqlx::query::Query<'q, Self::Database, Self::Arguments>, PyErr> {
-        // Create query with explicit lifetime
-        let (query_converted, params_converted) = self.convert_sql_params(query, params).unwrap();
-        let query_converted = String::leak(query_converted);
-        let mut query_builder = sqlx::query::<Self::Database>(query_converted);
+    ) -> Result<(Cow<'q, str>, Self::Arguments), PyErr> {
+        check_parameter_count(&DatabaseType::Mysql, query, params.len())?;
+        let expanded = expand_tuple_parameters(&DatabaseType::Mysql, query, &params)?;
+        let (query, params) = match &expanded {
+            Some((expanded, leaves)) => (expanded.as_str(), leaves.clone()),
+            None => (query, params),
+        };
+        let (query_converted, params_converted) = self.convert_sql_params(query, params)?;
+        let mut query_builder = sqlx::query::<Self::Database>(&query_converted);
 
         // Bind parameters with lifetime preservation
         for param in params_converted {
+            if param.is_none() {
+                query_builder = query_builder.bind(None::<String>);
+                continue;
+            }
+            // MySQL has no uuid type; UUIDs are stored in CHAR(36) columns in hyphenated form
+            if param.is_instance(param.py().import("uuid")?.getattr("UUID")?)? {
+                query_builder = query_builder.bind(param.str()?.to_str()?.to_string());
+                continue;
+            }
+            if let Ok(bytes) = param.downcast::<PyBytes>() {
+                query_builder = query_builder.bind(bytes.as_bytes().to_vec());
+                continue;
+            }
+            if let Ok(bytes) = param.downcast::<PyByteArray>() {
+                query_builder = query_builder.bind(bytes.to_vec());
+                continue;
+            }
+            if param.is_instance_of::<PySet>() || param.is_instance_of::<PyFrozenSet>() {
+                query_builder = query_builder.bind(join_set_members(param)?);
+                continue;
+            }
+            if param.is_instance_of::<PyDict>() || param.is_instance_of::<PyList>() {
+                query_builder = query_builder.bind(Json(py_to_json(param)?));
+                continue;
+            }
+            // The same scalars as on PostgreSQL; bool before int, as `True` is also an `int`
+            if param.is_instance_of::<PyBool>() {
+                query_builder = query_builder.bind(param.extract::<bool>()?);
+                continue;
+            }
+            // aware datetimes bind as their UTC value, naive ones unchanged
+            if param.is_instance_of::<PyDateTime>() {
+                query_builder = match is_naive(param)? {
+                    true => query_builder.bind(extract_datetime(param)?),
+                    false => query_builder.bind(extract_utc_datetime(param)?),
+                };
+                continue;
+            }
+            if param.is_instance_of::<PyDate>() {
+                query_builder = query_builder.bind(extract_date(param)?);
+                continue;
+            }
+            if param.is_instance_of::<PyTime>() {
+                query_builder = query_builder.bind(extract_time(param)?);
+                continue;
+            }
+            if is_decimal(param)? {
+                let Some(value) = extract_decimal(param)? else {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                        "Cannot bind Decimal('NaN'): MySQL DECIMAL has no NaN",
+                    ));
+                };
+                query_builder = query_builder.bind(value);
+                continue;
+            }
             query_builder = match param.extract::<String>() {
                 // Use String instead of &str
                 Ok(s) => query_builder.bind(s),
                 Err(_) => match param.extract::<i64>() {
                     Ok(i) => query_builder.bind(i),
+                    // past BIGINT's range as its digits, which MySQL converts to DECIMAL exactly
+                    Err(_) if param.is_instance_of::<PyInt>() => {
+                        query_builder.bind(param.str()?.to_string())
+                    }
                     Err(_) => match param.extract::<f64>() {
                         Ok(f) => query_builder.bind(f),
                         Err(_) => match param.extract::<bool>() {
@@ -75,43 +170,269 @@ impl DynamicParameterBinder for MySqlParameterBinder {
                 },
             };
         }
-        Ok(query_builder)
+        // The query only collects the arguments; the caller runs them with the SQL returned
+        // alongside
+        let arguments = query_builder
+            .take_arguments()
+            .map_err(encode_error)?
+            .unwrap_or_default();
+        Ok((Cow::Owned(query_converted), arguments))
     }
 
     fn bind_result(&self, py: Python<'_>, row: &MySqlRow) -> Result<PyObject, PyErr> {
         let dict = PyDict::new(py);
-
         for (i, column) in row.columns().iter().enumerate() {
-            let column_name = column.name();
-
-            // Dynamically handle different column types
-            match row.try_get_raw(i) {
-                Ok(val) => {
-                    if val.is_null() {
-                        dict.set_item(column_name, py.None()).unwrap();
-                    } else if let Ok(int_val) = row.try_get::<i32, _>(i) {
-                        dict.set_item(column_name, int_val).unwrap();
-                    } else if let Ok(str_val) = row.try_get::<String, _>(i) {
-                        dict.set_item(column_name, str_val).unwrap();
-                    } else if let Ok(float_val) = row.try_get::<f64, _>(i) {
-                        dict.set_item(column_name, float_val).unwrap();
-                    } else if let Ok(bool_val) = row.try_get::<bool, _>(i) {
-                        dict.set_item(column_name, bool_val).unwrap();
-                    }
-                }
-                Err(_) => {
-                    // Handle unsupported types or log an error
-                    dict.set_item(column_name, py.None()).unwrap();
-                }
-            }
+            dict.set_item(column.name(), column_value(py, row, i)?)?;
         }
-
         Ok(dict.into())
     }
+
+    fn bind_result_tuple(&self, py: Python<'_>, row: &MySqlRow) -> Result<PyObject, PyErr> {
+        let values = (0..row.len())
+            .map(|i| column_value(py, row, i))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(PyTuple::new(py, values).into())
+    }
+}
+
+// Dynamically handle different column types; anything unsupported is None
+fn column_value(py: Python<'_>, row: &MySqlRow, i: usize) -> PyResult<PyObject> {
+    let Ok(val) = row.try_get_raw(i) else {
+        return Ok(py.None());
+    };
+    if val.is_null() {
+        return Ok(py.None());
+    }
+    if let Some(value) = decode_typed_column(py, row, i)? {
+        return Ok(value);
+    }
+    Ok(if is_set_column(row.column(i).type_info()) {
+        let members = row.try_get::<String, _>(i).unwrap_or_default();
+        let members: Vec<&str> = members.split(',').filter(|m| !m.is_empty()).collect();
+        PySet::new(py, &members)?.into()
+    } else if let Ok(int_val) = row.try_get::<i64, _>(i) {
+        int_val.into_py(py)
+    } else if let Ok(int_val) = row.try_get::<u64, _>(i) {
+        // BIGINT UNSIGNED
+        int_val.into_py(py)
+    } else if let Ok(str_val) = row.try_get::<String, _>(i) {
+        str_val.into_py(py)
+    } else if let Ok(bytes_val) = row.try_get::<Vec<u8>, _>(i) {
+        // BINARY/VARBINARY/BLOB: binary-collated columns are not strings
+        PyBytes::new(py, &bytes_val).into()
+    } else if let Ok(float_val) = row.try_get::<f64, _>(i) {
+        float_val.into_py(py)
+    } else if let Ok(bool_val) = row.try_get::<bool, _>(i) {
+        bool_val.into_py(py)
+    } else {
+        // the raw value rather than None, which would read as NULL
+        let raw = row
+            .try_get_unchecked::<Vec<u8>, _>(i)
+            .map_err(database_error)?;
+        PyBytes::new(py, &raw).into()
+    })
+}
+
+// Types the probing chain in `column_value` cannot tell apart (TINYINT(1) from other integers)
+// or does not cover, decoded like their PostgreSQL counterparts: TIMESTAMP as a UTC-aware
+// datetime, DATETIME as a naive one, DECIMAL as Decimal and JSON as its text.
+fn decode_typed_column(py: Python<'_>, row: &MySqlRow, i: usize) -> PyResult<Option<PyObject>> {
+    let value = match row.column(i).type_info().name() {
+        "BOOLEAN" => row.try_get::<bool, _>(i).map(|v| v.into_py(py)),
+        "DATETIME" => match row.try_get::<NaiveDateTime, _>(i) {
+            Ok(v) => Ok(new_py_datetime(py, &v, None)?.into()),
+            Err(e) => Err(e),
+        },
+        "TIMESTAMP" => match row.try_get::<DateTime<Utc>, _>(i) {
+            Ok(v) => Ok(new_py_datetime(py, &v.naive_utc(), Some(timezone_utc(py)))?.into()),
+            Err(e) => Err(e),
+        },
+        "DATE" => match row.try_get::<NaiveDate, _>(i) {
+            Ok(v) => Ok(PyDate::new(py, v.year(), v.month() as u8, v.day() as u8)?.into()),
+            Err(e) => Err(e),
+        },
+        // TIME also holds durations past 24 hours and negative ones, which stay None
+        "TIME" => match row.try_get::<NaiveTime, _>(i) {
+            Ok(v) => Ok(PyTime::new(
+                py,
+                v.hour() as u8,
+                v.minute() as u8,
+                v.second() as u8,
+                v.nanosecond() / 1000,
+                None,
+            )?
+            .into()),
+            Err(e) => Err(e),
+        },
+        "DECIMAL" => match row.try_get::<Decimal, _>(i) {
+            Ok(v) => Ok(decimal_to_py(py, &v)?),
+            Err(e) => Err(e),
+        },
+        "JSON" => match row.try_get::<Json<JsonValue>, _>(i) {
+            Ok(v) => Ok(v.0.to_string().into_py(py)),
+            Err(e) => Err(e),
+        },
+        _ => return Ok(None),
+    };
+    Ok(value.ok())
+}
+
+lazy_static! {
+    static ref SET_FLAG: Regex = Regex::new(r"ColumnFlags\([^)]*\bSET\b").unwrap();
+}
+
+// MySQL reports `SET` columns as strings carrying the SET flag. sqlx keeps column flags
+// crate-private, so the flag is only observable through the type's Debug representation
+// (e.g. `flags: ColumnFlags(NOT_NULL | SET)`).
+fn is_set_column(type_info: &MySqlTypeInfo) -> bool {
+    type_info.name() == "SET" || SET_FLAG.is_match(&format!("{:?}", type_info))
+}
+
+// A Python set binds as the comma-joined string form of a `SET` value.
+fn join_set_members(param: &PyAny) -> PyResult<String> {
+    let mut members = param
+        .iter()?
+        .map(|member| member.and_then(|m| m.extract::<String>()))
+        .collect::<PyResult<Vec<String>>>()?;
+    if let Some(member) = members.iter().find(|m| m.contains(',')) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "SET members cannot contain ',': {:?}",
+            member
+        )));
+    }
+    members.sort();
+    Ok(members.join(","))
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct MySqlDatabase;
+#[derive(Debug, Clone)]
+pub struct MySqlDatabase {
+    // Enforced here for each statement: MySQL's max_execution_time covers SELECTs only and
+    // would cap a longer per-call timeout
+    statement_timeout: Option<Duration>,
+    // For the separate connection a statement that runs over is killed from
+    connect_options: Arc<MySqlConnectOptions>,
+    // The server's id for the connection in the transaction slot, read by the first bounded
+    // statement on it and shared by the handles for that slot
+    connection_id: Arc<std::sync::Mutex<Option<i64>>>,
+}
+
+impl MySqlDatabase {
+    pub fn new(
+        connect_options: Arc<MySqlConnectOptions>,
+        statement_timeout: Option<Duration>,
+    ) -> Self {
+        Self {
+            statement_timeout,
+            connect_options,
+            connection_id: Arc::default(),
+        }
+    }
+
+    // This handle with `timeout` in place of the configured statement timeout, if given
+    pub fn with_statement_timeout(self, timeout: Option<Duration>) -> Self {
+        match timeout {
+            Some(timeout) => Self {
+                statement_timeout: Some(timeout),
+                ..self
+            },
+            None => self,
+        }
+    }
+
+    // The server's id for `connection`, which a bounded statement about to run on it is
+    // killed by; None without a statement timeout. Asked for once per connection.
+    async fn arm(&self, connection: &mut MySqlConnection) -> PyResult<Option<i64>> {
+        if self.statement_timeout.is_none() {
+            return Ok(None);
+        }
+        if let Some(id) = *self.connection_id.lock().unwrap() {
+            return Ok(Some(id));
+        }
+        let id = sqlx::query_scalar("SELECT CAST(CONNECTION_ID() AS SIGNED)")
+            .fetch_one(connection)
+            .await
+            .map_err(database_error)?;
+        *self.connection_id.lock().unwrap() = Some(id);
+        Ok(Some(id))
+    }
+
+    // The transaction slot is about to get a transaction on another connection
+    pub fn forget_connection_id(&self) {
+        self.connection_id.lock().unwrap().take();
+    }
+
+    // `statement`'s output, or None when the statement timeout elapsed first and it was
+    // dropped
+    async fn bounded<T>(&self, statement: impl Future<Output = T>) -> Option<T> {
+        let Some(timeout) = self.statement_timeout else {
+            return Some(statement.await);
+        };
+        let bounded = {
+            let _timer = TIMER.enter();
+            tokio::time::timeout(timeout, statement)
+        };
+        bounded.await.ok()
+    }
+
+    // `operation`, which locks `transaction` to run one statement on it, bounded as the
+    // statements the DatabaseOperations methods run are
+    pub async fn run_bounded<T>(
+        &self,
+        transaction: &Arc<Mutex<Option<sqlx::Transaction<'static, sqlx::MySql>>>>,
+        operation: impl Future<Output = PyResult<T>>,
+    ) -> PyResult<T> {
+        if self.statement_timeout.is_none() {
+            return operation.await;
+        }
+        let id = self.arm(active(&mut *transaction.lock().await)?).await?;
+        match self.bounded(operation).await {
+            Some(result) => result,
+            None => Err(self.cancel(id, &mut *transaction.lock().await).await),
+        }
+    }
+
+    // After a bounded statement ran over: kill it on the server, where it is still running,
+    // then roll the transaction back and leave none active, as the connection was dropped in
+    // the middle of the statement. The returned error is raised in its place.
+    async fn cancel(
+        &self,
+        id: Option<i64>,
+        guard: &mut Option<sqlx::Transaction<'static, sqlx::MySql>>,
+    ) -> PyErr {
+        if let Some(id) = id {
+            if let Ok(mut connection) = MySqlConnection::connect_with(&self.connect_options).await {
+                let _ = connection
+                    .execute(format!("KILL QUERY {}", id).as_str())
+                    .await;
+                let _ = connection.close().await;
+            }
+        }
+        if let Some(transaction) = guard.take() {
+            // drains what is left of the killed statement first
+            let _ = transaction.rollback().await;
+        }
+        PyErr::new::<pyo3::exceptions::PyTimeoutError, _>(format!(
+            "statement timed out after {:?}; the transaction was rolled back",
+            self.statement_timeout.unwrap_or_default()
+        ))
+    }
+}
+
+// Whether a server reporting `SELECT VERSION()` as `version` accepts `INSERT ... RETURNING`:
+// MariaDB ("10.11.6-MariaDB-...") has it from 10.5, MySQL proper never did.
+pub fn supports_insert_returning(version: &str) -> bool {
+    if !version.contains("MariaDB") {
+        return false;
+    }
+    // Old handshakes prefix the real version with "5.5.5-"
+    let version = version.strip_prefix("5.5.5-").unwrap_or(version);
+    let mut parts = version
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|part| part.parse::<u32>().unwrap_or(0));
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    (major, minor) >= (10, 5)
+}
 
 impl DatabaseOperations for MySqlDatabase {
     type Row = MySqlRow;
@@ -119,21 +440,41 @@ impl DatabaseOperations for MySqlDatabase {
     type DatabaseType = sqlx::MySql;
     type ParameterBinder = MySqlParameterBinder;
 
+    fn binder(&self) -> Self::ParameterBinder {
+        MySqlParameterBinder
+    }
+
     async fn execute(
         &mut self,
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, sqlx::MySql>>>>,
         query: &str,
         params: Vec<&PyAny>,
+        prepared: bool,
     ) -> Result<u64, PyErr> {
-        let query_builder = MySqlParameterBinder.bind_parameters(query, params)?;
+        if !prepared {
+            ensure_no_parameters(&params)?;
+            let mut guard = transaction.lock().await;
+            let transaction = active(&mut guard)?;
+            let id = self.arm(transaction).await?;
+            let statement = sqlx::raw_sql(query).execute(&mut **transaction);
+            let Some(result) = self.bounded(statement).await else {
+                return Err(self.cancel(id, &mut guard).await);
+            };
+            return Ok(result.map_err(database_error)?.rows_affected());
+        }
+        let (sql, arguments) = MySqlParameterBinder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
         let mut guard = transaction.lock().await;
-        let transaction = guard.as_mut().unwrap();
-        let result = query_builder
-            .execute(&mut **transaction)
+        let transaction = active(&mut guard)?;
+        let id = self.arm(transaction).await?;
+        let Some(result) = self
+            .bounded(query_builder.execute(&mut **transaction))
             .await
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        else {
+            return Err(self.cancel(id, &mut guard).await);
+        };
 
-        Ok(result.rows_affected())
+        Ok(result.map_err(database_error)?.rows_affected())
     }
 
     async fn fetch_all(
@@ -142,14 +483,58 @@ impl DatabaseOperations for MySqlDatabase {
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
         query: &str,
         params: Vec<&PyAny>,
+        fetch_size: Option<usize>,
+        prepared: bool,
     ) -> Result<Vec<PyObject>, PyErr> {
-        let query_builder = MySqlParameterBinder.bind_parameters(query, params)?;
+        if !prepared {
+            ensure_no_parameters(&params)?;
+            let mut guard = transaction.lock().await;
+            let transaction = active(&mut guard)?;
+            let raw = sqlx::raw_sql(query);
+            let id = self.arm(transaction).await?;
+            let rows = match fetch_size {
+                Some(fetch_size) => {
+                    let stream = raw.fetch(&mut **transaction);
+                    let batches = fetch_in_batches(stream, fetch_size, |row| {
+                        MySqlParameterBinder.bind_result(py, row)
+                    });
+                    let Some(result) = self.bounded(batches).await else {
+                        return Err(self.cancel(id, &mut guard).await);
+                    };
+                    return result;
+                }
+                None => match self.bounded(raw.fetch_all(&mut **transaction)).await {
+                    Some(rows) => rows.map_err(database_error)?,
+                    None => return Err(self.cancel(id, &mut guard).await),
+                },
+            };
+            return rows
+                .iter()
+                .map(|row| MySqlParameterBinder.bind_result(py, row))
+                .collect();
+        }
+        let (sql, arguments) = MySqlParameterBinder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
         let mut guard = transaction.lock().await;
-        let transaction = guard.as_mut().unwrap();
-        let rows = query_builder
-            .fetch_all(&mut **transaction)
+        let transaction = active(&mut guard)?;
+        let id = self.arm(transaction).await?;
+        if let Some(fetch_size) = fetch_size {
+            let stream = query_builder.fetch(&mut **transaction);
+            let batches = fetch_in_batches(stream, fetch_size, |row| {
+                MySqlParameterBinder.bind_result(py, row)
+            });
+            let Some(result) = self.bounded(batches).await else {
+                return Err(self.cancel(id, &mut guard).await);
+            };
+            return result;
+        }
+        let Some(rows) = self
+            .bounded(query_builder.fetch_all(&mut **transaction))
             .await
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        else {
+            return Err(self.cancel(id, &mut guard).await);
+        };
+        let rows = rows.map_err(database_error)?;
 
         let result: Vec<PyObject> = rows
             .iter()
@@ -159,73 +544,155 @@ impl DatabaseOperations for MySqlDatabase {
         Ok(result)
     }
 
-    async fn stream_data(
+    async fn fetch_all_tuples(
         &mut self,
         py: Python<'_>,
-        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, sqlx::MySql>>>>,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
         query: &str,
         params: Vec<&PyAny>,
-        chunk_size: usize,
-    ) -> PyResult<Vec<Vec<PyObject>>> {
-        let query_builder = MySqlParameterBinder.bind_parameters(query, params)?;
-        let mut guard = transaction.lock().await.take().unwrap();
-        let mut stream = query_builder.fetch(&mut *guard);
-        let mut chunks: Vec<Vec<PyObject>> = Vec::new();
-        let mut current_chunk: Vec<PyObject> = Vec::new();
-
-        while let Some(row_result) = stream.next().await {
-            match row_result {
-                Ok(row) => {
-                    let row_data: PyObject = MySqlParameterBinder.bind_result(py, &row)?;
-                    current_chunk.push(row_data);
-
-                    if current_chunk.len() >= chunk_size {
-                        chunks.push(current_chunk);
-                        current_chunk = Vec::new();
-                    }
-                }
-                Err(e) => {
-                    return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                        e.to_string(),
-                    ));
-                }
-            }
-        }
+    ) -> PyResult<Vec<PyObject>> {
+        let (sql, arguments) = MySqlParameterBinder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
+        let mut guard = transaction.lock().await;
+        let transaction = active(&mut guard)?;
+        let id = self.arm(transaction).await?;
+        let Some(rows) = self
+            .bounded(query_builder.fetch_all(&mut **transaction))
+            .await
+        else {
+            return Err(self.cancel(id, &mut guard).await);
+        };
+        rows.map_err(database_error)?
+            .iter()
+            .map(|row| MySqlParameterBinder.bind_result_tuple(py, row))
+            .collect()
+    }
 
-        if !current_chunk.is_empty() {
-            chunks.push(current_chunk);
-        }
-        Ok(chunks)
+    async fn describe(
+        &mut self,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
+        query: &str,
+    ) -> PyResult<Vec<ColumnDescription>> {
+        let query = replace_placeholders(&DatabaseType::Mysql, query, |_| "?".to_string());
+        let mut guard = transaction.lock().await;
+        let describe = (&mut **active(&mut guard)?)
+            .describe(&query)
+            .await
+            .map_err(database_error)?;
+        Ok(column_descriptions(&describe))
+    }
+
+    async fn fetch_one(
+        &mut self,
+        py: Python<'_>,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
+        query: &str,
+        params: Vec<&PyAny>,
+    ) -> PyResult<PyObject> {
+        let (sql, arguments) = MySqlParameterBinder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
+        let mut guard = transaction.lock().await;
+        let transaction = active(&mut guard)?;
+        let id = self.arm(transaction).await?;
+        let Some(row) = self
+            .bounded(query_builder.fetch_one(&mut **transaction))
+            .await
+        else {
+            return Err(self.cancel(id, &mut guard).await);
+        };
+        MySqlParameterBinder.bind_result(py, &row.map_err(fetch_one_error)?)
+    }
+
+    async fn fetch_optional(
+        &mut self,
+        py: Python<'_>,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
+        query: &str,
+        params: Vec<&PyAny>,
+    ) -> PyResult<Option<PyObject>> {
+        let (sql, arguments) = MySqlParameterBinder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
+        let mut guard = transaction.lock().await;
+        let transaction = active(&mut guard)?;
+        let id = self.arm(transaction).await?;
+        let statement = query_builder.fetch_optional(&mut **transaction);
+        let Some(row) = self.bounded(statement).await else {
+            return Err(self.cancel(id, &mut guard).await);
+        };
+        row.map_err(database_error)?
+            .map(|row| MySqlParameterBinder.bind_result(py, &row))
+            .transpose()
     }
 
     async fn bulk_change(
         &mut self,
+        py: Python<'_>,
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
         query: &str,
         params: Vec<Vec<&PyAny>>,
         batch_size: usize,
-    ) -> Result<u64, PyErr> {
-        let mut total_affected: u64 = 0;
+        returning: bool,
+    ) -> Result<BulkChanges, PyErr> {
+        let mut changes = BulkChanges::default();
         let mut guard = transaction.lock().await;
-        let tx = guard.as_mut().ok_or_else(|| {
-            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("No active transaction")
-        })?;
-
-        // Process in batches
-        for chunk in params.chunks(batch_size) {
-            for param_set in chunk {
-                // Build query with current parameters
-                let query_builder =
-                    MySqlParameterBinder.bind_parameters(query, param_set.to_vec())?;
-
-                // Execute query and accumulate affected rows
-                let result = query_builder.execute(&mut **tx).await.map_err(|e| {
-                    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string())
-                })?;
+        let id = self.arm(active(&mut guard)?).await?;
 
-                total_affected += result.rows_affected();
+        for (statement, values) in bulk_statements(&DatabaseType::Mysql, query, &params, batch_size)
+        {
+            let (sql, arguments) = MySqlParameterBinder.bind_parameters(&statement, values)?;
+            let query_builder = sqlx::query_with(&sql, arguments);
+            let tx = active(&mut guard)?;
+            if returning {
+                let statement = (&mut **tx).fetch_many(query_builder).try_collect();
+                let Some(results) = self.bounded(statement).await else {
+                    return Err(self.cancel(id, &mut guard).await);
+                };
+                let results = results.map_err(database_error)?;
+                changes.add_results(results, MySqlQueryResult::rows_affected, |row| {
+                    MySqlParameterBinder.bind_result(py, row)
+                })?;
+                continue;
             }
+
+            // Execute query and accumulate affected rows
+            let Some(result) = self.bounded(query_builder.execute(&mut **tx)).await else {
+                return Err(self.cancel(id, &mut guard).await);
+            };
+
+            changes
+                .statement_counts
+                .push(result.map_err(database_error)?.rows_affected());
         }
-        Ok(total_affected)
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database(timeout: Option<Duration>) -> MySqlDatabase {
+        MySqlDatabase::new(Arc::new(MySqlConnectOptions::new()), timeout)
+    }
+
+    #[test]
+    fn statements_within_the_timeout_complete() {
+        let db = database(Some(Duration::from_secs(5)));
+        assert_eq!(
+            futures::executor::block_on(db.bounded(async { 7 })),
+            Some(7)
+        );
+        let db = database(None);
+        assert_eq!(
+            futures::executor::block_on(db.bounded(async { 7 })),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn statements_over_the_timeout_are_dropped() {
+        let db = database(Some(Duration::from_millis(20)));
+        let statement = futures::future::pending::<()>();
+        assert_eq!(futures::executor::block_on(db.bounded(statement)), None);
     }
 }
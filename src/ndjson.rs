@@ -0,0 +1,153 @@
+use std::sync::Arc;
+
+use pyo3::{
+    prelude::*,
+    types::{PyBytes, PyDict},
+};
+use sqlx::{Database, Executor, IntoArguments};
+use tokio::sync::Mutex;
+
+use super::{
+    db_trait::DynamicParameterBinder,
+    json::row_value_to_json,
+    row_stream::{RowPump, StreamBusy},
+};
+
+// One row as a JSON object line, keeping the column order of the row.
+fn encode_row(row: &PyAny) -> PyResult<Vec<u8>> {
+    let encoding_error =
+        |e: serde_json::Error| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string());
+    let mut line = vec![b'{'];
+    for (i, (column, value)) in row.downcast::<PyDict>()?.iter().enumerate() {
+        if i > 0 {
+            line.push(b',');
+        }
+        serde_json::to_writer(&mut line, column.extract::<&str>()?).map_err(encoding_error)?;
+        line.push(b':');
+        serde_json::to_writer(&mut line, &row_value_to_json(value)?).map_err(encoding_error)?;
+    }
+    line.extend_from_slice(b"}\n");
+    Ok(line)
+}
+
+// A read-only binary file object over a query's rows as newline-delimited JSON, one object per
+// row. Rows are pulled from the sqlx stream and encoded only as the caller reads, so at most
+// one encoded row is buffered ahead. The transaction is busy until the reader is exhausted or
+// closed.
+#[pyclass]
+pub struct NdjsonReader {
+    lines: Option<RowPump<Vec<u8>>>,
+    pending: Vec<u8>,
+}
+
+impl NdjsonReader {
+    pub fn new<DB, B>(
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, DB>>>>,
+        busy: &StreamBusy,
+        binder: B,
+        sql: String,
+        arguments: B::Arguments,
+    ) -> Self
+    where
+        DB: Database,
+        B: DynamicParameterBinder<Database = DB, Row = DB::Row> + Send + 'static,
+        B::Arguments: for<'q> IntoArguments<'q, DB> + Send + 'static,
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+    {
+        let lines = RowPump::new(transaction, busy, binder, sql, arguments, |py, row| {
+            encode_row(row.as_ref(py))
+        });
+        Self {
+            lines: Some(lines),
+            pending: Vec::new(),
+        }
+    }
+
+    // Next encoded row, or None once the rows are exhausted
+    fn next_line(&mut self) -> PyResult<Option<Vec<u8>>> {
+        let lines = self.lines.as_mut().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>("I/O operation on closed file")
+        })?;
+        lines.next()
+    }
+
+    // Fill `pending` with at least `size` bytes (everything for a negative size) or
+    // until the rows run out
+    fn fill(&mut self, size: isize) -> PyResult<()> {
+        while size < 0 || self.pending.len() < size as usize {
+            match self.next_line()? {
+                Some(line) => self.pending.extend_from_slice(&line),
+                None => break,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl NdjsonReader {
+    fn readable(&self) -> bool {
+        true
+    }
+
+    #[getter]
+    fn closed(&self) -> bool {
+        self.lines.is_none()
+    }
+
+    #[pyo3(signature = (size=-1))]
+    fn read(&mut self, py: Python<'_>, size: Option<isize>) -> PyResult<PyObject> {
+        let size = size.unwrap_or(-1);
+        self.fill(size)?;
+        let take = if size < 0 {
+            self.pending.len()
+        } else {
+            self.pending.len().min(size as usize)
+        };
+        let chunk: Vec<u8> = self.pending.drain(..take).collect();
+        Ok(PyBytes::new(py, &chunk).into())
+    }
+
+    fn readline(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        if !self.pending.contains(&b'\n') {
+            if let Some(line) = self.next_line()? {
+                self.pending.extend_from_slice(&line);
+            }
+        }
+        let take = match self.pending.iter().position(|b| *b == b'\n') {
+            Some(end) => end + 1,
+            None => self.pending.len(),
+        };
+        let line: Vec<u8> = self.pending.drain(..take).collect();
+        Ok(PyBytes::new(py, &line).into())
+    }
+
+    // Stops the query and releases the transaction, which stays open
+    fn close(&mut self) {
+        self.lines = None;
+        self.pending.clear();
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        let line = self.readline(py)?;
+        Ok(
+            if line.as_ref(py).downcast::<PyBytes>()?.as_bytes().is_empty() {
+                None
+            } else {
+                Some(line)
+            },
+        )
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(&mut self, _exc_type: &PyAny, _exc_value: &PyAny, _traceback: &PyAny) {
+        self.close();
+    }
+}
@@ -0,0 +1,112 @@
+use pyo3::{
+    prelude::*,
+    types::{IntoPyDict, PyBytes, PyFloat, PyString},
+};
+
+// numpy and pandas values are recognised by their type's module, so neither library is
+// imported (or required) unless the caller passes one of their objects.
+fn library_of(value: &PyAny) -> PyResult<Option<&'static str>> {
+    let module: &str = value.get_type().getattr("__module__")?.extract()?;
+    Ok(match module.split('.').next() {
+        Some("numpy") => Some("numpy"),
+        Some("pandas") => Some("pandas"),
+        _ => None,
+    })
+}
+
+fn type_name(value: &PyAny) -> PyResult<&str> {
+    value.get_type().name()
+}
+
+fn is_nan(value: &PyAny) -> bool {
+    value
+        .downcast::<PyFloat>()
+        .is_ok_and(|f| f.value().is_nan())
+}
+
+// Native Python value for a parameter taken from a numpy array or pandas object: numpy
+// scalars become their Python equivalents, datetime64/timedelta64 become datetime/timedelta,
+// pandas Timestamp/Timedelta become datetime/timedelta and the missing-value markers (NaN,
+// NaT, pd.NA) become None.
+fn to_native(value: &PyAny) -> PyResult<&PyAny> {
+    let py = value.py();
+    let native = match library_of(value)? {
+        None => value,
+        Some("pandas") => match type_name(value)? {
+            "NaTType" | "NAType" => return Ok(py.None().into_ref(py)),
+            "Timestamp" => value.call_method0("to_pydatetime")?,
+            "Timedelta" => value.call_method0("to_pytimedelta")?,
+            _ => value,
+        },
+        Some(_) => match type_name(value)? {
+            "datetime64" | "timedelta64" => {
+                let numpy = py.import("numpy")?;
+                if numpy.call_method1("isnat", (value,))?.is_true()? {
+                    return Ok(py.None().into_ref(py));
+                }
+                // `.item()` of a nanosecond value is a bare int; microseconds is the
+                // finest unit that maps onto datetime/timedelta
+                let unit = if type_name(value)? == "datetime64" {
+                    "datetime64[us]"
+                } else {
+                    "timedelta64[us]"
+                };
+                value
+                    .call_method1("astype", (unit,))?
+                    .call_method0("item")?
+            }
+            _ if value.hasattr("item")? => value.call_method0("item")?,
+            _ => value,
+        },
+    };
+    Ok(if is_nan(native) {
+        py.None().into_ref(py)
+    } else {
+        native
+    })
+}
+
+// One parameter set. Rows from numpy/pandas (an array row, a Series, a DataFrame tuple) are
+// iterated with their values converted by `to_native`; a bare scalar (an element of a 1-D
+// array) is a single parameter. Other rows keep the plain sequence handling, converting only
+// numpy scalars in them.
+fn param_row(row: &PyAny, from_library: bool) -> PyResult<Vec<&PyAny>> {
+    if from_library || library_of(row)?.is_some() {
+        if !row.hasattr("__iter__")?
+            || row.is_instance_of::<PyString>()
+            || row.is_instance_of::<PyBytes>()
+        {
+            return Ok(vec![to_native(row)?]);
+        }
+        return row.iter()?.map(|value| to_native(value?)).collect();
+    }
+    row.extract::<Vec<&PyAny>>()?
+        .into_iter()
+        .map(|value| match library_of(value)? {
+            Some(_) => to_native(value),
+            None => Ok(value),
+        })
+        .collect()
+}
+
+// Parameter sets for `bulk_change`: a sequence of rows, a 2-D numpy array, a pandas DataFrame
+// (one set per row, in column order) or a 1-D array/Series (one single-parameter set per value).
+pub fn param_rows(params: &PyAny) -> PyResult<Vec<Vec<&PyAny>>> {
+    match library_of(params)? {
+        Some("pandas") if type_name(params)? == "DataFrame" => {
+            let kwargs = [("index", false)].into_py_dict(params.py());
+            kwargs.set_item("name", params.py().None())?;
+            params
+                .call_method("itertuples", (), Some(kwargs))?
+                .iter()?
+                .map(|row| param_row(row?, true))
+                .collect()
+        }
+        Some(_) => params.iter()?.map(|row| param_row(row?, true)).collect(),
+        None => params
+            .extract::<Vec<&PyAny>>()?
+            .into_iter()
+            .map(|row| param_row(row, false))
+            .collect(),
+    }
+}
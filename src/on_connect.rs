@@ -0,0 +1,161 @@
+use std::sync::{Arc, Mutex};
+
+use futures_lite::future::block_on;
+use lazy_static::lazy_static;
+use pyo3::prelude::*;
+use sqlx::{mysql::MySqlConnection, postgres::PgConnection, sqlite::SqliteConnection};
+
+use super::{
+    config::TypeSettings,
+    db_trait::{DatabaseOperations, DynamicParameterBinder},
+    errors::database_error,
+    mysql::MySqlParameterBinder,
+    postgresql::{PostgresDatabase, PostgresParameterBinder},
+    sqlite::{SqliteDatabase, SqliteParameterBinder},
+};
+
+lazy_static! {
+    // Why the latest `on_connect` call failed, cleared when one succeeds. sqlx retries a
+    // failing `after_connect` until the acquire times out and then reports only the timeout.
+    static ref LAST_FAILURE: Mutex<Option<String>> = Mutex::new(None);
+}
+
+// The connection an `after_connect` hook is setting up. The hook's `&mut` borrow outlives the
+// callback, and the pointer is cleared as soon as the callback returns.
+enum Handle {
+    Postgres(*mut PgConnection, PostgresParameterBinder),
+    MySql(*mut MySqlConnection),
+    Sqlite(*mut SqliteConnection, SqliteParameterBinder),
+}
+
+// Handle on a freshly opened pooled connection, passed to `DatabaseConfig.on_connect`. It is
+// only usable while the callback runs; afterwards its methods raise RuntimeError.
+#[pyclass(unsendable)]
+pub struct NewConnection {
+    handle: Option<Handle>,
+}
+
+impl NewConnection {
+    fn handle(&self) -> PyResult<&Handle> {
+        self.handle.as_ref().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "The connection is only usable inside on_connect",
+            )
+        })
+    }
+}
+
+// The hook future is already being polled by an executor, so statements block on their own
+// (nestable) executor here rather than `futures::executor::block_on`, which refuses to nest.
+#[pymethods]
+impl NewConnection {
+    #[pyo3(signature = (query, params=Vec::new()))]
+    fn execute(&self, query: &str, params: Vec<&PyAny>) -> PyResult<u64> {
+        // SAFETY: see `Handle`; the pointer is live for as long as it is set
+        let result = match self.handle()? {
+            Handle::Postgres(conn, binder) => {
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let query = sqlx::query_with(&sql, arguments);
+                block_on(query.execute(unsafe { &mut **conn })).map(|r| r.rows_affected())
+            }
+            Handle::MySql(conn) => {
+                let (sql, arguments) = MySqlParameterBinder.bind_parameters(query, params)?;
+                let query = sqlx::query_with(&sql, arguments);
+                block_on(query.execute(unsafe { &mut **conn })).map(|r| r.rows_affected())
+            }
+            Handle::Sqlite(conn, binder) => {
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let query = sqlx::query_with(&sql, arguments);
+                block_on(query.execute(unsafe { &mut **conn })).map(|r| r.rows_affected())
+            }
+        };
+        result.map_err(database_error)
+    }
+
+    #[pyo3(signature = (query, params=Vec::new()))]
+    fn fetch_all(
+        &self,
+        py: Python<'_>,
+        query: &str,
+        params: Vec<&PyAny>,
+    ) -> PyResult<Vec<PyObject>> {
+        // SAFETY: see `Handle`; the pointer is live for as long as it is set
+        match self.handle()? {
+            Handle::Postgres(conn, binder) => {
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let query = sqlx::query_with(&sql, arguments);
+                let rows = block_on(query.fetch_all(unsafe { &mut **conn }));
+                let rows = rows.map_err(database_error)?;
+                rows.iter().map(|row| binder.bind_result(py, row)).collect()
+            }
+            Handle::MySql(conn) => {
+                let (sql, arguments) = MySqlParameterBinder.bind_parameters(query, params)?;
+                let query = sqlx::query_with(&sql, arguments);
+                let rows = block_on(query.fetch_all(unsafe { &mut **conn }));
+                let rows = rows.map_err(database_error)?;
+                rows.iter()
+                    .map(|row| MySqlParameterBinder.bind_result(py, row))
+                    .collect()
+            }
+            Handle::Sqlite(conn, binder) => {
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let query = sqlx::query_with(&sql, arguments);
+                let rows = block_on(query.fetch_all(unsafe { &mut **conn }));
+                let rows = rows.map_err(database_error)?;
+                rows.iter().map(|row| binder.bind_result(py, row)).collect()
+            }
+        }
+    }
+}
+
+// Call `callback` with a handle on `handle`'s connection. An exception fails the connection,
+// and with it the acquire that opened it.
+fn run(callback: &PyObject, handle: Handle) -> Result<(), sqlx::Error> {
+    let result = Python::with_gil(|py| {
+        let connection = Py::new(
+            py,
+            NewConnection {
+                handle: Some(handle),
+            },
+        )?;
+        let result = callback.call1(py, (connection.clone_ref(py),));
+        connection.borrow_mut(py).handle = None;
+        result.map(drop)
+    })
+    .map_err(|err| format!("on_connect failed: {}", err));
+    *LAST_FAILURE.lock().unwrap() = result.as_ref().err().cloned();
+    result.map_err(|failure| sqlx::Error::Configuration(failure.into()))
+}
+
+// A pool timeout caused by `on_connect` raising, reported as that failure instead.
+pub fn explain(error: sqlx::Error) -> sqlx::Error {
+    match error {
+        sqlx::Error::PoolTimedOut => match LAST_FAILURE.lock().unwrap().clone() {
+            Some(failure) => sqlx::Error::Configuration(failure.into()),
+            None => sqlx::Error::PoolTimedOut,
+        },
+        error => error,
+    }
+}
+
+pub fn run_postgres(
+    callback: &PyObject,
+    settings: &Arc<TypeSettings>,
+    conn: &mut PgConnection,
+) -> Result<(), sqlx::Error> {
+    let binder = PostgresDatabase::new(settings.clone()).binder();
+    run(callback, Handle::Postgres(conn, binder))
+}
+
+pub fn run_mysql(callback: &PyObject, conn: &mut MySqlConnection) -> Result<(), sqlx::Error> {
+    run(callback, Handle::MySql(conn))
+}
+
+pub fn run_sqlite(
+    callback: &PyObject,
+    settings: &Arc<TypeSettings>,
+    conn: &mut SqliteConnection,
+) -> Result<(), sqlx::Error> {
+    let binder = SqliteDatabase::new(settings.clone(), None).binder();
+    run(callback, Handle::Sqlite(conn, binder))
+}
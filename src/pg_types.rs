@@ -0,0 +1,761 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{
+        types::{Oid, PgRecordDecoder, PgRecordEncoder},
+        PgArgumentBuffer, PgTypeInfo, PgTypeKind, PgValueFormat, PgValueRef,
+    },
+    types::{Json, JsonValue, Uuid},
+    Decode, Encode, Postgres, Type, TypeInfo, ValueRef,
+};
+
+// Python `complex` is stored as the user-defined composite type
+//
+//     CREATE TYPE complex AS (re double precision, im double precision);
+//
+// Binding a `complex` targets that type by name, and any column of type `complex`
+// decodes back into a Python `complex`.
+pub struct PgComplex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Type<Postgres> for PgComplex {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("complex")
+    }
+}
+
+impl Encode<'_, Postgres> for PgComplex {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        let mut encoder = PgRecordEncoder::new(buf);
+        encoder.encode(self.re)?;
+        encoder.encode(self.im)?;
+        encoder.finish();
+        Ok(IsNull::No)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PgComplex {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let mut decoder = PgRecordDecoder::new(value)?;
+        let re = decoder.try_decode::<f64>()?;
+        let im = decoder.try_decode::<f64>()?;
+        Ok(PgComplex { re, im })
+    }
+}
+
+// A value of a type nothing else decodes, as the bytes the server sent: its text form under
+// the simple protocol, the type's binary send format in prepared results.
+pub struct PgRawValue(pub Vec<u8>);
+
+impl Type<Postgres> for PgRawValue {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("unknown")
+    }
+
+    fn compatible(_ty: &PgTypeInfo) -> bool {
+        true
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PgRawValue {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Self(value.as_bytes()?.to_vec()))
+    }
+}
+
+// The label of a user-defined enum value, sent as its text in both formats.
+pub struct PgEnumLabel(pub String);
+
+impl Type<Postgres> for PgEnumLabel {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("text")
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        matches!(base_type(ty).kind(), PgTypeKind::Enum(_))
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PgEnumLabel {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Self(value.as_str()?.to_string()))
+    }
+}
+
+// `numeric` NaN, which `rust_decimal::Decimal` cannot represent.
+pub struct PgNumericNaN;
+
+impl Type<Postgres> for PgNumericNaN {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("numeric")
+    }
+}
+
+impl Encode<'_, Postgres> for PgNumericNaN {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        // ndigits, weight, sign (0xC000 = NaN), dscale
+        buf.extend_from_slice(&0_i16.to_be_bytes());
+        buf.extend_from_slice(&0_i16.to_be_bytes());
+        buf.extend_from_slice(&0xC000_u16.to_be_bytes());
+        buf.extend_from_slice(&0_u16.to_be_bytes());
+        Ok(IsNull::No)
+    }
+}
+
+// A Python int too wide for bigint, bound as an exact `numeric` from its decimal text
+// (`str(n)`: an optional minus sign and digits).
+pub struct PgNumericInt(pub String);
+
+impl Type<Postgres> for PgNumericInt {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("numeric")
+    }
+}
+
+impl Encode<'_, Postgres> for PgNumericInt {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        let (sign, digits) = match self.0.strip_prefix('-') {
+            Some(digits) => (0x4000_u16, digits),
+            None => (0x0000, self.0.as_str()),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("invalid integer {:?}", self.0).into());
+        }
+        // base-10000 digits, most significant first; trailing zero digits are implied
+        let padded = format!("{}{}", "0".repeat((4 - digits.len() % 4) % 4), digits);
+        let mut groups: Vec<i16> = padded
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| chunk.iter().fold(0, |n, b| n * 10 + i16::from(b - b'0')))
+            .collect();
+        let weight = i16::try_from(groups.len() - 1)?;
+        while groups.last() == Some(&0) {
+            groups.pop();
+        }
+        buf.extend_from_slice(&i16::try_from(groups.len())?.to_be_bytes());
+        buf.extend_from_slice(&weight.to_be_bytes());
+        buf.extend_from_slice(&sign.to_be_bytes());
+        buf.extend_from_slice(&0_u16.to_be_bytes());
+        for group in groups {
+            buf.extend_from_slice(&group.to_be_bytes());
+        }
+        Ok(IsNull::No)
+    }
+}
+
+// `numeric` decoded to its exact decimal text form (as PostgreSQL prints it), including NaN
+// and the PostgreSQL 14+ infinities, which `rust_decimal::Decimal` cannot represent.
+pub struct PgNumericText(pub String);
+
+impl Type<Postgres> for PgNumericText {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("numeric")
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PgNumericText {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        if value.format() == PgValueFormat::Text {
+            return Ok(Self(value.as_str()?.to_string()));
+        }
+        let mut buf = value.as_bytes()?;
+        let ndigits = take_i16(&mut buf)?;
+        let weight = take_i16(&mut buf)? as i32;
+        let sign = take_i16(&mut buf)? as u16;
+        let dscale = take_i16(&mut buf)? as usize;
+        let digits = (0..ndigits)
+            .map(|_| take_i16(&mut buf))
+            .collect::<Result<Vec<_>, _>>()?;
+        let text = match sign {
+            0xC000 => return Ok(Self("NaN".to_string())),
+            0xD000 => return Ok(Self("Infinity".to_string())),
+            0xF000 => return Ok(Self("-Infinity".to_string())),
+            0x4000 => String::from("-"),
+            0x0000 => String::new(),
+            other => return Err(format!("invalid numeric sign 0x{:04x}", other).into()),
+        };
+        // base-10000 digits; digit `weight` is the last one before the decimal point
+        let digit = |index: i32| match usize::try_from(index) {
+            Ok(index) => digits.get(index).copied().unwrap_or(0),
+            Err(_) => 0,
+        };
+        let mut text = text;
+        if weight < 0 {
+            text.push('0');
+        }
+        for index in 0..=weight {
+            match index {
+                0 => text.push_str(&digit(0).to_string()),
+                _ => text.push_str(&format!("{:04}", digit(index))),
+            }
+        }
+        if dscale > 0 {
+            let mut fraction = String::with_capacity(dscale + 4);
+            let mut index = weight + 1;
+            while fraction.len() < dscale {
+                fraction.push_str(&format!("{:04}", digit(index)));
+                index += 1;
+            }
+            fraction.truncate(dscale);
+            text.push('.');
+            text.push_str(&fraction);
+        }
+        Ok(Self(text))
+    }
+}
+
+fn take_i16(buf: &mut &[u8]) -> Result<i16, BoxDynError> {
+    Ok(i16::from_be_bytes(take(buf, 2)?.try_into()?))
+}
+
+fn take<'r>(buf: &mut &'r [u8], len: usize) -> Result<&'r [u8], BoxDynError> {
+    if buf.len() < len {
+        return Err("unexpected end of array data".into());
+    }
+    let (head, tail) = buf.split_at(len);
+    *buf = tail;
+    Ok(head)
+}
+
+fn take_i32(buf: &mut &[u8]) -> Result<i32, BoxDynError> {
+    Ok(i32::from_be_bytes(take(buf, 4)?.try_into()?))
+}
+
+// A binary-format Postgres array split into its header and still-encoded elements
+// (`None` for NULL elements), stored in row-major order.
+pub struct BinaryArray<'r> {
+    pub element_oid: u32,
+    pub dims: Vec<usize>,
+    pub elements: Vec<Option<&'r [u8]>>,
+}
+
+pub fn parse_binary_array(mut buf: &[u8]) -> Result<BinaryArray<'_>, BoxDynError> {
+    let ndim = take_i32(&mut buf)?;
+    let _has_nulls = take_i32(&mut buf)?;
+    let element_oid = take_i32(&mut buf)? as u32;
+
+    let mut dims = Vec::with_capacity(ndim.max(0) as usize);
+    for _ in 0..ndim {
+        let len = take_i32(&mut buf)?;
+        let _lower_bound = take_i32(&mut buf)?;
+        dims.push(len.max(0) as usize);
+    }
+    let count = if dims.is_empty() {
+        0
+    } else {
+        dims.iter().product()
+    };
+
+    let mut elements = Vec::with_capacity(count);
+    for _ in 0..count {
+        let len = take_i32(&mut buf)?;
+        elements.push(if len < 0 {
+            None
+        } else {
+            Some(take(&mut buf, len as usize)?)
+        });
+    }
+    Ok(BinaryArray {
+        element_oid,
+        dims,
+        elements,
+    })
+}
+
+// `int2vector` and `oidvector` are catalog types (e.g. `pg_index.indkey`) that sqlx does
+// not map. On the wire they are one-dimensional arrays in binary format and
+// space-separated lists in text format.
+fn decode_vector<T, F>(value: PgValueRef<'_>, from_be: F) -> Result<Vec<T>, BoxDynError>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+    F: Fn(&[u8]) -> Result<T, BoxDynError>,
+{
+    match value.format() {
+        PgValueFormat::Binary => {
+            let array = parse_binary_array(value.as_bytes()?)?;
+            if array.dims.len() > 1 {
+                return Err(format!(
+                    "expected a one-dimensional vector, found {} dimensions",
+                    array.dims.len()
+                )
+                .into());
+            }
+            array
+                .elements
+                .into_iter()
+                .map(|element| from_be(element.ok_or("unexpected NULL vector element")?))
+                .collect()
+        }
+        PgValueFormat::Text => value
+            .as_str()?
+            .split_whitespace()
+            .map(|item| item.parse::<T>().map_err(Into::into))
+            .collect(),
+    }
+}
+
+pub struct PgInt2Vector(pub Vec<i16>);
+
+impl Type<Postgres> for PgInt2Vector {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_oid(Oid(22))
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PgInt2Vector {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        decode_vector(value, |b| Ok(i16::from_be_bytes(b.try_into()?))).map(PgInt2Vector)
+    }
+}
+
+pub struct PgOidVector(pub Vec<u32>);
+
+impl Type<Postgres> for PgOidVector {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_oid(Oid(30))
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PgOidVector {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        decode_vector(value, |b| Ok(u32::from_be_bytes(b.try_into()?))).map(PgOidVector)
+    }
+}
+
+// `tid` (e.g. `ctid`): a physical row location as (block number, tuple offset). Sent as
+// 4 + 2 big-endian bytes in binary format and as `(block,offset)` in text format.
+pub struct PgTid {
+    pub block: u32,
+    pub offset: u16,
+}
+
+impl Type<Postgres> for PgTid {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_oid(Oid(27))
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PgTid {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => {
+                let mut buf = value.as_bytes()?;
+                let block = u32::from_be_bytes(take(&mut buf, 4)?.try_into()?);
+                let offset = u16::from_be_bytes(take(&mut buf, 2)?.try_into()?);
+                Ok(PgTid { block, offset })
+            }
+            PgValueFormat::Text => {
+                let text = value.as_str()?;
+                let (block, offset) = text
+                    .strip_prefix('(')
+                    .and_then(|rest| rest.strip_suffix(')'))
+                    .and_then(|inner| inner.split_once(','))
+                    .ok_or_else(|| format!("invalid tid {:?}", text))?;
+                Ok(PgTid {
+                    block: block.trim().parse()?,
+                    offset: offset.trim().parse()?,
+                })
+            }
+        }
+    }
+}
+
+// Element types supported by `PgNdArray`, keyed by element OID.
+const BOOL_OID: u32 = 16;
+const INT8_OID: u32 = 20;
+const INT2_OID: u32 = 21;
+const INT4_OID: u32 = 23;
+const TEXT_OID: u32 = 25;
+const FLOAT4_OID: u32 = 700;
+const FLOAT8_OID: u32 = 701;
+const BPCHAR_OID: u32 = 1042;
+const VARCHAR_OID: u32 = 1043;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PgArrayElement {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+// An array of any dimensionality (`integer[][]`, `double precision[][][]`, ...) over the
+// scalar element types above. `dims` holds the length of each dimension and `elements` the
+// values in row-major order; an empty array has no dimensions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgNdArray {
+    pub dims: Vec<usize>,
+    pub elements: Vec<PgArrayElement>,
+}
+
+fn supported_element(oid: u32) -> bool {
+    matches!(
+        oid,
+        BOOL_OID
+            | INT8_OID
+            | INT2_OID
+            | INT4_OID
+            | TEXT_OID
+            | FLOAT4_OID
+            | FLOAT8_OID
+            | BPCHAR_OID
+            | VARCHAR_OID
+    )
+}
+
+// The type a domain (`CREATE DOMAIN positive_int AS integer ...`) is declared over,
+// following domains of domains; the type itself for anything else.
+fn base_type(ty: &PgTypeInfo) -> &PgTypeInfo {
+    match ty.kind() {
+        PgTypeKind::Domain(base) => base_type(base),
+        _ => ty,
+    }
+}
+
+fn element_oid(ty: &PgTypeInfo) -> Option<u32> {
+    match base_type(ty).kind() {
+        PgTypeKind::Array(element) => base_type(element).oid().map(|oid| oid.0),
+        _ => None,
+    }
+}
+
+fn decode_binary_element(oid: u32, bytes: &[u8]) -> Result<PgArrayElement, BoxDynError> {
+    Ok(match oid {
+        BOOL_OID => PgArrayElement::Bool(bytes.first().ok_or("empty bool element")? != &0),
+        INT2_OID => PgArrayElement::Int(i16::from_be_bytes(bytes.try_into()?).into()),
+        INT4_OID => PgArrayElement::Int(i32::from_be_bytes(bytes.try_into()?).into()),
+        INT8_OID => PgArrayElement::Int(i64::from_be_bytes(bytes.try_into()?)),
+        FLOAT4_OID => PgArrayElement::Float(f32::from_be_bytes(bytes.try_into()?).into()),
+        FLOAT8_OID => PgArrayElement::Float(f64::from_be_bytes(bytes.try_into()?)),
+        _ => PgArrayElement::Text(std::str::from_utf8(bytes)?.to_owned()),
+    })
+}
+
+fn decode_text_element(oid: u32, text: &str) -> Result<PgArrayElement, BoxDynError> {
+    Ok(match oid {
+        BOOL_OID => PgArrayElement::Bool(text == "t" || text == "true"),
+        INT2_OID | INT4_OID | INT8_OID => PgArrayElement::Int(text.parse()?),
+        FLOAT4_OID | FLOAT8_OID => PgArrayElement::Float(text.parse()?),
+        _ => PgArrayElement::Text(text.to_owned()),
+    })
+}
+
+// Parse the text form `{{1,2},{3,NULL}}` (optionally prefixed with explicit bounds such as
+// `[0:1]=`) into dimensions and row-major elements.
+fn parse_text_array(
+    text: &str,
+    oid: u32,
+) -> Result<(Vec<usize>, Vec<PgArrayElement>), BoxDynError> {
+    let text = match text.find("]={") {
+        Some(pos) if text.starts_with('[') => &text[pos + 2..],
+        _ => text,
+    };
+    let chars: Vec<char> = text.chars().collect();
+    let mut dims: Vec<usize> = Vec::new();
+    let mut counts: Vec<usize> = Vec::new();
+    let mut elements = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '{' => {
+                counts.push(0);
+                if dims.len() < counts.len() {
+                    dims.push(0);
+                }
+                i += 1;
+            }
+            '}' => {
+                let depth = counts.len() - 1;
+                let count = counts.pop().ok_or("unbalanced array literal")?;
+                if dims[depth] == 0 {
+                    dims[depth] = count;
+                } else if dims[depth] != count {
+                    return Err("multidimensional arrays must have matching extents".into());
+                }
+                if let Some(parent) = counts.last_mut() {
+                    *parent += 1;
+                }
+                i += 1;
+            }
+            ',' | ' ' => i += 1,
+            _ => {
+                let (element, quoted) = if chars[i] == '"' {
+                    let mut value = String::new();
+                    i += 1;
+                    while i < chars.len() && chars[i] != '"' {
+                        if chars[i] == '\\' {
+                            i += 1;
+                        }
+                        if let Some(c) = chars.get(i) {
+                            value.push(*c);
+                        }
+                        i += 1;
+                    }
+                    i += 1;
+                    (value, true)
+                } else {
+                    let start = i;
+                    while i < chars.len() && !matches!(chars[i], ',' | '}') {
+                        i += 1;
+                    }
+                    (chars[start..i].iter().collect::<String>(), false)
+                };
+                elements.push(if !quoted && element.eq_ignore_ascii_case("NULL") {
+                    PgArrayElement::Null
+                } else {
+                    decode_text_element(oid, &element)?
+                });
+                *counts.last_mut().ok_or("array element outside braces")? += 1;
+            }
+        }
+    }
+
+    if dims.first() == Some(&0) {
+        dims.clear();
+    }
+    Ok((dims, elements))
+}
+
+impl Type<Postgres> for PgNdArray {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_oid(Oid(1022))
+    }
+
+    // enum labels decode like text elements
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        element_oid(ty).is_some_and(supported_element)
+            || matches!(base_type(ty).kind(), PgTypeKind::Array(element)
+                if matches!(base_type(element).kind(), PgTypeKind::Enum(_)))
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PgNdArray {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => {
+                let array = parse_binary_array(value.as_bytes()?)?;
+                // The header names the element's own type, which for an array of a
+                // domain is the domain rather than the base type it encodes as.
+                let oid = element_oid(&value.type_info()).unwrap_or(array.element_oid);
+                let elements = array
+                    .elements
+                    .into_iter()
+                    .map(|element| match element {
+                        Some(bytes) => decode_binary_element(oid, bytes),
+                        None => Ok(PgArrayElement::Null),
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(PgNdArray {
+                    dims: array.dims,
+                    elements,
+                })
+            }
+            PgValueFormat::Text => {
+                let oid = element_oid(&value.type_info()).unwrap_or(TEXT_OID);
+                let (dims, elements) = parse_text_array(value.as_str()?, oid)?;
+                Ok(PgNdArray { dims, elements })
+            }
+        }
+    }
+}
+
+impl PgNdArray {
+    // Element OID and array type OID for binding, inferred from the non-NULL elements:
+    // any float makes the array `float8[]`, otherwise all-integer is `int8[]`, all-bool is
+    // `bool[]` and anything else is `text[]`.
+    fn array_oids(&self) -> (u32, u32) {
+        let mut has_float = false;
+        let mut has_int = false;
+        let mut has_bool = false;
+        for element in &self.elements {
+            match element {
+                PgArrayElement::Float(_) => has_float = true,
+                PgArrayElement::Int(_) => has_int = true,
+                PgArrayElement::Bool(_) => has_bool = true,
+                PgArrayElement::Text(_) => return (TEXT_OID, 1009),
+                PgArrayElement::Null => {}
+            }
+        }
+        match (has_float, has_int, has_bool) {
+            (true, _, false) => (FLOAT8_OID, 1022),
+            (false, true, false) => (INT8_OID, 1016),
+            (false, false, true) => (BOOL_OID, 1000),
+            _ => (TEXT_OID, 1009),
+        }
+    }
+}
+
+impl Encode<'_, Postgres> for PgNdArray {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        let (element_oid, _) = self.array_oids();
+        let has_nulls = self.elements.contains(&PgArrayElement::Null);
+
+        buf.extend_from_slice(&(self.dims.len() as i32).to_be_bytes());
+        buf.extend_from_slice(&(has_nulls as i32).to_be_bytes());
+        buf.extend_from_slice(&element_oid.to_be_bytes());
+        for dim in &self.dims {
+            buf.extend_from_slice(&(*dim as i32).to_be_bytes());
+            buf.extend_from_slice(&1_i32.to_be_bytes());
+        }
+
+        for element in &self.elements {
+            let bytes: Vec<u8> = match (element, element_oid) {
+                (PgArrayElement::Null, _) => {
+                    buf.extend_from_slice(&(-1_i32).to_be_bytes());
+                    continue;
+                }
+                (PgArrayElement::Bool(v), BOOL_OID) => vec![*v as u8],
+                (PgArrayElement::Int(v), INT8_OID) => v.to_be_bytes().to_vec(),
+                (PgArrayElement::Int(v), FLOAT8_OID) => (*v as f64).to_be_bytes().to_vec(),
+                (PgArrayElement::Float(v), FLOAT8_OID) => v.to_be_bytes().to_vec(),
+                (PgArrayElement::Text(v), _) => v.as_bytes().to_vec(),
+                (PgArrayElement::Bool(v), _) => v.to_string().into_bytes(),
+                (PgArrayElement::Int(v), _) => v.to_string().into_bytes(),
+                (PgArrayElement::Float(v), _) => v.to_string().into_bytes(),
+            };
+            buf.extend_from_slice(&(bytes.len() as i32).to_be_bytes());
+            buf.extend_from_slice(&bytes);
+        }
+        Ok(IsNull::No)
+    }
+
+    fn produces(&self) -> Option<PgTypeInfo> {
+        Some(PgTypeInfo::with_oid(Oid(self.array_oids().1)))
+    }
+}
+
+// A NULL of the array type a `PgNdArray` binds as (see `PgNdArray::null`)
+pub struct PgNdArrayNull(u32);
+
+impl PgNdArray {
+    pub fn null(&self) -> PgNdArrayNull {
+        PgNdArrayNull(self.array_oids().1)
+    }
+}
+
+impl Type<Postgres> for PgNdArrayNull {
+    fn type_info() -> PgTypeInfo {
+        PgNdArray::type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for PgNdArrayNull {
+    fn encode_by_ref(&self, _buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        Ok(IsNull::Yes)
+    }
+
+    fn produces(&self) -> Option<PgTypeInfo> {
+        Some(PgTypeInfo::with_oid(Oid(self.0)))
+    }
+}
+
+// A composite (`CREATE TYPE ... AS (...)`) value as (attribute name, value) pairs in
+// declaration order. Attribute names and types come from the column's type information,
+// which sqlx resolves for prepared statements only; other results never reach this decoder.
+pub struct PgRecord(pub Vec<(String, PgField)>);
+
+// One attribute of a `PgRecord`
+pub enum PgField {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    // text-like types, enum labels, json/jsonb documents and any attribute of a text-format
+    // record, which carries no per-attribute types
+    Text(String),
+    Numeric(String),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Timestamp(NaiveDateTime),
+    TimestampTz(DateTime<Utc>),
+    Uuid(Uuid),
+    Bytes(Vec<u8>),
+    Array(PgNdArray),
+    Record(PgRecord),
+}
+
+impl Type<Postgres> for PgRecord {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("record")
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        matches!(base_type(ty).kind(), PgTypeKind::Composite(_))
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PgRecord {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let ty = base_type(&value.type_info()).clone();
+        let PgTypeKind::Composite(attributes) = ty.kind() else {
+            return Err(format!("{} is not a composite type", TypeInfo::name(&ty)).into());
+        };
+        let mut decoder = PgRecordDecoder::new(value)?;
+        let fields = attributes
+            .iter()
+            .map(|(name, _)| Ok((name.clone(), decoder.try_decode::<PgField>()?)))
+            .collect::<Result<_, BoxDynError>>()?;
+        Ok(PgRecord(fields))
+    }
+}
+
+impl Type<Postgres> for PgField {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("unknown")
+    }
+
+    // Checked per attribute type in `decode`
+    fn compatible(_ty: &PgTypeInfo) -> bool {
+        true
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PgField {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        if value.is_null() {
+            return Ok(PgField::Null);
+        }
+        let ty = base_type(&value.type_info()).clone();
+        if value.format() == PgValueFormat::Text && ty.oid() == Some(Oid(0)) {
+            return Ok(PgField::Text(value.as_str()?.to_owned()));
+        }
+        Ok(match (ty.kind(), TypeInfo::name(&ty)) {
+            (PgTypeKind::Composite(_), _) => PgField::Record(PgRecord::decode(value)?),
+            (PgTypeKind::Enum(_), _) => PgField::Text(value.as_str()?.to_owned()),
+            (PgTypeKind::Array(_), _) if PgNdArray::compatible(&ty) => {
+                PgField::Array(PgNdArray::decode(value)?)
+            }
+            (_, "BOOL") => PgField::Bool(decode::<bool>(value)?),
+            (_, "INT2") => PgField::Int(decode::<i16>(value)?.into()),
+            (_, "INT4") => PgField::Int(decode::<i32>(value)?.into()),
+            (_, "INT8") => PgField::Int(decode::<i64>(value)?),
+            (_, "FLOAT4") => PgField::Float(decode::<f32>(value)?.into()),
+            (_, "FLOAT8") => PgField::Float(decode::<f64>(value)?),
+            (_, "NUMERIC") => PgField::Numeric(decode::<PgNumericText>(value)?.0),
+            (_, "DATE") => PgField::Date(decode::<NaiveDate>(value)?),
+            (_, "TIME") => PgField::Time(decode::<NaiveTime>(value)?),
+            (_, "TIMESTAMP") => PgField::Timestamp(decode::<NaiveDateTime>(value)?),
+            (_, "TIMESTAMPTZ") => PgField::TimestampTz(decode::<DateTime<Utc>>(value)?),
+            (_, "UUID") => PgField::Uuid(decode::<Uuid>(value)?),
+            (_, "BYTEA") => PgField::Bytes(decode::<Vec<u8>>(value)?),
+            (_, "JSON" | "JSONB") => PgField::Text(decode::<Json<JsonValue>>(value)?.0.to_string()),
+            (_, "TEXT" | "VARCHAR" | "CHAR" | "NAME" | "CITEXT") => {
+                PgField::Text(value.as_str()?.to_owned())
+            }
+            _ if value.format() == PgValueFormat::Text => PgField::Text(value.as_str()?.to_owned()),
+            (_, name) => {
+                return Err(format!("unsupported composite attribute type {}", name).into())
+            }
+        })
+    }
+}
+
+fn decode<'r, T: Decode<'r, Postgres>>(value: PgValueRef<'r>) -> Result<T, BoxDynError> {
+    T::decode(value)
+}
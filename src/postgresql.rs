@@ -1,51 +1,185 @@
-use std::sync::Arc;
+use std::{borrow::Cow, ops::Bound, sync::Arc};
 
-use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
-use futures::StreamExt;
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use futures::TryStreamExt;
 use pyo3::{
     prelude::*,
     types::{
-        PyBool, PyDate, PyDateAccess, PyDateTime, PyDict, PyFloat, PyInt, PyList, PyString, PyTime,
-        PyTimeAccess,
+        timezone_utc, PyBool, PyByteArray, PyBytes, PyComplex, PyDate, PyDateAccess, PyDateTime,
+        PyDelta, PyDeltaAccess, PyDict, PyFloat, PyInt, PyList, PyString, PyTime, PyTimeAccess,
+        PyTuple, PyTzInfo,
     },
 };
-use serde_json::{from_str, to_string};
+use rust_decimal::Decimal;
+use serde_json::to_string;
 use sqlx::{
-    postgres::{PgArguments, PgQueryResult, PgRow},
-    types::{Json, JsonValue},
-    Column, Row, ValueRef,
+    postgres::{
+        types::{PgInterval, PgRange},
+        PgArguments, PgConnection, PgQueryResult, PgRow, PgTypeKind, PgValueFormat,
+    },
+    types::{ipnetwork::IpNetwork, mac_address::MacAddress, Json, JsonValue, Uuid},
+    Column, Connection, Execute, Executor, Row, TypeInfo, ValueRef,
 };
 use tokio::sync::Mutex;
 
-use super::db_trait::{DatabaseOperations, DynamicParameterBinder};
-
-pub struct PostgresParameterBinder;
+use super::{
+    composite::Composite,
+    config::{DatabaseType, DateBinding, RangeBinding, TypeOverride, TypeSettings},
+    db_trait::{
+        active, column_descriptions, ensure_no_parameters, fetch_in_batches, BulkChanges,
+        ColumnDescription, DatabaseOperations, DynamicParameterBinder,
+    },
+    enum_value::EnumValue,
+    errors::{database_error, encode_error, fetch_one_error},
+    json::{py_to_json, record_to_json},
+    pg_types::{
+        PgArrayElement, PgComplex, PgEnumLabel, PgField, PgInt2Vector, PgNdArray, PgNumericInt,
+        PgNumericNaN, PgNumericText, PgOidVector, PgRawValue, PgRecord, PgTid,
+    },
+    query_builder::{
+        bulk_statements, cast_typed_parameters, check_parameter_count, expand_tuple_parameters,
+        is_wide_int, uuid_string,
+    },
+    range::Range,
+};
 
-impl DynamicParameterBinder for PostgresParameterBinder {
-    type Arguments = PgArguments;
-    type Database = sqlx::Postgres;
-    type Row = PgRow;
+pub struct PostgresParameterBinder {
+    settings: Arc<TypeSettings>,
+}
 
-    fn bind_parameters<'q>(
+impl PostgresParameterBinder {
+    // A None bound on its own is a text NULL, which PostgreSQL will not assign to e.g. an
+    // integer or uuid column. `null_hints` gives, per position, a non-None value from another
+    // parameter set of the same batch; the NULL is then bound with that value's type, so every
+    // row of a batch prepares the statement with the same parameter types.
+    fn bind_with_null_hints<'q>(
         &self,
         query: &'q str,
         params: Vec<&PyAny>,
-    ) -> Result<sqlx::query::Query<'q, Self::Database, PgArguments>, PyErr> {
-        let mut query_builder = sqlx::query(query);
+        null_hints: &[Option<&PyAny>],
+    ) -> PyResult<(Cow<'q, str>, PgArguments)> {
+        check_parameter_count(&DatabaseType::Postgres, query, params.len())?;
+        // positions no longer line up once tuples are expanded
+        let (query, params, null_hints) =
+            match expand_tuple_parameters(&DatabaseType::Postgres, query, &params)? {
+                Some((expanded, leaves)) => (Cow::Owned(expanded), leaves, &[][..]),
+                None => (Cow::Borrowed(query), params, null_hints),
+            };
+        // a NULL takes its hint's place so every parameter set of a batch gets the same SQL
+        let typed: Vec<&PyAny> = params
+            .iter()
+            .enumerate()
+            .map(
+                |(index, param)| match null_hints.get(index).copied().flatten() {
+                    Some(sample) if param.is_none() => sample,
+                    _ => *param,
+                },
+            )
+            .collect();
+        let query = match cast_typed_parameters(&query, &typed, self.settings.uuid_strings)? {
+            Some(cast) => Cow::Owned(cast),
+            None => query,
+        };
+        let mut query_builder = sqlx::query(&query);
 
-        for param in params {
+        for (index, param) in params.into_iter().enumerate() {
             query_builder = match param {
-                p if p.is_none() => query_builder.bind(None::<String>),
-                p if p.is_instance_of::<PyString>() => query_builder.bind(p.extract::<String>()?),
-                p if p.is_instance_of::<PyInt>() => query_builder.bind(p.extract::<i64>()?),
-                p if p.is_instance_of::<PyFloat>() => query_builder.bind(p.extract::<f64>()?),
+                p if p.is_none() => match null_hints.get(index).copied().flatten() {
+                    Some(sample) => self.bind_typed_null(query_builder, sample)?,
+                    None => query_builder.bind(None::<String>),
+                },
+                // its placeholder was cast to uuid above
+                p if p.is_instance_of::<PyString>() => match self.string_uuid(p) {
+                    Some(uuid) => query_builder.bind(uuid),
+                    None => query_builder.bind(p.extract::<String>()?),
+                },
+                p if p.is_instance_of::<PyBytes>() => {
+                    query_builder.bind(p.downcast::<PyBytes>()?.as_bytes().to_vec())
+                }
+                p if p.is_instance_of::<PyByteArray>() => {
+                    query_builder.bind(p.downcast::<PyByteArray>()?.to_vec())
+                }
+                // bool before int: `True` is also an `int`
                 p if p.is_instance_of::<PyBool>() => query_builder.bind(p.extract::<bool>()?),
+                // past bigint's range as an exact numeric; its placeholder was cast above
+                p if p.is_instance_of::<PyInt>() => match p.extract::<i64>() {
+                    Ok(value) => query_builder.bind(value),
+                    Err(_) => query_builder.bind(PgNumericInt(p.str()?.to_string())),
+                },
+                p if p.is_instance_of::<PyFloat>() => query_builder.bind(p.extract::<f64>()?),
+                // aware datetimes bind as timestamptz (converted to UTC), naive ones as timestamp
+                p if p.is_instance_of::<PyDateTime>() && !is_naive(p)? => {
+                    query_builder.bind(extract_utc_datetime(p)?)
+                }
                 p if p.is_instance_of::<PyDateTime>() => query_builder.bind(extract_datetime(p)?),
-                p if p.is_instance_of::<PyDate>() => query_builder.bind(extract_date(p)?),
+                p if p.is_instance_of::<PyDate>() => match self.settings.date_binding {
+                    DateBinding::Date => query_builder.bind(extract_date(p)?),
+                    DateBinding::Timestamp => {
+                        query_builder.bind(extract_date(p)?.and_time(NaiveTime::MIN))
+                    }
+                },
                 p if p.is_instance_of::<PyTime>() => query_builder.bind(extract_time(p)?),
+                p if p.is_instance_of::<PyDelta>() => {
+                    query_builder.bind(timedelta_to_interval(p.downcast()?))
+                }
+                p if p.is_instance_of::<PyComplex>() => {
+                    let c: &PyComplex = p.downcast()?;
+                    query_builder.bind(PgComplex {
+                        re: c.real(),
+                        im: c.imag(),
+                    })
+                }
+                // the placeholder was wrapped in jsonb_populate_record above
+                p if p.is_instance_of::<Composite>() => {
+                    let fields = p.extract::<PyRef<Composite>>()?.fields.clone();
+                    query_builder.bind(Json(record_to_json(fields.as_ref(p.py()))?))
+                }
+                // the placeholder was cast to the enum type above
+                p if p.is_instance_of::<EnumValue>() => {
+                    query_builder.bind(p.extract::<PyRef<EnumValue>>()?.label.clone())
+                }
                 p if p.is_instance_of::<PyDict>() || p.is_instance_of::<PyList>() => {
-                    let json_value = from_str(&p.to_string()).unwrap_or(JsonValue::Null);
-                    query_builder.bind(Json(json_value))
+                    if let Some(blobs) = bytes_list(p)? {
+                        query_builder.bind(blobs)
+                    } else if let Some(intervals) = interval_list(p)? {
+                        query_builder.bind(intervals)
+                    } else if let Some(array) = nested_list_to_array(p)? {
+                        query_builder.bind(array)
+                    } else if let Some(array) = flat_list_to_array(p)? {
+                        query_builder.bind(array)
+                    } else {
+                        query_builder.bind(Json(py_to_json(p)?))
+                    }
+                }
+                p if is_uuid(p)? => query_builder.bind(extract_uuid(p)?),
+                p if is_ip_address(p)? => query_builder.bind(extract_ip_network(p)?),
+                p if is_decimal(p)? => match extract_decimal(p)? {
+                    Some(value) => query_builder.bind(value),
+                    None => query_builder.bind(PgNumericNaN),
+                },
+                p if p.is_instance_of::<Range>() => {
+                    bind_range(p.py(), query_builder, &p.extract()?, false)?
+                }
+                p if is_range(p)? => match self.settings.range_binding {
+                    RangeBinding::Array => query_builder.bind(p.extract::<Vec<i64>>()?),
+                    RangeBinding::Range => {
+                        let (start, end) = extract_range(p)?;
+                        // int4range when both bounds fit, int8range otherwise
+                        match (i32::try_from(start), i32::try_from(end)) {
+                            (Ok(start), Ok(end)) => query_builder.bind(PgRange::from(start..end)),
+                            _ => query_builder.bind(PgRange::from(start..end)),
+                        }
+                    }
+                },
+                p if is_array_iterable(p) => {
+                    let items = materialize_iterable(p)?;
+                    if let Some(blobs) = bytes_list(items)? {
+                        query_builder.bind(blobs)
+                    } else if let Some(intervals) = interval_list(items)? {
+                        query_builder.bind(intervals)
+                    } else {
+                        query_builder.bind(iterable_to_array(items)?)
+                    }
                 }
                 _ => {
                     return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
@@ -55,27 +189,157 @@ impl DynamicParameterBinder for PostgresParameterBinder {
                 }
             };
         }
-        Ok(query_builder)
+        // The query only collects the arguments; the caller runs them with the SQL returned
+        // alongside
+        let arguments = query_builder
+            .take_arguments()
+            .map_err(encode_error)?
+            .unwrap_or_default();
+        Ok((query, arguments))
+    }
+
+    // With `uuid_strings`, the UUID a str parameter parses as
+    fn string_uuid(&self, param: &PyAny) -> Option<Uuid> {
+        match self.settings.uuid_strings {
+            true => uuid_string(param),
+            false => None,
+        }
+    }
+
+    // A NULL of the type `sample` binds as, with the checks in the order of
+    // `bind_with_null_hints`
+    fn bind_typed_null<'q>(
+        &self,
+        query_builder: PgQuery<'q>,
+        sample: &PyAny,
+    ) -> PyResult<PgQuery<'q>> {
+        Ok(match sample {
+            s if s.is_instance_of::<PyString>() => match self.string_uuid(s) {
+                Some(_) => query_builder.bind(None::<Uuid>),
+                None => query_builder.bind(None::<String>),
+            },
+            s if s.is_instance_of::<PyBytes>() || s.is_instance_of::<PyByteArray>() => {
+                query_builder.bind(None::<Vec<u8>>)
+            }
+            s if s.is_instance_of::<PyBool>() => query_builder.bind(None::<bool>),
+            s if is_wide_int(s) => query_builder.bind(None::<PgNumericInt>),
+            s if s.is_instance_of::<PyInt>() => query_builder.bind(None::<i64>),
+            s if s.is_instance_of::<PyFloat>() => query_builder.bind(None::<f64>),
+            s if s.is_instance_of::<PyDateTime>() && !is_naive(s)? => {
+                query_builder.bind(None::<DateTime<Utc>>)
+            }
+            s if s.is_instance_of::<PyDateTime>() => query_builder.bind(None::<NaiveDateTime>),
+            s if s.is_instance_of::<PyDate>() => match self.settings.date_binding {
+                DateBinding::Date => query_builder.bind(None::<NaiveDate>),
+                DateBinding::Timestamp => query_builder.bind(None::<NaiveDateTime>),
+            },
+            s if s.is_instance_of::<PyTime>() => query_builder.bind(None::<NaiveTime>),
+            s if s.is_instance_of::<PyDelta>() => query_builder.bind(None::<PgInterval>),
+            s if s.is_instance_of::<PyComplex>() => query_builder.bind(None::<PgComplex>),
+            s if s.is_instance_of::<Composite>() => query_builder.bind(None::<Json<JsonValue>>),
+            // the placeholder is cast to the enum type as for the sample
+            s if s.is_instance_of::<EnumValue>() => query_builder.bind(None::<String>),
+            s if s.is_instance_of::<PyDict>() || s.is_instance_of::<PyList>() => {
+                if bytes_list(s)?.is_some() {
+                    query_builder.bind(None::<Vec<Option<Vec<u8>>>>)
+                } else if interval_list(s)?.is_some() {
+                    query_builder.bind(None::<Vec<PgInterval>>)
+                } else if let Some(array) = nested_list_to_array(s)? {
+                    query_builder.bind(array.null())
+                } else if let Some(array) = flat_list_to_array(s)? {
+                    query_builder.bind(array.null())
+                } else {
+                    query_builder.bind(None::<Json<JsonValue>>)
+                }
+            }
+            s if is_uuid(s)? => query_builder.bind(None::<Uuid>),
+            s if is_ip_address(s)? => query_builder.bind(None::<IpNetwork>),
+            s if is_decimal(s)? => query_builder.bind(None::<Decimal>),
+            s if s.is_instance_of::<Range>() => {
+                bind_range(s.py(), query_builder, &s.extract()?, true)?
+            }
+            s if is_range(s)? => match self.settings.range_binding {
+                RangeBinding::Array => query_builder.bind(None::<Vec<i64>>),
+                RangeBinding::Range => {
+                    let (start, end) = extract_range(s)?;
+                    match (i32::try_from(start), i32::try_from(end)) {
+                        (Ok(_), Ok(_)) => query_builder.bind(None::<PgRange<i32>>),
+                        _ => query_builder.bind(None::<PgRange<i64>>),
+                    }
+                }
+            },
+            s if is_array_iterable(s) => {
+                let items = materialize_iterable(s)?;
+                if bytes_list(items)?.is_some() {
+                    query_builder.bind(None::<Vec<Option<Vec<u8>>>>)
+                } else if interval_list(items)?.is_some() {
+                    query_builder.bind(None::<Vec<PgInterval>>)
+                } else {
+                    query_builder.bind(iterable_to_array(items)?.null())
+                }
+            }
+            _ => query_builder.bind(None::<String>),
+        })
+    }
+
+    fn column_value(&self, py: Python<'_>, row: &PgRow, index: usize) -> PyResult<PyObject> {
+        Ok(match row.try_get_raw(index) {
+            Ok(val) if val.is_null() => py.None(),
+            Ok(_) => {
+                let value = extract_column_value(py, row, index, self.settings.strict_types)?;
+                if self.settings.type_overrides.is_empty() {
+                    return Ok(value);
+                }
+                let type_name = row.column(index).type_info().name().to_ascii_lowercase();
+                match self.settings.type_overrides.get(&type_name) {
+                    Some(type_override) => apply_type_override(py, type_override, value)?,
+                    None => value,
+                }
+            }
+            Err(_) => py.None(),
+        })
+    }
+}
+
+impl DynamicParameterBinder for PostgresParameterBinder {
+    type Arguments = PgArguments;
+    type Database = sqlx::Postgres;
+    type Row = PgRow;
+
+    fn bind_parameters<'q>(
+        &self,
+        query: &'q str,
+        params: Vec<&PyAny>,
+    ) -> Result<(Cow<'q, str>, PgArguments), PyErr> {
+        self.bind_with_null_hints(query, params, &[])
     }
 
     fn bind_result(&self, py: Python<'_>, row: &PgRow) -> Result<PyObject, PyErr> {
         let dict = PyDict::new(py);
-
         for (i, column) in row.columns().iter().enumerate() {
-            let name = column.name();
-            let value = match row.try_get_raw(i) {
-                Ok(val) if val.is_null() => py.None(),
-                Ok(_) => extract_column_value(py, row, i)?,
-                Err(_) => py.None(),
-            };
-            dict.set_item(name, value)?;
+            dict.set_item(column.name(), self.column_value(py, row, i)?)?;
         }
         Ok(dict.into())
     }
+
+    fn bind_result_tuple(&self, py: Python<'_>, row: &PgRow) -> Result<PyObject, PyErr> {
+        let values = (0..row.len())
+            .map(|i| self.column_value(py, row, i))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(PyTuple::new(py, values).into())
+    }
 }
 
 #[derive(Debug, Clone, Default)]
-pub struct PostgresDatabase;
+pub struct PostgresDatabase {
+    settings: Arc<TypeSettings>,
+}
+
+impl PostgresDatabase {
+    pub fn new(settings: Arc<TypeSettings>) -> Self {
+        Self { settings }
+    }
+}
 
 impl DatabaseOperations for PostgresDatabase {
     type Row = PgRow;
@@ -83,19 +347,38 @@ impl DatabaseOperations for PostgresDatabase {
     type DatabaseType = sqlx::Postgres;
     type ParameterBinder = PostgresParameterBinder;
 
+    fn binder(&self) -> Self::ParameterBinder {
+        PostgresParameterBinder {
+            settings: self.settings.clone(),
+        }
+    }
+
     async fn execute(
         &mut self,
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
         query: &str,
         params: Vec<&PyAny>,
+        prepared: bool,
     ) -> Result<u64, PyErr> {
-        let query_builder = PostgresParameterBinder.bind_parameters(query, params)?;
+        let binder = self.binder();
+        if !prepared {
+            ensure_no_parameters(&params)?;
+            let mut guard = transaction.lock().await;
+            let result = sqlx::raw_sql(query)
+                .execute(&mut **active(&mut guard)?)
+                .await
+                .map_err(database_error)?;
+            return Ok(result.rows_affected());
+        }
+        let (sql, arguments) = binder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
         let mut guard = transaction.lock().await;
-        let result = query_builder
-            .execute(&mut **guard.as_mut().unwrap())
-            .await
-            .unwrap_or(PgQueryResult::default());
-        Ok(result.rows_affected())
+        let conn = &mut **active(&mut guard)?;
+        let result = query_builder.execute(&mut *conn).await;
+        if let Err(e) = &result {
+            invalidate_stale_statements(conn, e).await;
+        }
+        Ok(result.map_err(database_error)?.rows_affected())
     }
 
     async fn fetch_all(
@@ -104,90 +387,906 @@ impl DatabaseOperations for PostgresDatabase {
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
         query: &str,
         params: Vec<&PyAny>,
+        fetch_size: Option<usize>,
+        prepared: bool,
     ) -> Result<Vec<PyObject>, PyErr> {
-        let query_builder = PostgresParameterBinder.bind_parameters(query, params)?;
+        let binder = self.binder();
+        if !prepared {
+            ensure_no_parameters(&params)?;
+            let mut guard = transaction.lock().await;
+            let raw = sqlx::raw_sql(query);
+            let rows = match fetch_size {
+                Some(fetch_size) => {
+                    let stream = raw.fetch(&mut **active(&mut guard)?);
+                    return fetch_in_batches(stream, fetch_size, |row| binder.bind_result(py, row))
+                        .await;
+                }
+                None => raw
+                    .fetch_all(&mut **active(&mut guard)?)
+                    .await
+                    .map_err(database_error)?,
+            };
+            return rows.iter().map(|row| binder.bind_result(py, row)).collect();
+        }
+        let (sql, arguments) = binder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
         let mut guard = transaction.lock().await;
-        let rows = query_builder
-            .fetch_all(&mut **guard.as_mut().unwrap())
-            .await
-            .unwrap_or(Vec::new());
-        rows.iter()
-            .map(|row| PostgresParameterBinder.bind_result(py, row))
+        if let Some(fetch_size) = fetch_size {
+            let stream = query_builder.fetch(&mut **active(&mut guard)?);
+            return fetch_in_batches(stream, fetch_size, |row| binder.bind_result(py, row)).await;
+        }
+        let conn = &mut **active(&mut guard)?;
+        let rows = query_builder.fetch_all(&mut *conn).await;
+        if let Err(e) = &rows {
+            invalidate_stale_statements(conn, e).await;
+        }
+        rows.map_err(database_error)?
+            .iter()
+            .map(|row| binder.bind_result(py, row))
             .collect()
     }
 
-    async fn stream_data(
+    async fn fetch_all_tuples(
         &mut self,
         py: Python<'_>,
-        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, sqlx::Postgres>>>>,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
         query: &str,
         params: Vec<&PyAny>,
-        chunk_size: usize,
-    ) -> PyResult<Vec<Vec<PyObject>>> {
-        let query_builder = PostgresParameterBinder.bind_parameters(query, params)?;
-        let mut guard = transaction.lock().await.take().unwrap();
-        let mut stream = query_builder.fetch(&mut *guard);
-        let mut chunks: Vec<Vec<PyObject>> = Vec::new();
-        let mut current_chunk: Vec<PyObject> = Vec::new();
-
-        while let Some(row_result) = stream.next().await {
-            match row_result {
-                Ok(row) => {
-                    let row_data: PyObject = PostgresParameterBinder.bind_result(py, &row)?;
-                    current_chunk.push(row_data);
-
-                    if current_chunk.len() >= chunk_size {
-                        chunks.push(current_chunk);
-                        current_chunk = Vec::new();
-                    }
-                }
-                Err(e) => {
-                    return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                        e.to_string(),
-                    ));
-                }
+    ) -> PyResult<Vec<PyObject>> {
+        let binder = self.binder();
+        let (sql, arguments) = binder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
+        let mut guard = transaction.lock().await;
+        let conn = &mut **active(&mut guard)?;
+        let rows = query_builder.fetch_all(&mut *conn).await;
+        if let Err(e) = &rows {
+            invalidate_stale_statements(conn, e).await;
+        }
+        rows.map_err(database_error)?
+            .iter()
+            .map(|row| binder.bind_result_tuple(py, row))
+            .collect()
+    }
+
+    async fn describe(
+        &mut self,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
+        query: &str,
+    ) -> PyResult<Vec<ColumnDescription>> {
+        let mut guard = transaction.lock().await;
+        let describe = (&mut **active(&mut guard)?)
+            .describe(query)
+            .await
+            .map_err(database_error)?;
+        Ok(column_descriptions(&describe))
+    }
+
+    async fn fetch_one(
+        &mut self,
+        py: Python<'_>,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
+        query: &str,
+        params: Vec<&PyAny>,
+    ) -> PyResult<PyObject> {
+        let binder = self.binder();
+        let (sql, arguments) = binder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
+        let mut guard = transaction.lock().await;
+        let conn = &mut **active(&mut guard)?;
+        match query_builder.fetch_one(&mut *conn).await {
+            Ok(row) => binder.bind_result(py, &row),
+            Err(e) => {
+                invalidate_stale_statements(conn, &e).await;
+                Err(fetch_one_error(e))
             }
         }
+    }
 
-        if !current_chunk.is_empty() {
-            chunks.push(current_chunk);
+    async fn fetch_optional(
+        &mut self,
+        py: Python<'_>,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
+        query: &str,
+        params: Vec<&PyAny>,
+    ) -> PyResult<Option<PyObject>> {
+        let binder = self.binder();
+        let (sql, arguments) = binder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
+        let mut guard = transaction.lock().await;
+        let conn = &mut **active(&mut guard)?;
+        match query_builder.fetch_optional(&mut *conn).await {
+            Ok(row) => row.map(|row| binder.bind_result(py, &row)).transpose(),
+            Err(e) => {
+                invalidate_stale_statements(conn, &e).await;
+                Err(database_error(e))
+            }
         }
-        Ok(chunks)
     }
 
     async fn bulk_change(
         &mut self,
+        py: Python<'_>,
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
         query: &str,
         params: Vec<Vec<&PyAny>>,
         batch_size: usize,
-    ) -> Result<u64, PyErr> {
-        let mut total_affected: u64 = 0;
+        returning: bool,
+    ) -> Result<BulkChanges, PyErr> {
+        let binder = self.binder();
+        let width = params.iter().map(Vec::len).max().unwrap_or(0);
+        let null_hints: Vec<Option<&PyAny>> = (0..width)
+            .map(|index| {
+                params
+                    .iter()
+                    .filter_map(|param_set| param_set.get(index).copied())
+                    .find(|value| !value.is_none())
+            })
+            .collect();
+        let mut changes = BulkChanges::default();
         let mut guard = transaction.lock().await;
-        let tx = guard.as_mut().ok_or_else(|| {
-            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("No active transaction")
-        })?;
-
-        // Process in batches
-        for chunk in params.chunks(batch_size) {
-            for param_set in chunk {
-                // Build query with current parameters
-                let query_builder =
-                    PostgresParameterBinder.bind_parameters(query, param_set.to_vec())?;
-                // Execute query and accumulate affected rows
-                let result = query_builder.execute(&mut **tx).await.map_err(|e| {
-                    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string())
+        let tx = active(&mut guard)?;
+
+        for (statement, values) in
+            bulk_statements(&DatabaseType::Postgres, query, &params, batch_size)
+        {
+            // Flattened rows repeat the per-column hints
+            let hints: Vec<Option<&PyAny>> = null_hints
+                .iter()
+                .cycle()
+                .take(values.len())
+                .copied()
+                .collect();
+            let (sql, arguments) = binder.bind_with_null_hints(&statement, values, &hints)?;
+            let query_builder = sqlx::query_with(&sql, arguments);
+            if returning {
+                let results = match (&mut **tx).fetch_many(query_builder).try_collect().await {
+                    Ok(results) => results,
+                    Err(e) => {
+                        invalidate_stale_statements(tx, &e).await;
+                        return Err(database_error(e));
+                    }
+                };
+                changes.add_results(results, PgQueryResult::rows_affected, |row| {
+                    binder.bind_result(py, row)
                 })?;
+                continue;
+            }
+            // Execute query and accumulate affected rows
+            let result = match query_builder.execute(&mut **tx).await {
+                Ok(result) => result,
+                Err(e) => {
+                    invalidate_stale_statements(tx, &e).await;
+                    return Err(database_error(e));
+                }
+            };
+
+            changes.statement_counts.push(result.rows_affected());
+        }
+        Ok(changes)
+    }
+}
+
+impl PostgresDatabase {
+    // Stream the contents of a Python file-like object (text or binary) into `COPY ... FROM STDIN`.
+    pub async fn copy_from_reader(
+        &mut self,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, sqlx::Postgres>>>>,
+        statement: &str,
+        reader: &PyAny,
+        chunk_size: usize,
+    ) -> PyResult<u64> {
+        let mut guard = transaction.lock().await;
+        let tx = active(&mut guard)?;
+        let mut copy = tx.copy_in_raw(statement).await.map_err(database_error)?;
 
-                total_affected += result.rows_affected();
+        loop {
+            let chunk = match reader.call_method1("read", (chunk_size,)) {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    copy.abort(e.to_string()).await.ok();
+                    return Err(e);
+                }
+            };
+            let data: Vec<u8> = if let Ok(text) = chunk.downcast::<PyString>() {
+                text.to_str()?.as_bytes().to_vec()
+            } else {
+                chunk.extract::<Vec<u8>>()?
+            };
+            if data.is_empty() {
+                break;
             }
+            copy.send(data).await.map_err(database_error)?;
+        }
+
+        copy.finish().await.map_err(database_error)
+    }
+
+    // (name, collation) for each result column of `query`, taken from the prepared statement
+    // description, so it works for queries returning no rows. Table columns report their own
+    // collation, expressions that of their type; "default" is resolved to the database's
+    // collation, and non-collatable columns report None.
+    pub async fn column_collations(
+        &mut self,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, sqlx::Postgres>>>>,
+        query: &str,
+    ) -> PyResult<Vec<(String, Option<String>)>> {
+        let mut guard = transaction.lock().await;
+        let tx = active(&mut guard)?;
+        let description = (&mut **tx).describe(query).await.map_err(database_error)?;
+
+        let mut collations = Vec::with_capacity(description.columns().len());
+        for column in description.columns() {
+            let collation: Option<String> = sqlx::query_scalar(
+                "SELECT CASE WHEN c.collname = 'default' \
+                 THEN (SELECT datcollate::text FROM pg_database WHERE datname = current_database()) \
+                 ELSE c.collname::text END \
+                 FROM pg_collation c WHERE c.oid = COALESCE( \
+                 (SELECT attcollation FROM pg_attribute WHERE attrelid = $1 AND attnum = $2), \
+                 (SELECT typcollation FROM pg_type WHERE oid = $3))",
+            )
+            .bind(column.relation_id())
+            .bind(column.relation_attribute_no())
+            .bind(column.type_info().oid())
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(database_error)?;
+            collations.push((column.name().to_string(), collation));
         }
-        Ok(total_affected)
+        Ok(collations)
+    }
+}
+
+// sqlx keeps each pooled connection's prepared statements across transactions. When a
+// table's columns change underneath one, Postgres rejects the stale plan with "cached plan
+// must not change result type"; drop that connection's cache so the next execution
+// re-prepares instead of failing the same way until the connection is recycled.
+async fn invalidate_stale_statements(conn: &mut PgConnection, error: &sqlx::Error) {
+    let stale = error.as_database_error().is_some_and(|db_error| {
+        db_error.code().as_deref() == Some("0A000")
+            && db_error
+                .message()
+                .contains("cached plan must not change result type")
+    });
+    if stale {
+        conn.clear_cached_statements().await.ok();
     }
 }
 
 // Helper functions
-fn extract_datetime(param: &PyAny) -> PyResult<NaiveDateTime> {
+fn apply_type_override(
+    py: Python<'_>,
+    type_override: &TypeOverride,
+    value: PyObject,
+) -> PyResult<PyObject> {
+    let value = value.as_ref(py);
+    Ok(match type_override {
+        // Values with no default extraction stay None rather than becoming "None"
+        TypeOverride::Str if value.is_none() => py.None(),
+        TypeOverride::Str => value.str()?.into(),
+        TypeOverride::Epoch => {
+            let datetime = py.import("datetime")?;
+            let utc = timezone_utc(py);
+            let value = if value.is_instance_of::<PyDateTime>() {
+                if value.getattr("tzinfo")?.is_none() {
+                    let kwargs = PyDict::new(py);
+                    kwargs.set_item("tzinfo", utc)?;
+                    value.call_method("replace", (), Some(kwargs))?
+                } else {
+                    value
+                }
+            } else {
+                // `date`: midnight UTC
+                let midnight = datetime.getattr("time")?.call0()?;
+                datetime
+                    .getattr("datetime")?
+                    .call_method1("combine", (value, midnight, utc))?
+            };
+            let epoch = datetime
+                .getattr("datetime")?
+                .call1((1970, 1, 1, 0, 0, 0, 0, utc))?;
+            let second = datetime.getattr("timedelta")?.call1((0, 1))?;
+            value
+                .call_method1("__sub__", (epoch,))?
+                .call_method1("__floordiv__", (second,))?
+                .into()
+        }
+        TypeOverride::Bool if value.is_none() => py.None(),
+        TypeOverride::Bool => {
+            let text: &str = value.extract()?;
+            match text.trim_end().to_ascii_lowercase().as_str() {
+                "t" | "true" => true.into_py(py),
+                "f" | "false" => false.into_py(py),
+                _ => {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Cannot read {:?} as a bool; expected t, f, true or false",
+                        text
+                    )))
+                }
+            }
+        }
+        TypeOverride::Callable(callable) => callable.call1(py, (value,))?,
+    })
+}
+
+pub fn is_decimal(param: &PyAny) -> PyResult<bool> {
+    param.is_instance(param.py().import("decimal")?.getattr("Decimal")?)
+}
+
+// A finite `decimal.Decimal` as `numeric`, or None for a quiet NaN (bound as numeric NaN).
+// Infinities and signaling NaNs are rejected up front: `numeric` has no infinity before
+// PostgreSQL 14 and `rust_decimal` has none at all, so they would otherwise surface as an
+// opaque server or encoding error.
+pub fn extract_decimal(param: &PyAny) -> PyResult<Option<Decimal>> {
+    if param.call_method0("is_snan")?.is_true()? {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "Cannot bind a signaling NaN Decimal",
+        ));
+    }
+    if param.call_method0("is_nan")?.is_true()? {
+        return Ok(None);
+    }
+    if param.call_method0("is_infinite")?.is_true()? {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Cannot bind Decimal('{}') to numeric: infinity is not supported",
+            param.str()?
+        )));
+    }
+    // Fixed-point formatting avoids exponent notation such as `1E+3`
+    let text: String = param.call_method1("__format__", ("f",))?.extract()?;
+    Decimal::from_str_exact(&text).map(Some).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Cannot bind Decimal('{}') to numeric: {}",
+            text, e
+        ))
+    })
+}
+
+// `uuid.UUID` objects; a str only binds as uuid with `uuid_strings` (see `string_uuid`)
+pub fn is_uuid(param: &PyAny) -> PyResult<bool> {
+    param.is_instance(param.py().import("uuid")?.getattr("UUID")?)
+}
+
+fn extract_uuid(param: &PyAny) -> PyResult<Uuid> {
+    let bytes: Vec<u8> = param.getattr("bytes")?.extract()?;
+    Uuid::from_slice(&bytes)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+fn uuid_to_py(py: Python<'_>, value: &Uuid) -> PyResult<PyObject> {
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("bytes", PyBytes::new(py, value.as_bytes()))?;
+    Ok(py
+        .import("uuid")?
+        .getattr("UUID")?
+        .call((), Some(kwargs))?
+        .into())
+}
+
+// `ipaddress` addresses, interfaces (a subclass of addresses) and networks, bound as inet
+fn is_ip_address(param: &PyAny) -> PyResult<bool> {
+    let ipaddress = param.py().import("ipaddress")?;
+    for class in ["IPv4Address", "IPv6Address", "IPv4Network", "IPv6Network"] {
+        if param.is_instance(ipaddress.getattr(class)?)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// An address binds with its full prefix, so it reads back as a plain address
+fn extract_ip_network(param: &PyAny) -> PyResult<IpNetwork> {
+    let text = param.str()?.to_str()?;
+    text.parse().map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Cannot bind {:?} as inet: {}",
+            text, e
+        ))
+    })
+}
+
+// cidr as an `ipaddress` network; inet as an address, or an interface when it carries a
+// shorter prefix (`10.0.0.1/24`)
+fn ip_network_to_py(py: Python<'_>, value: &IpNetwork, cidr: bool) -> PyResult<PyObject> {
+    let full_prefix = match value {
+        IpNetwork::V4(_) => 32,
+        IpNetwork::V6(_) => 128,
+    };
+    let (constructor, text) = if cidr {
+        ("ip_network", value.to_string())
+    } else if value.prefix() == full_prefix {
+        ("ip_address", value.ip().to_string())
+    } else {
+        ("ip_interface", value.to_string())
+    };
+    Ok(py
+        .import("ipaddress")?
+        .getattr(constructor)?
+        .call1((text,))?
+        .into())
+}
+
+fn is_range(param: &PyAny) -> PyResult<bool> {
+    param.is_instance(param.py().import("builtins")?.getattr("range")?)
+}
+
+// Bounds of a unit-step `range` as a half-open `[start, stop)`; an empty range collapses to
+// `[start, start)` since Postgres rejects a lower bound above the upper one.
+fn extract_range(param: &PyAny) -> PyResult<(i64, i64)> {
+    let start: i64 = param.getattr("start")?.extract()?;
+    let stop: i64 = param.getattr("stop")?.extract()?;
+    let step: i64 = param.getattr("step")?.extract()?;
+    if step != 1 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Cannot bind range with step {} as a range type; use range_binding='array'",
+            step
+        )));
+    }
+    Ok((start, stop.max(start)))
+}
+
+// Largest generator / iterable materialized into an array parameter
+const MAX_ITERABLE_ELEMENTS: usize = 1_000_000;
+
+// Generators, sets and other iterables bind as arrays. bytes are iterable too but are never
+// an array of their byte values.
+fn is_array_iterable(param: &PyAny) -> bool {
+    !param.is_instance_of::<PyBytes>()
+        && !param.is_instance_of::<PyByteArray>()
+        && param.iter().is_ok()
+}
+
+fn materialize_iterable(param: &PyAny) -> PyResult<&PyList> {
+    let items = PyList::empty(param.py());
+    for item in param.iter()? {
+        if items.len() == MAX_ITERABLE_ELEMENTS {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Iterable parameter has more than {} elements",
+                MAX_ITERABLE_ELEMENTS
+            )));
+        }
+        items.append(item?)?;
+    }
+    Ok(items)
+}
+
+// Materialized iterable as an array; unlike a list parameter it never falls back to JSON.
+fn iterable_to_array(items: &PyList) -> PyResult<PgNdArray> {
+    if let Some(array) = nested_list_to_array(items)? {
+        return Ok(array);
+    }
+    if items.is_empty() {
+        return Ok(PgNdArray {
+            dims: Vec::new(),
+            elements: Vec::new(),
+        });
+    }
+    let dims = vec![items.len()];
+    let mut elements = Vec::with_capacity(items.len());
+    if !collect_array_elements(items, &dims, &mut elements)? {
+        return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+            "Iterable parameters must yield None, bool, int, float, str, bytes or timedelta",
+        ));
+    }
+    Ok(PgNdArray { dims, elements })
+}
+
+// A non-empty list of bytes / bytearray (None allowed between them) binds as `bytea[]`.
+fn bytes_list(param: &PyAny) -> PyResult<Option<Vec<Option<Vec<u8>>>>> {
+    let Ok(list) = param.downcast::<PyList>() else {
+        return Ok(None);
+    };
+    let mut blobs = Vec::with_capacity(list.len());
+    for item in list.iter() {
+        if let Ok(bytes) = item.downcast::<PyBytes>() {
+            blobs.push(Some(bytes.as_bytes().to_vec()));
+        } else if let Ok(bytes) = item.downcast::<PyByteArray>() {
+            blobs.push(Some(bytes.to_vec()));
+        } else if item.is_none() {
+            blobs.push(None);
+        } else {
+            return Ok(None);
+        }
+    }
+    Ok(blobs.iter().any(Option::is_some).then_some(blobs))
+}
+
+// A non-empty list of timedeltas (None allowed between them) binds as `interval[]`.
+fn interval_list(param: &PyAny) -> PyResult<Option<Vec<Option<PgInterval>>>> {
+    let Ok(list) = param.downcast::<PyList>() else {
+        return Ok(None);
+    };
+    let mut intervals = Vec::with_capacity(list.len());
+    for item in list.iter() {
+        if let Ok(delta) = item.downcast::<PyDelta>() {
+            intervals.push(Some(timedelta_to_interval(delta)));
+        } else if item.is_none() {
+            intervals.push(None);
+        } else {
+            return Ok(None);
+        }
+    }
+    Ok(intervals.iter().any(Option::is_some).then_some(intervals))
+}
+
+fn timedelta_to_interval(delta: &PyDelta) -> PgInterval {
+    PgInterval {
+        months: 0,
+        days: delta.get_days(),
+        microseconds: delta.get_seconds() as i64 * 1_000_000 + delta.get_microseconds() as i64,
+    }
+}
+
+const TIMEDELTA_MAX_DAYS: i64 = 999_999_999;
+
+// timedelta has no months, so a month counts as 30 days (as PostgreSQL's own
+// `justify_days` and `EXTRACT(epoch ...)` do).
+fn interval_to_py(py: Python<'_>, interval: &PgInterval) -> PyResult<PyObject> {
+    let seconds = interval.microseconds.div_euclid(1_000_000);
+    let microseconds = interval.microseconds.rem_euclid(1_000_000) as i32;
+    let days = interval.months as i64 * 30 + interval.days as i64 + seconds.div_euclid(86_400);
+    // Intervals reach about 178 million years; timedelta stops at 999999999 days either way
+    if days.abs() > TIMEDELTA_MAX_DAYS {
+        return Err(PyErr::new::<pyo3::exceptions::PyOverflowError, _>(format!(
+            "interval of {} months {} days {} microseconds ({} days) is outside the range of \
+             timedelta (+/-{} days)",
+            interval.months, interval.days, interval.microseconds, days, TIMEDELTA_MAX_DAYS
+        )));
+    }
+    Ok(PyDelta::new(
+        py,
+        days as i32,
+        seconds.rem_euclid(86_400) as i32,
+        microseconds,
+        true,
+    )?
+    .into())
+}
+
+// A list of lists (`[[1.0, 2.0], [3.0, 4.0]]`) becomes a multidimensional array when it is
+// rectangular and every leaf is None, bool, int, float or str. Anything else keeps binding
+// as JSON.
+fn nested_list_to_array(param: &PyAny) -> PyResult<Option<PgNdArray>> {
+    let Ok(list) = param.downcast::<PyList>() else {
+        return Ok(None);
+    };
+    if !list
+        .iter()
+        .next()
+        .is_some_and(|first| first.is_instance_of::<PyList>())
+    {
+        return Ok(None);
+    }
+
+    let mut dims = Vec::new();
+    let mut level = param;
+    while let Ok(inner) = level.downcast::<PyList>() {
+        dims.push(inner.len());
+        match inner.iter().next() {
+            Some(first) => level = first,
+            None => break,
+        }
+    }
+
+    let mut elements = Vec::new();
+    if !collect_array_elements(param, &dims, &mut elements)? {
+        return Ok(None);
+    }
+    Ok(Some(PgNdArray { dims, elements }))
+}
+
+// A flat list whose non-None items are all bools, all ints, all floats or all strs binds as
+// `bool[]`, `int8[]`, `float8[]` or `text[]`, so `id = ANY($1)` works. Empty, all-None and
+// mixed lists keep binding as JSON.
+fn flat_list_to_array(param: &PyAny) -> PyResult<Option<PgNdArray>> {
+    let Ok(list) = param.downcast::<PyList>() else {
+        return Ok(None);
+    };
+    let dims = vec![list.len()];
+    let mut elements = Vec::with_capacity(list.len());
+    if !collect_array_elements(param, &dims, &mut elements)? {
+        return Ok(None);
+    }
+    let mut kinds = elements
+        .iter()
+        .filter(|element| **element != PgArrayElement::Null)
+        .map(std::mem::discriminant);
+    let Some(kind) = kinds.next() else {
+        return Ok(None);
+    };
+    if !kinds.all(|other| other == kind) {
+        return Ok(None);
+    }
+    Ok(Some(PgNdArray { dims, elements }))
+}
+
+fn collect_array_elements(
+    value: &PyAny,
+    dims: &[usize],
+    elements: &mut Vec<PgArrayElement>,
+) -> PyResult<bool> {
+    if let Some((len, rest)) = dims.split_first() {
+        let Ok(list) = value.downcast::<PyList>() else {
+            return Ok(false);
+        };
+        if list.len() != *len {
+            return Ok(false);
+        }
+        for item in list.iter() {
+            if !collect_array_elements(item, rest, elements)? {
+                return Ok(false);
+            }
+        }
+        return Ok(true);
+    }
+
+    elements.push(match value {
+        v if v.is_none() => PgArrayElement::Null,
+        // Checked before int, which `bool` subclasses, so `[True, False]` stays `boolean[]`
+        v if v.is_instance_of::<PyBool>() => PgArrayElement::Bool(v.extract()?),
+        v if v.is_instance_of::<PyInt>() => PgArrayElement::Int(v.extract()?),
+        v if v.is_instance_of::<PyFloat>() => PgArrayElement::Float(v.extract()?),
+        v if v.is_instance_of::<PyString>() => PgArrayElement::Text(v.extract()?),
+        _ => return Ok(false),
+    });
+    Ok(true)
+}
+
+// Rebuild nested Python lists from an array's dimensions and row-major elements.
+fn nd_array_to_py(
+    py: Python<'_>,
+    dims: &[usize],
+    elements: &mut impl Iterator<Item = PgArrayElement>,
+) -> PyObject {
+    match dims.split_first() {
+        None => match elements.next() {
+            Some(PgArrayElement::Bool(v)) => v.into_py(py),
+            Some(PgArrayElement::Int(v)) => v.into_py(py),
+            Some(PgArrayElement::Float(v)) => v.into_py(py),
+            Some(PgArrayElement::Text(v)) => v.into_py(py),
+            Some(PgArrayElement::Null) | None => py.None(),
+        },
+        Some((len, rest)) => {
+            let items: Vec<PyObject> = (0..*len)
+                .map(|_| nd_array_to_py(py, rest, elements))
+                .collect();
+            PyList::new(py, items).into()
+        }
+    }
+}
+
+// A composite value as a dict of its attributes, in declaration order.
+fn record_to_py(py: Python<'_>, record: PgRecord) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    for (name, field) in record.0 {
+        let value = match field {
+            PgField::Null => py.None(),
+            PgField::Bool(v) => v.into_py(py),
+            PgField::Int(v) => v.into_py(py),
+            PgField::Float(v) => v.into_py(py),
+            PgField::Text(v) => v.into_py(py),
+            PgField::Numeric(v) => decimal_to_py(py, &v)?,
+            PgField::Date(v) => PyDate::new(py, v.year(), v.month() as u8, v.day() as u8)?.into(),
+            PgField::Time(v) => PyTime::new(
+                py,
+                v.hour() as u8,
+                v.minute() as u8,
+                v.second() as u8,
+                v.nanosecond() / 1000,
+                None,
+            )?
+            .into(),
+            PgField::Timestamp(v) => new_py_datetime(py, &v, None)?.into(),
+            PgField::TimestampTz(v) => {
+                new_py_datetime(py, &v.naive_utc(), Some(timezone_utc(py)))?.into()
+            }
+            PgField::Uuid(v) => uuid_to_py(py, &v)?,
+            PgField::Bytes(v) => PyBytes::new(py, &v).into(),
+            PgField::Array(v) if v.dims.is_empty() => PyList::empty(py).into(),
+            PgField::Array(v) => nd_array_to_py(py, &v.dims, &mut v.elements.into_iter()),
+            PgField::Record(v) => record_to_py(py, v)?,
+        };
+        dict.set_item(name, value)?;
+    }
+    Ok(dict.into())
+}
+
+type PgQuery<'q> = sqlx::query::Query<'q, sqlx::Postgres, PgArguments>;
+
+fn to_pg_range<T>(
+    range: &Range,
+    py: Python<'_>,
+    convert: impl Fn(&PyAny) -> PyResult<T>,
+) -> PyResult<PgRange<T>> {
+    let bound = |value: &PyObject, inclusive: bool| -> PyResult<Bound<T>> {
+        if value.is_none(py) {
+            Ok(Bound::Unbounded)
+        } else if inclusive {
+            Ok(Bound::Included(convert(value.as_ref(py))?))
+        } else {
+            Ok(Bound::Excluded(convert(value.as_ref(py))?))
+        }
+    };
+    Ok(PgRange {
+        start: bound(&range.lower, range.lower_inc)?,
+        end: bound(&range.upper, range.upper_inc)?,
+    })
+}
+
+// Bind a `Range`, picking the range type from its bounds: int -> int4range (int8range when a
+// bound exceeds 32 bits), Decimal -> numrange, date -> daterange, naive datetime -> tsrange,
+// aware datetime -> tstzrange. An empty range still needs one bound to fix its type and is
+// sent as `(b, b)`. With `null`, a NULL of that range type is bound instead.
+fn bind_range<'q>(
+    py: Python<'_>,
+    query_builder: PgQuery<'q>,
+    range: &Range,
+    null: bool,
+) -> PyResult<PgQuery<'q>> {
+    let range = if range.is_empty {
+        let bound = if range.lower.is_none(py) {
+            range.upper.clone_ref(py)
+        } else {
+            range.lower.clone_ref(py)
+        };
+        Range {
+            lower_inc: false,
+            upper_inc: false,
+            ..Range::from_bounds(py, Some((bound.clone_ref(py), false)), Some((bound, false)))
+        }
+    } else {
+        range.clone()
+    };
+    let sample = [&range.lower, &range.upper]
+        .into_iter()
+        .find(|bound| !bound.is_none(py))
+        .ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Cannot infer a range type from a Range without bounds",
+            )
+        })?
+        .as_ref(py);
+
+    Ok(match sample {
+        v if v.is_instance_of::<PyBool>() => {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "Range bounds cannot be bool",
+            ))
+        }
+        v if v.is_instance_of::<PyInt>() => match to_pg_range(&range, py, |b| b.extract::<i32>()) {
+            Ok(range) => bind_or_null(query_builder, range, null),
+            Err(_) => {
+                let range = to_pg_range(&range, py, |b| b.extract::<i64>())?;
+                bind_or_null(query_builder, range, null)
+            }
+        },
+        v if is_decimal(v)? => {
+            let range = to_pg_range(&range, py, |b| {
+                extract_decimal(b)?.ok_or_else(|| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>("Range bounds cannot be NaN")
+                })
+            })?;
+            bind_or_null(query_builder, range, null)
+        }
+        v if v.is_instance_of::<PyDateTime>() => {
+            if is_naive(v)? {
+                bind_or_null(
+                    query_builder,
+                    to_pg_range(&range, py, extract_datetime)?,
+                    null,
+                )
+            } else {
+                bind_or_null(
+                    query_builder,
+                    to_pg_range(&range, py, extract_utc_datetime)?,
+                    null,
+                )
+            }
+        }
+        v if v.is_instance_of::<PyDate>() => {
+            bind_or_null(query_builder, to_pg_range(&range, py, extract_date)?, null)
+        }
+        v => {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                "Unsupported Range bound type: {:?}",
+                v.get_type()
+            )))
+        }
+    })
+}
+
+fn bind_or_null<'q, T>(query_builder: PgQuery<'q>, value: T, null: bool) -> PgQuery<'q>
+where
+    T: sqlx::Encode<'q, sqlx::Postgres> + sqlx::Type<sqlx::Postgres> + Send + 'q,
+{
+    match null {
+        true => query_builder.bind(None::<T>),
+        false => query_builder.bind(value),
+    }
+}
+
+fn range_to_py<T>(
+    py: Python<'_>,
+    range: PgRange<T>,
+    convert: impl Fn(T) -> PyResult<PyObject>,
+) -> PyResult<Range> {
+    let bound = |bound: Bound<T>| -> PyResult<Option<(PyObject, bool)>> {
+        Ok(match bound {
+            Bound::Included(value) => Some((convert(value)?, true)),
+            Bound::Excluded(value) => Some((convert(value)?, false)),
+            Bound::Unbounded => None,
+        })
+    };
+    Ok(Range::from_bounds(
+        py,
+        bound(range.start)?,
+        bound(range.end)?,
+    ))
+}
+
+// Any built-in range column as a `Range`. sqlx decodes an empty range as unbounded on both
+// sides, so emptiness is read from the value's flags (binary) or literal (text) first.
+fn decode_range_column(py: Python<'_>, row: &PgRow, index: usize) -> PyResult<Option<PyObject>> {
+    let raw = row.try_get_raw(index).map_err(database_error)?;
+    if !matches!(raw.type_info().kind(), PgTypeKind::Range(_)) {
+        return Ok(None);
+    }
+    let is_empty = match raw.format() {
+        PgValueFormat::Binary => raw.as_bytes().map_err(decode_error)?.first() == Some(&0x01),
+        PgValueFormat::Text => raw.as_str().map_err(decode_error)? == "empty",
+    };
+    if is_empty {
+        return Ok(Some(Range::empty(py).into_py(py)));
+    }
+
+    let range = if let Ok(v) = row.try_get::<PgRange<i32>, _>(index) {
+        range_to_py(py, v, |x| Ok(x.into_py(py)))?
+    } else if let Ok(v) = row.try_get::<PgRange<i64>, _>(index) {
+        range_to_py(py, v, |x| Ok(x.into_py(py)))?
+    } else if let Ok(v) = row.try_get::<PgRange<Decimal>, _>(index) {
+        range_to_py(py, v, |x| decimal_to_py(py, &x))?
+    } else if let Ok(v) = row.try_get::<PgRange<NaiveDate>, _>(index) {
+        range_to_py(py, v, |x| {
+            Ok(PyDate::new(py, x.year(), x.month() as u8, x.day() as u8)?.into())
+        })?
+    } else if let Ok(v) = row.try_get::<PgRange<NaiveDateTime>, _>(index) {
+        range_to_py(py, v, |x| Ok(new_py_datetime(py, &x, None)?.into()))?
+    } else if let Ok(v) = row.try_get::<PgRange<DateTime<Utc>>, _>(index) {
+        range_to_py(py, v, |x| {
+            Ok(new_py_datetime(py, &x.naive_utc(), Some(timezone_utc(py)))?.into())
+        })?
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(range.into_py(py)))
+}
+
+fn decode_error(e: sqlx::error::BoxDynError) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string())
+}
+
+pub fn decimal_to_py(py: Python<'_>, value: &impl std::fmt::Display) -> PyResult<PyObject> {
+    Ok(py
+        .import("decimal")?
+        .getattr("Decimal")?
+        .call1((value.to_string(),))?
+        .into())
+}
+
+// A datetime without a usable offset: tzinfo is None or its utcoffset() is.
+pub fn is_naive(param: &PyAny) -> PyResult<bool> {
+    Ok(param.call_method0("utcoffset")?.is_none())
+}
+
+// An aware datetime converted to UTC, for `timestamptz`.
+pub fn extract_utc_datetime(param: &PyAny) -> PyResult<DateTime<Utc>> {
+    let utc = param.call_method1("astimezone", (timezone_utc(param.py()),))?;
+    Ok(DateTime::from_naive_utc_and_offset(
+        extract_datetime(utc)?,
+        Utc,
+    ))
+}
+
+pub fn extract_datetime(param: &PyAny) -> PyResult<NaiveDateTime> {
     let dt: &PyDateTime = param.downcast()?;
     Ok(NaiveDateTime::new(
         NaiveDate::from_ymd_opt(dt.get_year(), dt.get_month() as u32, dt.get_day() as u32).unwrap(),
@@ -195,13 +1294,13 @@ fn extract_datetime(param: &PyAny) -> PyResult<NaiveDateTime> {
             dt.get_hour() as u32,
             dt.get_minute() as u32,
             dt.get_second() as u32,
-            dt.get_microsecond() as u32 * 1000,
+            dt.get_microsecond() * 1000,
         )
         .unwrap(),
     ))
 }
 
-fn extract_date(param: &PyAny) -> PyResult<NaiveDate> {
+pub fn extract_date(param: &PyAny) -> PyResult<NaiveDate> {
     let date: &PyDate = param.downcast()?;
     Ok(NaiveDate::from_ymd_opt(
         date.get_year(),
@@ -211,41 +1310,146 @@ fn extract_date(param: &PyAny) -> PyResult<NaiveDate> {
     .unwrap())
 }
 
-fn extract_time(param: &PyAny) -> PyResult<NaiveTime> {
+pub fn extract_time(param: &PyAny) -> PyResult<NaiveTime> {
     let time: &PyTime = param.downcast()?;
     Ok(NaiveTime::from_hms_nano_opt(
         time.get_hour() as u32,
         time.get_minute() as u32,
         time.get_second() as u32,
-        time.get_microsecond() as u32 * 1000,
+        time.get_microsecond() * 1000,
     )
     .unwrap())
 }
 
-fn extract_column_value(py: Python<'_>, row: &PgRow, index: usize) -> PyResult<PyObject> {
-    Ok(if let Ok(v) = row.try_get::<i32, _>(index) {
+// Build a Python datetime from its naive components.
+//
+// `timestamp` and `timestamptz` decode through distinct arms in `extract_column_value`:
+// `timestamp` never carries a tzinfo, `timestamptz` is always UTC-aware. Callers can rely
+// on `tzinfo is None` to tell the two apart. The session time zone (`DatabaseConfig.timezone`)
+// does not change either result; it only affects server-side conversions between the two.
+pub fn new_py_datetime<'p>(
+    py: Python<'p>,
+    v: &NaiveDateTime,
+    tzinfo: Option<&PyTzInfo>,
+) -> PyResult<&'p PyDateTime> {
+    PyDateTime::new(
+        py,
+        v.year(),
+        v.month() as u8,
+        v.day() as u8,
+        v.hour() as u8,
+        v.minute() as u8,
+        v.second() as u8,
+        v.nanosecond() / 1000,
+        tzinfo,
+    )
+}
+
+// Decode the common scalar types directly from the column's declared type. The probing chain
+// in `extract_column_value` pays for a failed `try_get` (and its error message) per earlier
+// arm, which dominates wide numeric result sets. Both result formats decode here: prepared
+// statements get binary results, while `prepared=False` (the simple protocol) always returns
+// text, as do multi-statement queries; binary cannot be requested there.
+fn decode_common_column(py: Python<'_>, row: &PgRow, index: usize) -> PyResult<Option<PyObject>> {
+    let type_name = row.column(index).type_info().name();
+    let value = match type_name {
+        "INT2" => row.try_get::<i16, _>(index).map(|v| v.into_py(py)),
+        "INT4" => row.try_get::<i32, _>(index).map(|v| v.into_py(py)),
+        "INT8" => row.try_get::<i64, _>(index).map(|v| v.into_py(py)),
+        "FLOAT4" => row
+            .try_get::<f32, _>(index)
+            .map(|v| f64::from(v).into_py(py)),
+        "FLOAT8" => row.try_get::<f64, _>(index).map(|v| v.into_py(py)),
+        "BOOL" => row.try_get::<bool, _>(index).map(|v| v.into_py(py)),
+        "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" => {
+            row.try_get::<String, _>(index).map(|v| v.into_py(py))
+        }
+        "TIMESTAMP" => match row.try_get::<NaiveDateTime, _>(index) {
+            Ok(v) => Ok(new_py_datetime(py, &v, None)?.into()),
+            Err(e) => Err(e),
+        },
+        "TIMESTAMPTZ" => match row.try_get::<DateTime<Utc>, _>(index) {
+            Ok(v) => Ok(new_py_datetime(py, &v.naive_utc(), Some(timezone_utc(py)))?.into()),
+            Err(e) => Err(e),
+        },
+        // Exact in both formats: rust_decimal would round anything past 28 digits and
+        // cannot hold NaN or the infinities
+        "NUMERIC" => match row.try_get::<PgNumericText, _>(index) {
+            Ok(v) => Ok(decimal_to_py(py, &v.0)?),
+            Err(e) => Err(e),
+        },
+        // Vec<u8> rather than &[u8]: the simple protocol sends bytea as hex text
+        "BYTEA" => row
+            .try_get::<Vec<u8>, _>(index)
+            .map(|v| PyBytes::new(py, &v).into()),
+        "UUID" => match row.try_get::<Uuid, _>(index) {
+            Ok(v) => Ok(uuid_to_py(py, &v)?),
+            Err(e) => Err(e),
+        },
+        "DATE" => match row.try_get::<NaiveDate, _>(index) {
+            Ok(v) => Ok(PyDate::new(py, v.year(), v.month() as u8, v.day() as u8)?.into()),
+            Err(e) => Err(e),
+        },
+        "INET" | "CIDR" => match row.try_get::<IpNetwork, _>(index) {
+            Ok(v) => Ok(ip_network_to_py(py, &v, type_name == "CIDR")?),
+            Err(e) => Err(e),
+        },
+        "MACADDR" => row.try_get::<MacAddress, _>(index).map(|v| {
+            let octets: Vec<String> = v.bytes().iter().map(|b| format!("{:02x}", b)).collect();
+            octets.join(":").into_py(py)
+        }),
+        // binary only; sqlx cannot parse the text form the simple protocol sends
+        "INTERVAL" => match row.try_get::<PgInterval, _>(index) {
+            Ok(v) => Ok(interval_to_py(py, &v)?),
+            Err(e) => Err(e),
+        },
+        _ => return Ok(None),
+    };
+    Ok(value.ok())
+}
+
+fn extract_column_value(
+    py: Python<'_>,
+    row: &PgRow,
+    index: usize,
+    strict: bool,
+) -> PyResult<PyObject> {
+    if let Some(v) = decode_common_column(py, row, index)? {
+        return Ok(v);
+    }
+    // narrowest width first: each integer and float type only decodes as its own width
+    Ok(if let Ok(v) = row.try_get::<i16, _>(index) {
+        v.into_py(py)
+    } else if let Ok(v) = row.try_get::<i32, _>(index) {
         v.into_py(py)
     } else if let Ok(v) = row.try_get::<i64, _>(index) {
         v.into_py(py)
+    } else if let Ok(v) = row.try_get::<Uuid, _>(index) {
+        uuid_to_py(py, &v)?
+    } else if let Ok(v) = row.try_get::<PgTid, _>(index) {
+        (v.block, v.offset).into_py(py)
     } else if let Ok(v) = row.try_get::<String, _>(index) {
         v.into_py(py)
+    } else if let Ok(v) = row.try_get::<PgEnumLabel, _>(index) {
+        v.0.into_py(py)
+    } else if let Ok(v) = row.try_get::<Vec<u8>, _>(index) {
+        // `bytea` only: text columns are not byte-compatible and were taken above
+        PyBytes::new(py, &v).into()
+    } else if let Ok(v) = row.try_get::<PgNumericText, _>(index) {
+        // exact, ahead of the float arm
+        decimal_to_py(py, &v.0)?
+    } else if let Ok(v) = row.try_get::<f32, _>(index) {
+        f64::from(v).into_py(py)
     } else if let Ok(v) = row.try_get::<f64, _>(index) {
         v.into_py(py)
     } else if let Ok(v) = row.try_get::<bool, _>(index) {
         v.into_py(py)
     } else if let Ok(v) = row.try_get::<NaiveDateTime, _>(index) {
-        PyDateTime::new(
-            py,
-            v.year(),
-            v.month() as u8,
-            v.day() as u8,
-            v.hour() as u8,
-            v.minute() as u8,
-            v.second() as u8,
-            (v.nanosecond() / 1000) as u32,
-            None,
-        )?
-        .into()
+        // `timestamp` (without time zone): naive in, naive out.
+        new_py_datetime(py, &v, None)?.into()
+    } else if let Ok(v) = row.try_get::<DateTime<Utc>, _>(index) {
+        // `timestamptz`: the server sends an absolute instant, so always attach UTC.
+        new_py_datetime(py, &v.naive_utc(), Some(timezone_utc(py)))?.into()
     } else if let Ok(v) = row.try_get::<NaiveDate, _>(index) {
         PyDate::new(py, v.year(), v.month() as u8, v.day() as u8)?.into()
     } else if let Ok(v) = row.try_get::<NaiveTime, _>(index) {
@@ -254,7 +1458,7 @@ fn extract_column_value(py: Python<'_>, row: &PgRow, index: usize) -> PyResult<P
             v.hour() as u8,
             v.minute() as u8,
             v.second() as u8,
-            (v.nanosecond() / 1000) as u32,
+            v.nanosecond() / 1000,
             None,
         )?
         .into()
@@ -262,11 +1466,59 @@ fn extract_column_value(py: Python<'_>, row: &PgRow, index: usize) -> PyResult<P
         to_string(&v.0)
             .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?
             .into_py(py)
+    } else if let Some(v) = decode_range_column(py, row, index)? {
+        v
+    } else if let Ok(v) = row.try_get::<PgNdArray, _>(index) {
+        // Arrays of any dimensionality over scalar element types
+        if v.dims.is_empty() {
+            PyList::empty(py).into()
+        } else {
+            nd_array_to_py(py, &v.dims, &mut v.elements.into_iter())
+        }
+    } else if let Ok(v) = row.try_get::<Vec<Option<Vec<u8>>>, _>(index) {
+        // `bytea[]`
+        let blobs: Vec<PyObject> = v
+            .iter()
+            .map(|blob| match blob {
+                Some(blob) => PyBytes::new(py, blob).into(),
+                None => py.None(),
+            })
+            .collect();
+        PyList::new(py, blobs).into()
+    } else if let Ok(v) = row.try_get::<Vec<Option<PgInterval>>, _>(index) {
+        // `interval[]`
+        let intervals = v
+            .iter()
+            .map(|interval| match interval {
+                Some(interval) => interval_to_py(py, interval),
+                None => Ok(py.None()),
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        PyList::new(py, intervals).into()
     } else if let Ok(v) = row.try_get::<Vec<String>, _>(index) {
         PyList::new(py, &v).into()
     } else if let Ok(v) = row.try_get::<Vec<i32>, _>(index) {
         PyList::new(py, &v).into()
+    } else if let Ok(v) = row.try_get::<PgComplex, _>(index) {
+        PyComplex::from_doubles(py, v.re, v.im).into()
+    } else if let Ok(v) = row.try_get::<PgRecord, _>(index) {
+        record_to_py(py, v)?
+    } else if let Ok(v) = row.try_get::<PgInt2Vector, _>(index) {
+        PyList::new(py, &v.0).into()
+    } else if let Ok(v) = row.try_get::<PgOidVector, _>(index) {
+        PyList::new(py, &v.0).into()
+    } else if strict {
+        let column = row.column(index);
+        return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "Column {:?} has type {} which cannot be decoded",
+            column.name(),
+            column.type_info().name()
+        )));
     } else {
-        py.None()
+        // never None, which would read as NULL
+        let raw = row
+            .try_get::<PgRawValue, _>(index)
+            .map_err(database_error)?;
+        PyBytes::new(py, &raw.0).into()
     })
 }
@@ -0,0 +1,1088 @@
+use std::borrow::Cow;
+
+use lazy_static::lazy_static;
+use pyo3::{
+    prelude::*,
+    types::{PyDate, PyDateTime, PyDict, PyInt, PyString, PyTuple},
+};
+use regex::Regex;
+use sqlx::types::Uuid;
+
+use super::{composite::Composite, config::DatabaseType, enum_value::EnumValue};
+
+lazy_static! {
+    static ref IDENTIFIER: Regex = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap();
+    static ref PLACEHOLDER: Regex = Regex::new(r"\$(\d+)").unwrap();
+    static ref INSERT_VALUES: Regex =
+        Regex::new(r"(?is)^\s*INSERT\s+INTO\s[^$']*?\bVALUES\s*\(").unwrap();
+    static ref NAME: Regex = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*").unwrap();
+    static ref DOLLAR_QUOTE: Regex = Regex::new(r"^\$(?:[A-Za-z_][A-Za-z0-9_]*)?\$").unwrap();
+}
+
+// Maximum number of bind parameters a single statement may carry for each backend.
+pub fn max_bind_parameters(driver: &DatabaseType) -> usize {
+    match driver {
+        DatabaseType::Postgres | DatabaseType::Mysql => 65_535,
+        DatabaseType::Sqlite => 32_766,
+    }
+}
+
+// Validate and quote a (optionally schema-qualified) identifier for the given backend.
+pub fn quote_identifier(driver: &DatabaseType, identifier: &str) -> PyResult<String> {
+    identifier
+        .split('.')
+        .map(|part| {
+            if !IDENTIFIER.is_match(part) {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Invalid SQL identifier: {:?}",
+                    identifier
+                )));
+            }
+            Ok(match driver {
+                DatabaseType::Mysql => format!("`{}`", part),
+                DatabaseType::Postgres | DatabaseType::Sqlite => format!("\"{}\"", part),
+            })
+        })
+        .collect::<PyResult<Vec<_>>>()
+        .map(|parts| parts.join("."))
+}
+
+// A savepoint name, which cannot be bound as a parameter: one plain identifier, quoted
+pub fn quote_savepoint(driver: &DatabaseType, name: &str) -> PyResult<String> {
+    if !IDENTIFIER.is_match(name) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Invalid savepoint name: {:?}",
+            name
+        )));
+    }
+    quote_identifier(driver, name)
+}
+
+fn quote_identifiers(driver: &DatabaseType, identifiers: &[String]) -> PyResult<Vec<String>> {
+    identifiers
+        .iter()
+        .map(|identifier| quote_identifier(driver, identifier))
+        .collect()
+}
+
+// `($1, $2), ($3, $4), ...` for `row_count` rows of `column_count` values.
+fn values_placeholders(column_count: usize, row_count: usize) -> String {
+    (0..row_count)
+        .map(|row| {
+            let slots: Vec<String> = (1..=column_count)
+                .map(|col| format!("${}", row * column_count + col))
+                .collect();
+            format!("({})", slots.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Single-row INSERT for the given columns, optionally returning the inserted row. On MySQL
+// `returning` needs a MariaDB server that has it (`mariadb_returning`).
+pub fn build_insert(
+    driver: &DatabaseType,
+    table: &str,
+    columns: &[String],
+    returning: bool,
+    mariadb_returning: bool,
+) -> PyResult<String> {
+    let table = quote_identifier(driver, table)?;
+    let mut sql = if columns.is_empty() {
+        match driver {
+            DatabaseType::Mysql => format!("INSERT INTO {} () VALUES ()", table),
+            DatabaseType::Postgres | DatabaseType::Sqlite => {
+                format!("INSERT INTO {} DEFAULT VALUES", table)
+            }
+        }
+    } else {
+        format!(
+            "INSERT INTO {} ({}) VALUES {}",
+            table,
+            quote_identifiers(driver, columns)?.join(", "),
+            values_placeholders(columns.len(), 1)
+        )
+    };
+
+    if returning {
+        if let (DatabaseType::Mysql, false) = (driver, mariadb_returning) {
+            return Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                "MySQL does not support INSERT ... RETURNING (MariaDB 10.5+ does)",
+            ));
+        }
+        sql.push_str(" RETURNING *");
+    }
+    Ok(sql)
+}
+
+// `UPDATE table SET ... WHERE ...` with the SET values bound first, then the WHERE values.
+// WHERE columns flagged as NULL compare with `IS NULL` and take no parameter. An empty WHERE
+// is rejected so a missing filter cannot rewrite the whole table.
+pub fn build_update(
+    driver: &DatabaseType,
+    table: &str,
+    set_columns: &[String],
+    where_columns: &[(String, bool)],
+    returning: bool,
+) -> PyResult<String> {
+    if set_columns.is_empty() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "update requires at least one column to set",
+        ));
+    }
+    if where_columns.is_empty() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "update requires at least one WHERE column",
+        ));
+    }
+
+    let mut placeholder = 0;
+    let assignments = quote_identifiers(driver, set_columns)?
+        .into_iter()
+        .map(|col| {
+            placeholder += 1;
+            format!("{} = ${}", col, placeholder)
+        })
+        .collect::<Vec<_>>();
+    let conditions = where_columns
+        .iter()
+        .map(|(col, is_null)| {
+            let col = quote_identifier(driver, col)?;
+            Ok(if *is_null {
+                format!("{} IS NULL", col)
+            } else {
+                placeholder += 1;
+                format!("{} = ${}", col, placeholder)
+            })
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let mut sql = format!(
+        "UPDATE {} SET {} WHERE {}",
+        quote_identifier(driver, table)?,
+        assignments.join(", "),
+        conditions.join(" AND ")
+    );
+    if returning {
+        if let DatabaseType::Mysql = driver {
+            return Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                "MySQL does not support UPDATE ... RETURNING",
+            ));
+        }
+        sql.push_str(" RETURNING *");
+    }
+    Ok(sql)
+}
+
+// Build a multi-row INSERT that resolves key conflicts per backend:
+// `ON CONFLICT ... DO UPDATE` for Postgres/SQLite and `ON DUPLICATE KEY UPDATE` for MySQL.
+// MySQL resolves against every unique key on the table, so `conflict_columns` only selects
+// the no-op assignment used when there is nothing to update.
+pub fn build_upsert(
+    driver: &DatabaseType,
+    table: &str,
+    columns: &[String],
+    conflict_columns: &[String],
+    update_columns: &[String],
+    row_count: usize,
+) -> PyResult<String> {
+    if columns.is_empty() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "bulk_upsert requires at least one column",
+        ));
+    }
+    if conflict_columns.is_empty() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "bulk_upsert requires at least one conflict column",
+        ));
+    }
+
+    let table = quote_identifier(driver, table)?;
+    let quoted_columns = quote_identifiers(driver, columns)?;
+    let quoted_conflict = quote_identifiers(driver, conflict_columns)?;
+    let quoted_update = quote_identifiers(driver, update_columns)?;
+
+    let mut sql = format!(
+        "INSERT INTO {} ({}) VALUES {}",
+        table,
+        quoted_columns.join(", "),
+        values_placeholders(columns.len(), row_count)
+    );
+
+    match driver {
+        DatabaseType::Postgres | DatabaseType::Sqlite => {
+            let excluded = match driver {
+                DatabaseType::Postgres => "EXCLUDED",
+                _ => "excluded",
+            };
+            sql.push_str(&format!(" ON CONFLICT ({})", quoted_conflict.join(", ")));
+            if quoted_update.is_empty() {
+                sql.push_str(" DO NOTHING");
+            } else {
+                let assignments: Vec<String> = quoted_update
+                    .iter()
+                    .map(|col| format!("{} = {}.{}", col, excluded, col))
+                    .collect();
+                sql.push_str(&format!(" DO UPDATE SET {}", assignments.join(", ")));
+            }
+        }
+        DatabaseType::Mysql => {
+            let assignments: Vec<String> = if quoted_update.is_empty() {
+                vec![format!("{} = {}", quoted_conflict[0], quoted_conflict[0])]
+            } else {
+                quoted_update
+                    .iter()
+                    .map(|col| format!("{} = VALUES({})", col, col))
+                    .collect()
+            };
+            sql.push_str(&format!(
+                " ON DUPLICATE KEY UPDATE {}",
+                assignments.join(", ")
+            ));
+        }
+    }
+
+    Ok(sql)
+}
+
+// The `(...)` row of a single-row `INSERT INTO ... VALUES (...)` taking exactly `width`
+// parameters, split into the statement before it and the row itself. None when anything
+// follows the row (more rows, ON CONFLICT, RETURNING), the row holds a string literal, or the
+// query is not such an INSERT.
+fn insert_values_row(query: &str, width: usize) -> Option<(&str, &str)> {
+    let open = INSERT_VALUES.find(query)?.end() - 1;
+    let mut depth = 0;
+    let mut close = None;
+    for (offset, c) in query[open..].char_indices() {
+        match c {
+            // Placeholders are renumbered textually, which would rewrite `$n` inside literals
+            '\'' => return None,
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(open + offset);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let close = close?;
+    if !query[close + 1..]
+        .trim()
+        .trim_end_matches(';')
+        .trim()
+        .is_empty()
+    {
+        return None;
+    }
+    let row = &query[open..=close];
+    let highest = PLACEHOLDER
+        .captures_iter(row)
+        .filter_map(|caps| caps[1].parse::<usize>().ok())
+        .max();
+    (width > 0 && highest == Some(width)).then_some((&query[..open], row))
+}
+
+// Statements `bulk_change` runs for its parameter sets. A single-row parameterized INSERT
+// becomes one multi-row INSERT per batch of up to `batch_size` sets (fewer if the backend's
+// parameter limit requires), its placeholders renumbered over the flattened values; any
+// other query, or sets of differing lengths, run once per set.
+pub fn bulk_statements<'q, T: Copy>(
+    driver: &DatabaseType,
+    query: &'q str,
+    params: &[Vec<T>],
+    batch_size: usize,
+) -> Vec<(Cow<'q, str>, Vec<T>)> {
+    let width = params.first().map_or(0, Vec::len);
+    let template = match params.iter().all(|param_set| param_set.len() == width) {
+        true => insert_values_row(query, width),
+        false => None,
+    };
+    let Some((head, row)) = template else {
+        return params
+            .iter()
+            .map(|param_set| (Cow::Borrowed(query), param_set.clone()))
+            .collect();
+    };
+
+    let rows_per_statement = batch_size.min(max_bind_parameters(driver) / width).max(1);
+    params
+        .chunks(rows_per_statement)
+        .map(|chunk| {
+            let rows: Vec<String> = (0..chunk.len())
+                .map(|index| {
+                    PLACEHOLDER
+                        .replace_all(row, |caps: &regex::Captures| {
+                            let slot = caps[1].parse::<usize>().unwrap_or(0);
+                            format!("${}", index * width + slot)
+                        })
+                        .into_owned()
+                })
+                .collect();
+            let sql = format!("{}{}", head, rows.join(", "));
+            (Cow::Owned(sql), chunk.iter().flatten().copied().collect())
+        })
+        .collect()
+}
+
+// Statement switching constraint checking between deferred (checked at commit) and
+// immediate for the rest of the current transaction. Postgres can target named constraints;
+// SQLite only defers foreign keys as a whole and resets the pragma at commit.
+pub fn build_set_constraints(
+    driver: &DatabaseType,
+    deferred: bool,
+    constraints: Option<&[String]>,
+) -> PyResult<String> {
+    match driver {
+        DatabaseType::Postgres => {
+            let target = match constraints {
+                Some(names) if !names.is_empty() => quote_identifiers(driver, names)?.join(", "),
+                _ => "ALL".to_string(),
+            };
+            let mode = if deferred { "DEFERRED" } else { "IMMEDIATE" };
+            Ok(format!("SET CONSTRAINTS {} {}", target, mode))
+        }
+        DatabaseType::Sqlite => {
+            if constraints.is_some_and(|names| !names.is_empty()) {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "SQLite cannot defer individual constraints",
+                ));
+            }
+            let value = if deferred { "ON" } else { "OFF" };
+            Ok(format!("PRAGMA defer_foreign_keys = {}", value))
+        }
+        DatabaseType::Mysql => Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+            "MySQL does not support deferred constraints",
+        )),
+    }
+}
+
+// Count and page statements for `query`: the count wraps it as a derived table, the page appends
+// LIMIT/OFFSET (so `query` keeps its ORDER BY and must not have its own LIMIT).
+pub fn build_page(query: &str, limit: u64, offset: u64) -> (String, String) {
+    let query = query.trim_end().trim_end_matches(';').trim_end();
+    (
+        format!("SELECT COUNT(*) AS total FROM ({}) AS page_source", query),
+        format!("{} LIMIT {} OFFSET {}", query, limit, offset),
+    )
+}
+
+// Single-quoted SQL string literal
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn single_char_option(key: &str, value: &PyAny) -> PyResult<String> {
+    let value: String = value.extract()?;
+    if value.chars().count() != 1 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "COPY option {:?} must be a single character",
+            key
+        )));
+    }
+    Ok(quote_literal(&value))
+}
+
+// `COPY table (columns) FROM STDIN` in CSV format. Supported options: `delimiter`, `quote`,
+// `escape` (single characters), `null` (the string read as NULL), `header` (bool) and
+// `encoding`.
+pub fn build_copy_from_csv(
+    table: &str,
+    columns: Option<&[String]>,
+    options: Option<&PyDict>,
+) -> PyResult<String> {
+    let driver = DatabaseType::Postgres;
+    let mut sql = format!("COPY {}", quote_identifier(&driver, table)?);
+    if let Some(columns) = columns.filter(|columns| !columns.is_empty()) {
+        sql.push_str(&format!(
+            " ({})",
+            quote_identifiers(&driver, columns)?.join(", ")
+        ));
+    }
+
+    let mut with = vec!["FORMAT csv".to_string()];
+    if let Some(options) = options {
+        for (key, value) in options.iter() {
+            let key: String = key.extract()?;
+            let option = match key.as_str() {
+                "delimiter" | "quote" | "escape" => {
+                    format!(
+                        "{} {}",
+                        key.to_uppercase(),
+                        single_char_option(&key, value)?
+                    )
+                }
+                "null" | "encoding" => {
+                    format!(
+                        "{} {}",
+                        key.to_uppercase(),
+                        quote_literal(&value.extract::<String>()?)
+                    )
+                }
+                "header" => format!("HEADER {}", value.extract::<bool>()?),
+                _ => {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Unsupported COPY option: {:?}",
+                        key
+                    )))
+                }
+            };
+            with.push(option);
+        }
+    }
+
+    sql.push_str(&format!(" FROM STDIN WITH ({})", with.join(", ")));
+    Ok(sql)
+}
+
+// `ORDER BY <column> ASC|DESC` for a sort column taken from untrusted input. The column must
+// appear verbatim in `allowed` and is quoted for the target backend; anything else is rejected
+// rather than interpolated.
+#[pyfunction]
+#[pyo3(signature = (column, allowed, direction="asc", driver=DatabaseType::Postgres))]
+pub fn order_by(
+    column: &str,
+    allowed: Vec<String>,
+    direction: &str,
+    driver: DatabaseType,
+) -> PyResult<String> {
+    if !allowed.iter().any(|candidate| candidate == column) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Sorting by {:?} is not allowed",
+            column
+        )));
+    }
+    let direction = match direction.to_ascii_lowercase().as_str() {
+        "asc" => "ASC",
+        "desc" => "DESC",
+        _ => {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid sort direction {:?}; expected 'asc' or 'desc'",
+                direction
+            )))
+        }
+    };
+    Ok(format!(
+        "ORDER BY {} {}",
+        quote_identifier(&driver, column)?,
+        direction
+    ))
+}
+
+// Comma-separated SELECT list for columns requested by untrusted input (e.g. `?fields=`). Every
+// column must appear verbatim in `allowed`; repeats are dropped and the request order is kept.
+#[pyfunction]
+#[pyo3(signature = (columns, allowed, driver=DatabaseType::Postgres))]
+pub fn select_list(
+    columns: Vec<String>,
+    allowed: Vec<String>,
+    driver: DatabaseType,
+) -> PyResult<String> {
+    if columns.is_empty() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "At least one column must be selected",
+        ));
+    }
+    let rejected: Vec<&String> = columns
+        .iter()
+        .filter(|column| !allowed.contains(column))
+        .collect();
+    if !rejected.is_empty() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Selecting {:?} is not allowed",
+            rejected
+        )));
+    }
+    let mut selected: Vec<String> = Vec::with_capacity(columns.len());
+    for column in columns {
+        if !selected.contains(&column) {
+            selected.push(column);
+        }
+    }
+    Ok(quote_identifiers(&driver, &selected)?.join(", "))
+}
+
+// Date part of a datetime parameter meant for a `date` column. PostgreSQL only truncates a
+// timestamp on assignment and explicit casts; compared with a date column it is compared as a
+// timestamp, so `d = $1` would not match a datetime with a time of day. An aware datetime keeps
+// the date of its own time zone. Dates and None are returned unchanged.
+#[pyfunction]
+pub fn as_date(value: &PyAny) -> PyResult<&PyAny> {
+    if value.is_instance_of::<PyDateTime>() {
+        value.call_method0("date")
+    } else if value.is_none() || value.is_instance_of::<PyDate>() {
+        Ok(value)
+    } else {
+        Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "as_date expects a date, datetime or None, not {}",
+            value.get_type().name()?
+        )))
+    }
+}
+
+// PostgreSQL full-text search over `columns`: returns the `@@` match condition, the `ts_rank`
+// expression and the parameters both use (text search config, then the search text), numbered
+// from `$<parameter>` so the fragments can follow parameters the caller already has.
+#[pyfunction]
+#[pyo3(signature = (columns, query, config="english", mode="plain", parameter=1))]
+pub fn text_search(
+    columns: Vec<String>,
+    query: &str,
+    config: &str,
+    mode: &str,
+    parameter: usize,
+) -> PyResult<(String, String, Vec<String>)> {
+    if columns.is_empty() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "text_search needs at least one column",
+        ));
+    }
+    if parameter == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "Placeholders are numbered from 1",
+        ));
+    }
+    let function = match mode {
+        "plain" => "plainto_tsquery",
+        "phrase" => "phraseto_tsquery",
+        "websearch" => "websearch_to_tsquery",
+        "raw" => "to_tsquery",
+        other => {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid search mode {:?}; expected 'plain', 'phrase', 'websearch' or 'raw'",
+                other
+            )))
+        }
+    };
+    let document = quote_identifiers(&DatabaseType::Postgres, &columns)?
+        .iter()
+        .map(|column| format!("coalesce({}::text, '')", column))
+        .collect::<Vec<_>>()
+        .join(" || ' ' || ");
+    let (config_param, query_param) = (parameter, parameter + 1);
+    let vector = format!("to_tsvector(${}::regconfig, {})", config_param, document);
+    let tsquery = format!(
+        "{}(${}::regconfig, ${})",
+        function, config_param, query_param
+    );
+    Ok((
+        format!("{} @@ {}", vector, tsquery),
+        format!("ts_rank({}, {})", vector, tsquery),
+        vec![config.to_string(), query.to_string()],
+    ))
+}
+
+fn flatten_tuple<'p>(value: &'p PyAny, leaves: &mut Vec<&'p PyAny>) -> PyResult<usize> {
+    match value.downcast::<PyTuple>() {
+        Ok(tuple) => {
+            if tuple.is_empty() {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "Cannot bind an empty tuple",
+                ));
+            }
+            let mut count = 0;
+            for item in tuple.iter() {
+                count += flatten_tuple(item, leaves)?;
+            }
+            Ok(count)
+        }
+        Err(_) => {
+            leaves.push(value);
+            Ok(1)
+        }
+    }
+}
+
+// `(…)` placeholder list mirroring the tuple's shape, numbering leaves from `next`.
+fn tuple_placeholders(value: &PyAny, next: &mut usize) -> String {
+    match value.downcast::<PyTuple>() {
+        Ok(tuple) => {
+            let items: Vec<String> = tuple
+                .iter()
+                .map(|item| tuple_placeholders(item, next))
+                .collect();
+            format!("({})", items.join(", "))
+        }
+        Err(_) => {
+            *next += 1;
+            format!("${}", *next - 1)
+        }
+    }
+}
+
+// Expand tuple parameters into row constructors so `(a, b) IN $1` with
+// `((1, 2), (3, 4))` becomes `(a, b) IN (($1, $2), ($3, $4))` and `a IN $1` with `(1, 2)`
+// becomes `a IN ($1, $2)`. Placeholders are renumbered over the flattened values; `$n` inside
+// literals and comments is left alone. Returns None when no parameter is a tuple, leaving the
+// query untouched.
+pub fn expand_tuple_parameters<'p>(
+    driver: &DatabaseType,
+    query: &str,
+    params: &[&'p PyAny],
+) -> PyResult<Option<(String, Vec<&'p PyAny>)>> {
+    if !params.iter().any(|param| param.is_instance_of::<PyTuple>()) {
+        return Ok(None);
+    }
+
+    let mut leaves = Vec::new();
+    let mut expansions = Vec::with_capacity(params.len());
+    for param in params {
+        let mut next = leaves.len() + 1;
+        flatten_tuple(param, &mut leaves)?;
+        expansions.push(tuple_placeholders(param, &mut next));
+    }
+
+    let mut missing = None;
+    let expanded = replace_placeholders(driver, query, |index| {
+        match index.checked_sub(1).and_then(|i| expansions.get(i)) {
+            Some(expansion) => expansion.clone(),
+            None => {
+                missing = Some(index);
+                format!("${}", index)
+            }
+        }
+    });
+
+    if let Some(index) = missing {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Placeholder ${} has no matching parameter ({} provided)",
+            index,
+            params.len()
+        )));
+    }
+    Ok(Some((expanded, leaves)))
+}
+
+// Wrap the placeholder of every `Composite` parameter in `jsonb_populate_record`, which builds
+// the named composite type from the JSON object bound there by attribute name (absent ones
+// are NULL), cast that of every `EnumValue` to its enum type, that of every int past
+// bigint's range to numeric and, with `uuid_strings`, that of every str that parses as a UUID
+// to uuid; `$n` inside literals and comments is left alone. Returns None when there are none,
+// leaving the query untouched.
+pub fn cast_typed_parameters(
+    query: &str,
+    params: &[&PyAny],
+    uuid_strings: bool,
+) -> PyResult<Option<String>> {
+    let is_uuid_string = |param: &PyAny| uuid_strings && uuid_string(param).is_some();
+    if !params.iter().any(|param| {
+        param.is_instance_of::<Composite>()
+            || param.is_instance_of::<EnumValue>()
+            || is_wide_int(param)
+            || is_uuid_string(param)
+    }) {
+        return Ok(None);
+    }
+    let mut casts = Vec::with_capacity(params.len());
+    for param in params {
+        casts.push(if let Ok(composite) = param.extract::<PyRef<Composite>>() {
+            let type_name = quote_identifier(&DatabaseType::Postgres, &composite.type_name)?;
+            Some(format!("jsonb_populate_record(NULL::{}, {{}})", type_name))
+        } else if let Ok(value) = param.extract::<PyRef<EnumValue>>() {
+            let type_name = quote_identifier(&DatabaseType::Postgres, &value.type_name)?;
+            Some(format!("CAST({{}} AS {})", type_name))
+        } else if is_wide_int(param) {
+            Some("CAST({} AS numeric)".to_string())
+        } else if is_uuid_string(param) {
+            Some("CAST({} AS uuid)".to_string())
+        } else {
+            None
+        });
+    }
+    let cast = replace_placeholders(&DatabaseType::Postgres, query, |index| {
+        let placeholder = format!("${}", index);
+        match index.checked_sub(1).and_then(|i| casts.get(i)) {
+            Some(Some(cast)) => cast.replace("{}", &placeholder),
+            _ => placeholder,
+        }
+    });
+    Ok(Some(cast))
+}
+
+// A Python int outside bigint's range, which PostgreSQL gets as numeric. Its placeholder is
+// cast so the statement text differs from the bigint one: sqlx caches prepared statements
+// by text, and would otherwise send the other width's bytes to a statement prepared for one.
+pub fn is_wide_int(param: &PyAny) -> bool {
+    param.is_instance_of::<PyInt>() && param.extract::<i64>().is_err()
+}
+
+// A str in one of the forms `uuid.UUID` accepts (hyphenated, 32 hex digits, braced, urn:uuid:),
+// parsed. Cast like a wide int, so its statement is not the one cached for a text parameter.
+pub fn uuid_string(param: &PyAny) -> Option<Uuid> {
+    Uuid::try_parse(param.downcast::<PyString>().ok()?.to_str().ok()?).ok()
+}
+
+fn is_name_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+// End (exclusive) of the quoted string or identifier opened at `start`; a doubled quote stays
+// inside, as does a backslash-escaped one when `backslash` is set (MySQL strings).
+fn quoted_end(bytes: &[u8], start: usize, backslash: bool) -> usize {
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        let escaped = backslash && bytes[i] == b'\\';
+        if escaped || (bytes[i] == quote && bytes.get(i + 1) == Some(&quote)) {
+            i += 2;
+        } else if bytes[i] == quote {
+            return i + 1;
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+// End (exclusive) of the string, quoted identifier, comment or PostgreSQL dollar-quoted body
+// that starts at byte `i` of `query`, or None when none starts there.
+fn literal_end(driver: &DatabaseType, query: &str, i: usize) -> Option<usize> {
+    let bytes = query.as_bytes();
+    let mysql = matches!(driver, DatabaseType::Mysql);
+    match bytes[i] {
+        b'\'' | b'"' => Some(quoted_end(bytes, i, mysql)),
+        b'`' if mysql => Some(quoted_end(bytes, i, false)),
+        b'-' if bytes.get(i + 1) == Some(&b'-') => {
+            Some(query[i..].find('\n').map_or(bytes.len(), |end| i + end))
+        }
+        b'#' if mysql => Some(query[i..].find('\n').map_or(bytes.len(), |end| i + end)),
+        b'/' if bytes.get(i + 1) == Some(&b'*') => Some(
+            query[i + 2..]
+                .find("*/")
+                .map_or(bytes.len(), |end| i + end + 4),
+        ),
+        b'$' if matches!(driver, DatabaseType::Postgres)
+            && (i == 0 || !is_name_byte(bytes[i - 1])) =>
+        {
+            DOLLAR_QUOTE.find(&query[i..]).map(|tag| {
+                let body = i + tag.end();
+                query[body..]
+                    .find(tag.as_str())
+                    .map_or(bytes.len(), |end| body + end + tag.end())
+            })
+        }
+        _ => None,
+    }
+}
+
+// Rewrite `:name` placeholders to `$n`, numbering each distinct name once in order of first use,
+// and return the names in that order. Strings, quoted identifiers, comments, PostgreSQL dollar
+// quotes and `::` casts are left alone, as is a colon right after a word character (`arr[1:n]`).
+fn rewrite_named_parameters<'q>(driver: &DatabaseType, query: &'q str) -> (String, Vec<&'q str>) {
+    let bytes = query.as_bytes();
+    let mut rewritten = String::with_capacity(query.len());
+    let mut names: Vec<&str> = Vec::new();
+    let (mut i, mut copied) = (0, 0);
+    while i < bytes.len() {
+        if let Some(end) = literal_end(driver, query, i) {
+            i = end;
+            continue;
+        }
+        match bytes[i] {
+            b':' if i > 0 && (is_name_byte(bytes[i - 1]) || bytes[i - 1] == b':') => i += 1,
+            b':' => match NAME.find(&query[i + 1..]) {
+                Some(name) => {
+                    let name = name.as_str();
+                    let index = match names.iter().position(|seen| *seen == name) {
+                        Some(position) => position + 1,
+                        None => {
+                            names.push(name);
+                            names.len()
+                        }
+                    };
+                    rewritten.push_str(&query[copied..i]);
+                    rewritten.push_str(&format!("${}", index));
+                    i += 1 + name.len();
+                    copied = i;
+                }
+                None => i += 1,
+            },
+            _ => i += 1,
+        }
+    }
+    rewritten.push_str(&query[copied..]);
+    (rewritten, names)
+}
+
+// `query` with its `:name` placeholders rewritten to `$n` (see `rewrite_named_parameters`),
+// and the values from `params` in that order. A name missing from `params` raises KeyError;
+// unused keys are ignored.
+pub fn bind_named_parameters<'p>(
+    driver: &DatabaseType,
+    query: &str,
+    params: &'p PyDict,
+) -> PyResult<(String, Vec<&'p PyAny>)> {
+    let (rewritten, names) = rewrite_named_parameters(driver, query);
+    let values = names
+        .into_iter()
+        .map(|name| {
+            params.get_item(name)?.ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyKeyError, _>(format!(
+                    "Named parameter :{} has no value",
+                    name
+                ))
+            })
+        })
+        .collect::<PyResult<_>>()?;
+    Ok((rewritten, values))
+}
+
+// (start, end, n) of each `$n` placeholder of `query`, skipping strings, quoted identifiers,
+// comments and PostgreSQL dollar-quoted bodies (a function's own `$1`).
+fn placeholders(driver: &DatabaseType, query: &str) -> Vec<(usize, usize, usize)> {
+    let bytes = query.as_bytes();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if let Some(end) = literal_end(driver, query, i) {
+            i = end;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        // `a$1` is an identifier, not a placeholder
+        if bytes[start] != b'$' || (start > 0 && is_name_byte(bytes[start - 1])) {
+            continue;
+        }
+        let digits = bytes[i..].iter().take_while(|b| b.is_ascii_digit()).count();
+        if let Ok(index) = query[i..i + digits].parse::<usize>() {
+            found.push((start, i + digits, index));
+        }
+        i += digits;
+    }
+    found
+}
+
+// The number of parameters `query` takes: its highest `$n`
+fn placeholder_count(driver: &DatabaseType, query: &str) -> usize {
+    placeholders(driver, query)
+        .iter()
+        .map(|&(_, _, index)| index)
+        .max()
+        .unwrap_or(0)
+}
+
+// `query` with each `$n` placeholder (see `placeholders`) replaced by `replace(n)`, in one pass
+// so `$1` never clobbers the prefix of `$10`
+pub fn replace_placeholders(
+    driver: &DatabaseType,
+    query: &str,
+    mut replace: impl FnMut(usize) -> String,
+) -> String {
+    let mut replaced = String::with_capacity(query.len());
+    let mut copied = 0;
+    for (start, end, index) in placeholders(driver, query) {
+        replaced.push_str(&query[copied..start]);
+        replaced.push_str(&replace(index));
+        copied = end;
+    }
+    replaced.push_str(&query[copied..]);
+    replaced
+}
+
+// Fail before anything is sent when `provided` parameters do not match the placeholders of
+// `query`, rather than with the driver's error (or a silently ignored extra value).
+pub fn check_parameter_count(driver: &DatabaseType, query: &str, provided: usize) -> PyResult<()> {
+    let expected = placeholder_count(driver, query);
+    if expected == provided {
+        return Ok(());
+    }
+    Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+        "Query expects {} parameter{} but {} {} provided",
+        expected,
+        if expected == 1 { "" } else { "s" },
+        provided,
+        if provided == 1 { "was" } else { "were" }
+    )))
+}
+
+// (query, params) with `:name` placeholders rewritten to positional ones for `driver`, for
+// methods that take a list of parameters; see `bind_named_parameters`.
+#[pyfunction]
+#[pyo3(signature = (query, params, driver=DatabaseType::Postgres))]
+pub fn named_parameters<'p>(
+    query: &str,
+    params: &'p PyDict,
+    driver: DatabaseType,
+) -> PyResult<(String, Vec<&'p PyAny>)> {
+    bind_named_parameters(&driver, query, params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INSERT: &str = "INSERT INTO t (a, b) VALUES ($1, $2)";
+
+    fn sets(count: usize, width: usize) -> Vec<Vec<usize>> {
+        (0..count)
+            .map(|set| (0..width).map(|slot| set * width + slot).collect())
+            .collect()
+    }
+
+    #[test]
+    fn single_row_insert_batches_into_multi_row_inserts() {
+        let statements = bulk_statements(&DatabaseType::Postgres, INSERT, &sets(5, 2), 2);
+        let sql: Vec<&str> = statements.iter().map(|(sql, _)| sql.as_ref()).collect();
+        assert_eq!(
+            sql,
+            [
+                "INSERT INTO t (a, b) VALUES ($1, $2), ($3, $4)",
+                "INSERT INTO t (a, b) VALUES ($1, $2), ($3, $4)",
+                "INSERT INTO t (a, b) VALUES ($1, $2)",
+            ]
+        );
+        let values: Vec<usize> = statements.into_iter().flat_map(|(_, v)| v).collect();
+        assert_eq!(values, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn placeholders_are_renumbered_per_row() {
+        let query = "insert into t (a, b) values ($2, lower($1));";
+        let statements = bulk_statements(&DatabaseType::Sqlite, query, &sets(2, 2), 10);
+        assert_eq!(
+            statements[0].0,
+            "insert into t (a, b) values ($2, lower($1)), ($4, lower($3))"
+        );
+    }
+
+    #[test]
+    fn parameter_limit_caps_rows_per_statement() {
+        let statements = bulk_statements(&DatabaseType::Sqlite, INSERT, &sets(20_000, 2), 50_000);
+        let rows: Vec<usize> = statements.iter().map(|(_, v)| v.len() / 2).collect();
+        assert_eq!(rows, [16_383, 3_617]);
+    }
+
+    #[test]
+    fn other_queries_run_once_per_set() {
+        for query in [
+            "UPDATE t SET a = $1 WHERE b = $2",
+            "INSERT INTO t (a, b) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            "INSERT INTO t (a, b) VALUES ($1, $2) RETURNING a",
+            "INSERT INTO t (a, b, c) VALUES ($1, $2, '$3')",
+            "INSERT INTO t (a, b) VALUES ($1, $2), ($3, $4)",
+            "INSERT INTO t (a) VALUES ($1)",
+        ] {
+            let statements = bulk_statements(&DatabaseType::Postgres, query, &sets(3, 2), 10);
+            assert_eq!(statements.len(), 3, "{query}");
+            assert!(statements.iter().all(|(sql, _)| sql == query), "{query}");
+        }
+    }
+
+    #[test]
+    fn sets_of_differing_lengths_run_once_each() {
+        let params = vec![vec![1, 2], vec![3]];
+        let statements = bulk_statements(&DatabaseType::Postgres, INSERT, &params, 10);
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[1], (Cow::Borrowed(INSERT), vec![3]));
+    }
+
+    fn indexes(driver: &DatabaseType, query: &str) -> Vec<usize> {
+        placeholders(driver, query)
+            .into_iter()
+            .map(|(_, _, index)| index)
+            .collect()
+    }
+
+    #[test]
+    fn placeholders_are_found_with_their_spans() {
+        let query = "SELECT $1, $10 FROM t WHERE a = $2";
+        assert_eq!(
+            placeholders(&DatabaseType::Postgres, query),
+            [(7, 9, 1), (11, 14, 10), (32, 34, 2)]
+        );
+    }
+
+    #[test]
+    fn placeholders_skip_literals_and_comments() {
+        let postgres = &DatabaseType::Postgres;
+        for query in [
+            "SELECT '$2', 'it''s $2', \"col$2\" FROM t WHERE a = $1",
+            "SELECT $1 -- and $2\n",
+            "SELECT /* $2 */ $1",
+            "SELECT $1, $$ $2 $$, $body$ SELECT $2 $body$",
+            "SELECT a$2, $1",
+        ] {
+            assert_eq!(indexes(postgres, query), [1], "{query}");
+        }
+        let mysql = &DatabaseType::Mysql;
+        for query in ["SELECT 'it\\'s $2', `col $2`, $1", "SELECT $1 # and $2\n"] {
+            assert_eq!(indexes(mysql, query), [1], "{query}");
+        }
+        // only PostgreSQL has dollar quotes
+        assert_eq!(indexes(&DatabaseType::Sqlite, "SELECT $$, $1"), [1]);
+        assert_eq!(indexes(&DatabaseType::Sqlite, "SELECT $a$ $1 $a$"), [1]);
+    }
+
+    #[test]
+    fn an_unterminated_literal_runs_to_the_end() {
+        assert!(indexes(&DatabaseType::Postgres, "SELECT 'open $1").is_empty());
+        assert!(indexes(&DatabaseType::Postgres, "SELECT /* open $1").is_empty());
+    }
+
+    #[test]
+    fn parameter_count_is_the_highest_placeholder() {
+        let postgres = &DatabaseType::Postgres;
+        assert_eq!(placeholder_count(postgres, "SELECT 1"), 0);
+        assert_eq!(placeholder_count(postgres, "SELECT $2, $1, $2"), 2);
+        assert_eq!(placeholder_count(postgres, "SELECT $3"), 3);
+        assert_eq!(placeholder_count(postgres, "SELECT '$9' || $1"), 1);
+    }
+
+    #[test]
+    fn replacing_placeholders_keeps_longer_numbers_whole() {
+        let query = "SELECT $1, $10, '$1'";
+        assert_eq!(
+            replace_placeholders(&DatabaseType::Sqlite, query, |_| "?".to_string()),
+            "SELECT ?, ?, '$1'"
+        );
+    }
+
+    fn named(driver: &DatabaseType, query: &str) -> (String, Vec<String>) {
+        let (rewritten, names) = rewrite_named_parameters(driver, query);
+        (rewritten, names.into_iter().map(str::to_string).collect())
+    }
+
+    #[test]
+    fn repeated_names_share_a_placeholder() {
+        assert_eq!(
+            named(
+                &DatabaseType::Postgres,
+                "SELECT * FROM t WHERE a = :id OR b = :other OR c = :id"
+            ),
+            (
+                "SELECT * FROM t WHERE a = $1 OR b = $2 OR c = $1".to_string(),
+                vec!["id".to_string(), "other".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn names_in_literals_casts_and_slices_are_left_alone() {
+        let query = "SELECT ':a', \":a\", $$ :a $$, x::text, arr[1:n], :b -- :a\n";
+        assert_eq!(
+            named(&DatabaseType::Postgres, query),
+            (
+                "SELECT ':a', \":a\", $$ :a $$, x::text, arr[1:n], $1 -- :a\n".to_string(),
+                vec!["b".to_string()]
+            )
+        );
+        assert_eq!(
+            named(&DatabaseType::Mysql, "SELECT 'it\\'s :a', `:a`, :b # :a"),
+            (
+                "SELECT 'it\\'s :a', `:a`, $1 # :a".to_string(),
+                vec!["b".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn a_lone_colon_is_not_a_name() {
+        assert_eq!(
+            named(&DatabaseType::Sqlite, "SELECT : , :1, :_a1"),
+            ("SELECT : , :1, $1".to_string(), vec!["_a1".to_string()])
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use pyo3::prelude::*;
+
+// A PostgreSQL range value (`int4range`, `int8range`, `numrange`, `daterange`, `tsrange`,
+// `tstzrange`). A None bound is unbounded on that side and never inclusive.
+#[pyclass(module = "sqlrustler")]
+#[derive(Debug, Clone)]
+pub struct Range {
+    #[pyo3(get)]
+    pub lower: PyObject,
+    #[pyo3(get)]
+    pub upper: PyObject,
+    #[pyo3(get)]
+    pub lower_inc: bool,
+    #[pyo3(get)]
+    pub upper_inc: bool,
+    #[pyo3(get)]
+    pub is_empty: bool,
+}
+
+impl Range {
+    pub fn from_bounds(
+        py: Python<'_>,
+        lower: Option<(PyObject, bool)>,
+        upper: Option<(PyObject, bool)>,
+    ) -> Self {
+        let (lower, lower_inc) = lower.unwrap_or_else(|| (py.None(), false));
+        let (upper, upper_inc) = upper.unwrap_or_else(|| (py.None(), false));
+        Range {
+            lower,
+            upper,
+            lower_inc,
+            upper_inc,
+            is_empty: false,
+        }
+    }
+
+    pub fn empty(py: Python<'_>) -> Self {
+        Range {
+            is_empty: true,
+            ..Range::from_bounds(py, None, None)
+        }
+    }
+}
+
+#[pymethods]
+impl Range {
+    #[new]
+    #[pyo3(signature = (lower=None, upper=None, lower_inc=true, upper_inc=false, is_empty=false))]
+    fn new(
+        py: Python<'_>,
+        lower: Option<PyObject>,
+        upper: Option<PyObject>,
+        lower_inc: bool,
+        upper_inc: bool,
+        is_empty: bool,
+    ) -> Self {
+        let lower = lower.filter(|value| !value.is_none(py));
+        let upper = upper.filter(|value| !value.is_none(py));
+        Range {
+            is_empty,
+            ..Range::from_bounds(
+                py,
+                lower.map(|value| (value, lower_inc)),
+                upper.map(|value| (value, upper_inc)),
+            )
+        }
+    }
+
+    fn __eq__(&self, py: Python<'_>, other: &Self) -> PyResult<bool> {
+        Ok(self.is_empty == other.is_empty
+            && self.lower_inc == other.lower_inc
+            && self.upper_inc == other.upper_inc
+            && self.lower.as_ref(py).eq(other.lower.as_ref(py))?
+            && self.upper.as_ref(py).eq(other.upper.as_ref(py))?)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        if self.is_empty {
+            return Ok("Range(is_empty=True)".to_string());
+        }
+        Ok(format!(
+            "Range(lower={}, upper={}, lower_inc={}, upper_inc={})",
+            self.lower.as_ref(py).repr()?,
+            self.upper.as_ref(py).repr()?,
+            if self.lower_inc { "True" } else { "False" },
+            if self.upper_inc { "True" } else { "False" },
+        ))
+    }
+}
@@ -0,0 +1,220 @@
+use pyo3::prelude::*;
+
+// Where a statement issued on a connection is sent when replicas are configured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    Primary,
+    Replica,
+}
+
+impl Route {
+    // Explicit override from Python (`"primary"` / `"replica"`); `None` classifies the query.
+    pub fn resolve(query: &str, route: Option<&str>) -> PyResult<Self> {
+        match route {
+            None => Ok(if is_read_only(query) {
+                Route::Replica
+            } else {
+                Route::Primary
+            }),
+            Some("primary") => Ok(Route::Primary),
+            Some("replica") => Ok(Route::Replica),
+            Some(other) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid route {:?}; expected 'primary' or 'replica'",
+                other
+            ))),
+        }
+    }
+}
+
+// SQL syntax that may be followed by a parenthesis without calling a function
+const PAREN_KEYWORDS: &str = "\
+    SELECT FROM JOIN ON USING WHERE AND OR NOT IN EXISTS ANY ALL SOME AS VALUES UNION \
+    INTERSECT EXCEPT OVER FILTER WITHIN BY HAVING LIMIT OFFSET CASE WHEN THEN ELSE LATERAL ROW \
+    ARRAY DISTINCT IS BETWEEN LIKE ILIKE SIMILAR TO ROLLUP CUBE SETS WITH RECURSIVE CAST \
+    EXTRACT POSITION SUBSTRING TRIM OVERLAY COALESCE NULLIF GREATEST LEAST ESCAPE";
+
+// Built-in functions, and types taking modifiers, known not to write or take locks
+const PURE_FUNCTIONS: &str = "\
+    COUNT SUM AVG MIN MAX ARRAY_AGG STRING_AGG JSON_AGG JSONB_AGG BOOL_AND BOOL_OR EVERY \
+    ROW_NUMBER RANK DENSE_RANK LAG LEAD FIRST_VALUE LAST_VALUE NTILE LOWER UPPER LENGTH \
+    CHAR_LENGTH CONCAT CONCAT_WS LEFT RIGHT REPLACE SPLIT_PART STRPOS LPAD RPAD LTRIM RTRIM \
+    BTRIM ABS ROUND CEIL CEILING FLOOR TRUNC MOD POWER SQRT NOW DATE_TRUNC DATE_PART TO_CHAR \
+    TO_DATE TO_TIMESTAMP TO_NUMBER TO_JSON TO_JSONB JSON_BUILD_OBJECT JSONB_BUILD_OBJECT \
+    JSON_BUILD_ARRAY JSONB_BUILD_ARRAY ARRAY_LENGTH CARDINALITY UNNEST GENERATE_SERIES IFNULL \
+    NUMERIC DECIMAL VARCHAR CHAR CHARACTER VARYING TIMESTAMP TIME INTERVAL BIT FLOAT";
+
+fn listed(words: &str, name: &str) -> bool {
+    words.split_whitespace().any(|word| word == name)
+}
+
+// Upper-cased keywords/identifiers of a statement with string literals, dollar-quoted bodies and
+// comments skipped. A quoted identifier becomes a `"` token, and `;` followed by more SQL, `(`
+// and `.` are kept as tokens so batches and function calls can be recognised.
+fn keywords(query: &str) -> Vec<String> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' | '"' | '`' => {
+                if c != '\'' {
+                    tokens.push("\"".to_string());
+                }
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == c {
+                        // Doubled quote is an escaped quote inside the literal
+                        if chars.get(i + 1) == Some(&c) {
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            '$' => {
+                // `$tag$ ... $tag$` (Postgres); plain `$1` placeholders fall through.
+                let tag_end = chars[i + 1..]
+                    .iter()
+                    .position(|ch| !(ch.is_alphanumeric() || *ch == '_'))
+                    .map(|offset| i + 1 + offset);
+                match tag_end {
+                    Some(end) if chars[end] == '$' && !chars[i + 1].is_ascii_digit() => {
+                        let tag: String = chars[i..=end].iter().collect();
+                        let rest: String = chars[end + 1..].iter().collect();
+                        let body_len = rest.find(&tag).unwrap_or(rest.len());
+                        i = end + 1 + rest[..body_len].chars().count() + tag.chars().count();
+                    }
+                    _ => i += 1,
+                }
+            }
+            ';' | '(' | '.' => {
+                tokens.push(c.to_string());
+                i += 1;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(chars[start..i].iter().collect::<String>().to_uppercase());
+            }
+            _ => i += 1,
+        }
+    }
+    while tokens.last().map(String::as_str) == Some(";") {
+        tokens.pop();
+    }
+    tokens
+}
+
+// Whether the token at `i`, followed by `(`, calls a function other than a known pure built-in
+// (user-defined functions, pg_advisory_lock, set_config and the like may write). Column lists
+// of aliases and CTEs are not calls.
+fn calls_function(tokens: &[String], i: usize) -> bool {
+    let name = tokens[i].as_str();
+    let before = i.checked_sub(1).map(|before| tokens[before].as_str());
+    if matches!(before, Some("AS" | "WITH" | "RECURSIVE")) {
+        return false;
+    }
+    if before == Some(".") {
+        let schema = i.checked_sub(2).map(|schema| tokens[schema].as_str());
+        return schema != Some("PG_CATALOG") || !listed(PURE_FUNCTIONS, name);
+    }
+    !(listed(PAREN_KEYWORDS, name) || listed(PURE_FUNCTIONS, name) || name == "(")
+}
+
+// Conservative read-only check: only plain SELECT / WITH ... SELECT statements (and VALUES)
+// qualify. Locking reads, SELECT INTO, data-modifying CTEs, function calls other than known
+// pure built-ins, multi-statement batches and anything unrecognised are treated as writes.
+pub fn is_read_only(query: &str) -> bool {
+    let tokens = keywords(query);
+    let Some(first) = tokens.iter().find(|token| *token != "(") else {
+        return false;
+    };
+    if !matches!(first.as_str(), "SELECT" | "WITH" | "VALUES" | "TABLE") {
+        return false;
+    }
+
+    !tokens
+        .iter()
+        .enumerate()
+        .any(|(i, token)| match token.as_str() {
+            ";" | "INTO" | "INSERT" | "UPDATE" | "DELETE" | "MERGE" | "LOCK" | "NEXTVAL"
+            | "SETVAL" => true,
+            "FOR" => matches!(
+                tokens.get(i + 1).map(String::as_str),
+                Some("UPDATE" | "SHARE" | "NO" | "KEY")
+            ),
+            _ => tokens.get(i + 1).map(String::as_str) == Some("(") && calls_function(&tokens, i),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_read_only;
+
+    #[test]
+    fn plain_reads_go_to_replicas() {
+        for query in [
+            "SELECT * FROM users WHERE id = $1",
+            "select count(*), max(age) from users",
+            "WITH recent AS (SELECT * FROM orders) SELECT lower(name) FROM recent",
+            "SELECT id FROM t WHERE id IN (SELECT id FROM u) AND EXISTS (SELECT 1)",
+            "SELECT CAST(price AS numeric(10, 2)) FROM items",
+            "SELECT * FROM (VALUES (1), (2)) AS v(n)",
+            "(SELECT 1) UNION (SELECT 2)",
+            "SELECT pg_catalog.lower(name) FROM users",
+            "SELECT 'pg_advisory_lock(1)' -- set_config(\n",
+            "VALUES (1)",
+        ] {
+            assert!(is_read_only(query), "{query}");
+        }
+    }
+
+    #[test]
+    fn writes_and_locks_stay_on_primary() {
+        for query in [
+            "INSERT INTO t VALUES (1)",
+            "SELECT * FROM t FOR UPDATE",
+            "SELECT id INTO backup FROM t",
+            "WITH gone AS (DELETE FROM t RETURNING *) SELECT * FROM gone",
+            "SELECT 1; DELETE FROM t",
+            "SELECT nextval('seq')",
+            "",
+        ] {
+            assert!(!is_read_only(query), "{query}");
+        }
+    }
+
+    #[test]
+    fn function_calls_are_ambiguous() {
+        for query in [
+            "SELECT pg_advisory_lock(1)",
+            "SELECT set_config('search_path', 'x', false)",
+            "SELECT pg_notify('channel', 'payload')",
+            "SELECT archive_order($1)",
+            "SELECT * FROM refresh_cache()",
+            "SELECT app.lower(name) FROM users",
+            "SELECT \"CamelCase\"(1)",
+            "SELECT count(*) FROM t WHERE touch(t.id)",
+        ] {
+            assert!(!is_read_only(query), "{query}");
+        }
+    }
+}
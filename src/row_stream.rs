@@ -0,0 +1,189 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use futures::{channel::mpsc, SinkExt, StreamExt};
+use pyo3::prelude::*;
+use sqlx::{Database, Executor, IntoArguments};
+use tokio::sync::Mutex;
+
+use super::{db_trait::DynamicParameterBinder, errors::database_error};
+
+// Set while a stream holds its transaction's connection. Other calls on the transaction check
+// it and raise rather than wait for the lock, which would block forever: the stream only
+// advances when its consumer asks for rows, usually from the thread that is waiting.
+#[derive(Clone, Debug, Default)]
+pub struct StreamBusy(Arc<AtomicBool>);
+
+impl StreamBusy {
+    pub fn check(&self) -> PyResult<()> {
+        match self.0.load(Ordering::Acquire) {
+            true => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "transaction is busy with an open stream",
+            )),
+            false => Ok(()),
+        }
+    }
+
+    fn claim(&self) -> StreamClaim {
+        self.0.store(true, Ordering::Release);
+        StreamClaim(self.0.clone())
+    }
+}
+
+// Clears the flag when the stream's worker finishes or is dropped
+struct StreamClaim(Arc<AtomicBool>);
+
+impl Drop for StreamClaim {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+// A query's rows pulled from the sqlx stream only as the consumer asks for them, each decoded
+// with the binder and then passed through `convert`; at most one converted row is buffered
+// ahead. The worker owns the SQL and its bound arguments, and holds the transaction lock until
+// the rows run out or the pump is dropped.
+pub struct RowPump<T> {
+    // Drives the row stream; it holds the transaction lock until it finishes or is dropped.
+    worker: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+    items: mpsc::Receiver<PyResult<T>>,
+}
+
+impl<T: Send + 'static> RowPump<T> {
+    pub fn new<DB, B, F>(
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, DB>>>>,
+        busy: &StreamBusy,
+        binder: B,
+        sql: String,
+        arguments: B::Arguments,
+        convert: F,
+    ) -> Self
+    where
+        DB: Database,
+        B: DynamicParameterBinder<Database = DB, Row = DB::Row> + Send + 'static,
+        B::Arguments: for<'q> IntoArguments<'q, DB> + Send + 'static,
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+        F: Fn(Python<'_>, PyObject) -> PyResult<T> + Send + 'static,
+    {
+        let (mut sender, items) = mpsc::channel(0);
+        let claim = busy.claim();
+        let worker = async move {
+            let _claim = claim;
+            let mut guard = transaction.lock_owned().await;
+            let Some(connection) = guard.as_mut() else {
+                let _ = sender
+                    .send(Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                        "Transaction is no longer active",
+                    )))
+                    .await;
+                return;
+            };
+            let mut rows = sqlx::query_with(&sql, arguments).fetch(&mut **connection);
+            while let Some(row) = rows.next().await {
+                let item = row.map_err(database_error).and_then(|row| {
+                    Python::with_gil(|py| convert(py, binder.bind_result(py, &row)?))
+                });
+                let failed = item.is_err();
+                if sender.send(item).await.is_err() || failed {
+                    break;
+                }
+            }
+        };
+        Self {
+            worker: Some(Box::pin(worker)),
+            items,
+        }
+    }
+
+    // Next converted row, or None once the rows are exhausted
+    pub fn next(&mut self) -> PyResult<Option<T>> {
+        let items = &mut self.items;
+        let worker = &mut self.worker;
+        let next =
+            futures::executor::block_on(futures::future::poll_fn(|cx: &mut Context<'_>| loop {
+                if let Poll::Ready(item) = items.poll_next_unpin(cx) {
+                    return Poll::Ready(item);
+                }
+                let Some(running) = worker.as_mut() else {
+                    return Poll::Ready(None);
+                };
+                if running.as_mut().poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+                // Once the worker finishes its sender is gone and the channel drains
+                *worker = None;
+            }));
+        if let Some(Err(_)) = next {
+            // the worker stops after an error; release the lock now, not at the next call
+            self.worker = None;
+        }
+        next.transpose()
+    }
+}
+
+// Iterator over a query's rows in lists of up to `chunk_size` dicts, fetched from the server
+// as it advances, so only one chunk is held at a time. The transaction is busy until the
+// iterator is exhausted or closed.
+#[pyclass]
+pub struct RowStream {
+    pump: Option<RowPump<PyObject>>,
+    chunk_size: usize,
+}
+
+impl RowStream {
+    pub fn new(pump: RowPump<PyObject>, chunk_size: usize) -> Self {
+        Self {
+            pump: Some(pump),
+            chunk_size,
+        }
+    }
+}
+
+#[pymethods]
+impl RowStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<Vec<PyObject>>> {
+        let Some(pump) = self.pump.as_mut() else {
+            return Ok(None);
+        };
+        let mut chunk = Vec::with_capacity(self.chunk_size);
+        while chunk.len() < self.chunk_size {
+            match pump.next() {
+                Ok(Some(row)) => chunk.push(row),
+                Ok(None) => break,
+                Err(e) => {
+                    self.close();
+                    return Err(e);
+                }
+            }
+        }
+        if chunk.len() < self.chunk_size {
+            // Exhausted: release the transaction now rather than when the iterator is freed
+            self.close();
+        }
+        Ok((!chunk.is_empty()).then_some(chunk))
+    }
+
+    // Stops the query and releases the transaction, which stays open
+    fn close(&mut self) {
+        self.pump = None;
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(&mut self, _exc_type: &PyAny, _exc_value: &PyAny, _traceback: &PyAny) {
+        self.close();
+    }
+}
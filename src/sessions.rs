@@ -0,0 +1,39 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+
+// A server-side session opened by a pool: the PostgreSQL backend pid with its start time
+// (pids are reused), or the MySQL connection id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Session {
+    pub id: i64,
+    pub started: Option<DateTime<Utc>>,
+}
+
+// Sessions a pool has opened, so they can be killed from outside the pool when
+// its connections are all busy. Entries for closed connections are pruned lazily.
+#[derive(Debug, Clone, Default)]
+pub struct SessionRegistry {
+    sessions: Arc<Mutex<Vec<Session>>>,
+}
+
+impl SessionRegistry {
+    // Record a new session; returns true once the registry has grown past `limit`
+    // and should be pruned against the sessions still alive on the server.
+    pub fn register(&self, session: Session, limit: usize) -> bool {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.push(session);
+        sessions.len() > limit
+    }
+
+    pub fn retain_live(&self, live: &[Session]) {
+        self.sessions
+            .lock()
+            .unwrap()
+            .retain(|session| live.contains(session));
+    }
+
+    pub fn snapshot(&self) -> Vec<Session> {
+        self.sessions.lock().unwrap().clone()
+    }
+}
@@ -1,17 +1,54 @@
-use std::sync::Arc;
+use std::{
+    borrow::Cow,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use super::db_trait::{DatabaseOperations, DynamicParameterBinder};
-use futures::StreamExt;
-use pyo3::{prelude::*, types::PyDict};
-use regex::Regex;
+use super::{
+    config::{DatabaseType, TypeSettings},
+    db_trait::{
+        active, column_descriptions, ensure_no_parameters, fetch_in_batches, BulkChanges,
+        ColumnDescription, DatabaseOperations, DynamicParameterBinder,
+    },
+    errors::{database_error, encode_error, fetch_one_error},
+    json::py_to_json,
+    postgresql::{
+        extract_date, extract_datetime, extract_time, extract_utc_datetime, is_decimal, is_naive,
+        is_uuid, new_py_datetime,
+    },
+    query_builder::{
+        bulk_statements, check_parameter_count, expand_tuple_parameters, replace_placeholders,
+    },
+};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use futures::TryStreamExt;
+use pyo3::{
+    prelude::*,
+    types::{
+        timezone_utc, PyBool, PyByteArray, PyBytes, PyDate, PyDateTime, PyDict, PyList, PyTime,
+        PyTuple,
+    },
+};
 use sqlx::{
-    query::Query,
-    sqlite::{SqliteArguments, SqliteRow},
-    Column, Row, Sqlite, ValueRef,
+    sqlite::{SqliteArguments, SqliteConnection, SqliteQueryResult, SqliteRow},
+    types::Json,
+    Arguments, Column, Executor, IntoArguments, Row, Sqlite, TypeInfo, ValueRef,
 };
 use tokio::sync::Mutex;
 
-pub struct SqliteParameterBinder;
+// Arguments holding only owned values, which sqlx's `SqliteArguments<'q>` does not express:
+// they can run with SQL of any lifetime, rather than only the SQL they were bound for
+pub struct OwnedSqliteArguments(SqliteArguments<'static>);
+
+impl<'q> IntoArguments<'q, Sqlite> for OwnedSqliteArguments {
+    fn into_arguments(self) -> SqliteArguments<'q> {
+        self.0
+    }
+}
+
+pub struct SqliteParameterBinder {
+    settings: Arc<TypeSettings>,
+}
 
 impl SqliteParameterBinder {
     fn convert_sql_params<'q>(
@@ -19,20 +56,23 @@ impl SqliteParameterBinder {
         query: &str,
         params: Vec<&'q PyAny>,
     ) -> Result<(String, Vec<&'q PyAny>), PyErr> {
-        let re = Regex::new(r"\$(\d+)").unwrap();
-
-        let params_extracted: Vec<String> = re
-            .find_iter(query)
-            .filter_map(|mat| Some(mat.as_str().to_string()))
-            .collect();
-
-        let mut converted_query = query.to_string();
         let mut param_values: Vec<&PyAny> = Vec::new();
+        let mut out_of_range: Option<usize> = None;
 
-        for p in params_extracted {
-            converted_query = converted_query.replace(&p, "?");
-            let index = p[1..].parse::<usize>().unwrap();
-            param_values.push(params[index - 1]);
+        let converted_query = replace_placeholders(&DatabaseType::Sqlite, query, |index| {
+            match index.checked_sub(1).and_then(|i| params.get(i)) {
+                Some(param) => param_values.push(*param),
+                None => out_of_range = Some(index),
+            }
+            "?".to_string()
+        });
+
+        if let Some(index) = out_of_range {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Placeholder ${} has no matching parameter ({} provided)",
+                index,
+                params.len()
+            )));
         }
 
         Ok((converted_query, param_values))
@@ -41,7 +81,7 @@ impl SqliteParameterBinder {
 }
 
 impl DynamicParameterBinder for SqliteParameterBinder {
-    type Arguments = SqliteArguments<'static>;
+    type Arguments = OwnedSqliteArguments;
     type Database = Sqlite;
     type Row = SqliteRow;
 
@@ -50,100 +90,284 @@ impl DynamicParameterBinder for SqliteParameterBinder {
         &self,
         query: &'q str,
         params: Vec<&PyAny>,
-    ) -> Result<Query<'q, Self::Database, Self::Arguments>, PyErr> {
-        // Box the query string to give it a 'static lifetime
-        let (query_converted, params_converted) = self.convert_sql_params(query, params).unwrap();
-        let query_converted = String::leak(query_converted);
-
-        // Create a query with the boxed query string
-        let mut query = sqlx::query::<Sqlite>(query_converted);
+    ) -> Result<(Cow<'q, str>, Self::Arguments), PyErr> {
+        check_parameter_count(&DatabaseType::Sqlite, query, params.len())?;
+        let expanded = expand_tuple_parameters(&DatabaseType::Sqlite, query, &params)?;
+        let (query, params) = match &expanded {
+            Some((expanded, leaves)) => (expanded.as_str(), leaves.clone()),
+            None => (query, params),
+        };
+        let (query_converted, params_converted) = self.convert_sql_params(query, params)?;
 
         // Bind parameters dynamically
+        let mut arguments = SqliteArguments::default();
         for param in params_converted {
-            query = if let Ok(s) = param.extract::<String>() {
-                query.bind(s)
+            // SQLite columns are dynamically typed, so an untyped NULL fits any of them
+            let added = if param.is_none() {
+                arguments.add(None::<String>)
+            } else if let Ok(s) = param.extract::<String>() {
+                arguments.add(s)
+            } else if let Ok(bytes) = param.downcast::<PyBytes>() {
+                arguments.add(bytes.as_bytes().to_vec())
+            } else if let Ok(bytes) = param.downcast::<PyByteArray>() {
+                arguments.add(bytes.to_vec())
+            } else if param.is_instance_of::<PyBool>() {
+                // before int, as `True` is also an `int`
+                arguments.add(param.extract::<bool>()?)
             } else if let Ok(i) = param.extract::<i64>() {
-                query.bind(i)
+                arguments.add(i)
+            } else if is_decimal(param)? {
+                // as its digits, exactly; SQLite has no decimal type, and a NUMERIC column
+                // converts them itself
+                arguments.add(param.str()?.to_str()?.to_string())
+            } else if is_uuid(param)? {
+                // hyphenated, as on MySQL, since SQLite has no uuid type either
+                arguments.add(param.str()?.to_str()?.to_string())
             } else if let Ok(f) = param.extract::<f64>() {
-                query.bind(f)
-            } else if let Ok(b) = param.extract::<bool>() {
-                query.bind(b)
+                arguments.add(f)
+            } else if param.is_instance_of::<PyDateTime>() && !is_naive(param)? {
+                // text in SQLite's own format, so its date functions and comparisons work;
+                // aware values are converted to UTC and keep the offset
+                let utc = extract_utc_datetime(param)?.naive_utc();
+                arguments.add(format!("{}+00:00", utc.format("%F %T%.f")))
+            } else if param.is_instance_of::<PyDateTime>() {
+                arguments.add(extract_datetime(param)?)
+            } else if param.is_instance_of::<PyDate>() {
+                arguments.add(extract_date(param)?)
+            } else if param.is_instance_of::<PyTime>() {
+                arguments.add(extract_time(param)?)
+            } else if param.is_instance_of::<PyDict>() || param.is_instance_of::<PyList>() {
+                // stored as JSON text, which SQLite's json functions read
+                arguments.add(Json(py_to_json(param)?))
             } else {
                 return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
                     "Unsupported parameter type: {:?}",
                     param.get_type()
                 )));
             };
+            added.map_err(encode_error)?;
         }
-
-        // Transmute to 'static lifetime (safe because we've boxed the string)
-        // This is a bit of a hack, but necessary to satisfy the lifetime requirements
-        unsafe { std::mem::transmute(query) }
+        Ok((Cow::Owned(query_converted), OwnedSqliteArguments(arguments)))
     }
 
     fn bind_result(&self, py: Python<'_>, row: &SqliteRow) -> Result<PyObject, PyErr> {
         let dict = PyDict::new(py);
-
         for (i, column) in row.columns().iter().enumerate() {
-            let column_name = column.name();
-
-            // Dynamically handle different column types
-            match row.try_get_raw(i) {
-                Ok(val) => {
-                    if val.is_null() {
-                        dict.set_item(column_name, py.None())?;
-                    } else if let Ok(int_val) = row.try_get::<i32, _>(i) {
-                        dict.set_item(column_name, int_val)?;
-                    } else if let Ok(float_val) = row.try_get::<f64, _>(i) {
-                        dict.set_item(column_name, float_val)?;
-                    } else if let Ok(bool_val) = row.try_get::<bool, _>(i) {
-                        dict.set_item(column_name, bool_val)?;
-                    } else if let Ok(string_val) = row.try_get::<String, _>(i) {
-                        dict.set_item(column_name, string_val)?;
-                    } else {
-                        return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
-                            "Unsupported column type: {:?}",
-                            val.type_info()
-                        )));
-                    }
-                }
-                Err(e) => {
-                    return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                        e.to_string(),
-                    ))
+            dict.set_item(column.name(), column_value(py, &self.settings, row, i)?)?;
+        }
+        Ok(dict.into())
+    }
+
+    fn bind_result_tuple(&self, py: Python<'_>, row: &SqliteRow) -> Result<PyObject, PyErr> {
+        let values = (0..row.len())
+            .map(|i| column_value(py, &self.settings, row, i))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(PyTuple::new(py, values).into())
+    }
+}
+
+// A value stored in a DATETIME column: text with an offset (or `Z`) is an instant, text
+// without one a local time; numbers are Unix seconds (INTEGER) or Julian days (REAL).
+enum StoredDatetime {
+    Naive(NaiveDateTime),
+    Utc(DateTime<Utc>),
+}
+
+fn parse_datetime_text(text: &str) -> Option<StoredDatetime> {
+    const NAIVE: [&str; 4] = ["%F %T%.f", "%F %R", "%FT%T%.f", "%FT%R"];
+    const AWARE: [&str; 4] = ["%F %T%.f%:z", "%F %R%:z", "%FT%T%.f%:z", "%FT%R%:z"];
+    if let Some(instant) = NAIVE
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    {
+        return Some(StoredDatetime::Naive(instant));
+    }
+    if let Some(utc) = text.strip_suffix('Z') {
+        return NAIVE
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(utc, format).ok())
+            .map(|instant| StoredDatetime::Utc(instant.and_utc()));
+    }
+    AWARE
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(text, format).ok())
+        .map(|instant| StoredDatetime::Utc(instant.with_timezone(&Utc)))
+}
+
+// Columns declared DATETIME/TIMESTAMP, DATE or TIME, decoded like their PostgreSQL
+// counterparts. Only the declared type counts, so expressions (including CAST(col AS TEXT))
+// keep their text; so does a value that does not parse.
+fn decode_datetime_column(py: Python<'_>, row: &SqliteRow, i: usize) -> PyResult<Option<PyObject>> {
+    Ok(match row.column(i).type_info().name() {
+        "DATETIME" => {
+            let stored = match row.try_get::<&str, _>(i) {
+                Ok(text) => parse_datetime_text(text),
+                Err(_) => row
+                    .try_get::<DateTime<Utc>, _>(i)
+                    .ok()
+                    .map(StoredDatetime::Utc),
+            };
+            match stored {
+                Some(StoredDatetime::Naive(v)) => Some(new_py_datetime(py, &v, None)?.into()),
+                Some(StoredDatetime::Utc(v)) => {
+                    Some(new_py_datetime(py, &v.naive_utc(), Some(timezone_utc(py)))?.into())
                 }
+                None => None,
             }
         }
+        "DATE" => match row.try_get::<NaiveDate, _>(i) {
+            Ok(v) => Some(PyDate::new(py, v.year(), v.month() as u8, v.day() as u8)?.into()),
+            Err(_) => None,
+        },
+        "TIME" => match row.try_get::<NaiveTime, _>(i) {
+            Ok(v) => Some(
+                PyTime::new(
+                    py,
+                    v.hour() as u8,
+                    v.minute() as u8,
+                    v.second() as u8,
+                    v.nanosecond() / 1000,
+                    None,
+                )?
+                .into(),
+            ),
+            Err(_) => None,
+        },
+        _ => None,
+    })
+}
 
-        Ok(dict.into())
+// Dynamically handle different column types
+fn column_value(
+    py: Python<'_>,
+    settings: &TypeSettings,
+    row: &SqliteRow,
+    i: usize,
+) -> PyResult<PyObject> {
+    let val = row
+        .try_get_raw(i)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    if val.is_null() {
+        return Ok(py.None());
+    }
+    if settings.sqlite_datetimes {
+        if let Some(value) = decode_datetime_column(py, row, i)? {
+            return Ok(value);
+        }
     }
+    // declared BOOLEAN columns hold 0/1, which would otherwise read as ints
+    if row.column(i).type_info().name() == "BOOLEAN" {
+        if let Ok(bool_val) = row.try_get::<bool, _>(i) {
+            return Ok(bool_val.into_py(py));
+        }
+    }
+    Ok(if let Ok(int_val) = row.try_get::<i64, _>(i) {
+        int_val.into_py(py)
+    } else if let Ok(float_val) = row.try_get::<f64, _>(i) {
+        float_val.into_py(py)
+    } else if let Ok(bool_val) = row.try_get::<bool, _>(i) {
+        bool_val.into_py(py)
+    } else if let Ok(string_val) = row.try_get::<String, _>(i) {
+        string_val.into_py(py)
+    } else if let Ok(blob_val) = row.try_get::<Vec<u8>, _>(i) {
+        PyBytes::new(py, &blob_val).into()
+    } else {
+        return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "Unsupported column type: {:?}",
+            val.type_info()
+        )));
+    })
 }
 
 #[derive(Debug, Clone, Default)]
 #[pyclass]
-pub struct SqliteDatabase;
+pub struct SqliteDatabase {
+    settings: Arc<TypeSettings>,
+    // SQLite has no statement_timeout, so it is enforced here for each statement
+    statement_timeout: Option<Duration>,
+}
+
+impl SqliteDatabase {
+    pub fn new(settings: Arc<TypeSettings>, statement_timeout: Option<Duration>) -> Self {
+        Self {
+            settings,
+            statement_timeout,
+        }
+    }
+
+    // This handle with `timeout` in place of the configured statement timeout, if given
+    pub fn with_statement_timeout(self, timeout: Option<Duration>) -> Self {
+        match timeout {
+            Some(timeout) => Self {
+                statement_timeout: Some(timeout),
+                ..self
+            },
+            None => self,
+        }
+    }
+
+    // Interrupt the statement about to run once `statement_timeout` has elapsed, through
+    // SQLite's progress handler. `disarm` removes it again so COMMIT and statements issued by
+    // sqlx itself are never interrupted.
+    async fn arm(&self, connection: &mut SqliteConnection) -> PyResult<()> {
+        if let Some(timeout) = self.statement_timeout {
+            let deadline = Instant::now() + timeout;
+            connection
+                .lock_handle()
+                .await
+                .map_err(database_error)?
+                .set_progress_handler(1000, move || Instant::now() < deadline);
+        }
+        Ok(())
+    }
+
+    async fn disarm(&self, connection: &mut SqliteConnection) {
+        if self.statement_timeout.is_some() {
+            if let Ok(mut handle) = connection.lock_handle().await {
+                handle.remove_progress_handler();
+            }
+        }
+    }
+}
 
 impl DatabaseOperations for SqliteDatabase {
     type Row = SqliteRow;
-    type Arguments = SqliteArguments<'static>;
+    type Arguments = OwnedSqliteArguments;
     type DatabaseType = sqlx::Sqlite;
     type ParameterBinder = SqliteParameterBinder;
 
+    fn binder(&self) -> Self::ParameterBinder {
+        SqliteParameterBinder {
+            settings: self.settings.clone(),
+        }
+    }
+
     async fn execute(
         &mut self,
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, sqlx::Sqlite>>>>,
         query: &str,
         params: Vec<&PyAny>,
+        prepared: bool,
     ) -> Result<u64, PyErr> {
-        let query_builder = SqliteParameterBinder.bind_parameters(query, params)?;
+        let binder = self.binder();
+        if !prepared {
+            ensure_no_parameters(&params)?;
+            let mut guard = transaction.lock().await;
+            let transaction = active(&mut guard)?;
+            self.arm(transaction).await?;
+            let result = sqlx::raw_sql(query).execute(&mut **transaction).await;
+            self.disarm(transaction).await;
+            return Ok(result.map_err(database_error)?.rows_affected());
+        }
+        let (sql, arguments) = binder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
         let mut guard = transaction.lock().await;
-        let transaction = guard.as_mut().unwrap();
-        let result = query_builder
-            .execute(&mut **transaction)
-            .await
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        let transaction = active(&mut guard)?;
+        self.arm(transaction).await?;
+        let result = query_builder.execute(&mut **transaction).await;
+        self.disarm(transaction).await;
 
-        Ok(result.rows_affected())
+        Ok(result.map_err(database_error)?.rows_affected())
     }
 
     async fn fetch_all(
@@ -152,89 +376,168 @@ impl DatabaseOperations for SqliteDatabase {
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
         query: &str,
         params: Vec<&PyAny>,
+        fetch_size: Option<usize>,
+        prepared: bool,
     ) -> Result<Vec<PyObject>, PyErr> {
-        let query_builder = SqliteParameterBinder.bind_parameters(query, params)?;
+        let binder = self.binder();
+        if !prepared {
+            ensure_no_parameters(&params)?;
+            let mut guard = transaction.lock().await;
+            let transaction = active(&mut guard)?;
+            let raw = sqlx::raw_sql(query);
+            self.arm(transaction).await?;
+            let rows = match fetch_size {
+                Some(fetch_size) => {
+                    let stream = raw.fetch(&mut **transaction);
+                    let result =
+                        fetch_in_batches(stream, fetch_size, |row| binder.bind_result(py, row))
+                            .await;
+                    self.disarm(transaction).await;
+                    return result;
+                }
+                None => raw.fetch_all(&mut **transaction).await,
+            };
+            self.disarm(transaction).await;
+            return rows
+                .map_err(database_error)?
+                .iter()
+                .map(|row| binder.bind_result(py, row))
+                .collect();
+        }
+        let (sql, arguments) = binder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
         let mut guard = transaction.lock().await;
-        let transaction = guard.as_mut().unwrap();
-        let rows = query_builder
-            .fetch_all(&mut **transaction)
-            .await
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        let transaction = active(&mut guard)?;
+        self.arm(transaction).await?;
+        if let Some(fetch_size) = fetch_size {
+            let stream = query_builder.fetch(&mut **transaction);
+            let result =
+                fetch_in_batches(stream, fetch_size, |row| binder.bind_result(py, row)).await;
+            self.disarm(transaction).await;
+            return result;
+        }
+        let rows = query_builder.fetch_all(&mut **transaction).await;
+        self.disarm(transaction).await;
+        let rows = rows.map_err(database_error)?;
 
         let result: Vec<PyObject> = rows
             .iter()
-            .map(|row| SqliteParameterBinder.bind_result(py, row))
+            .map(|row| binder.bind_result(py, row))
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(result)
     }
 
-    async fn stream_data(
+    async fn fetch_all_tuples(
         &mut self,
         py: Python<'_>,
-        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, sqlx::Sqlite>>>>,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
         query: &str,
         params: Vec<&PyAny>,
-        chunk_size: usize,
-    ) -> PyResult<Vec<Vec<PyObject>>> {
-        let query_builder = SqliteParameterBinder.bind_parameters(query, params)?;
-        let mut guard = transaction.lock().await.take().unwrap();
-        let mut stream = query_builder.fetch(&mut *guard);
-        let mut chunks: Vec<Vec<PyObject>> = Vec::new();
-        let mut current_chunk: Vec<PyObject> = Vec::new();
-
-        while let Some(row_result) = stream.next().await {
-            match row_result {
-                Ok(row) => {
-                    let row_data: PyObject = SqliteParameterBinder.bind_result(py, &row)?;
-                    current_chunk.push(row_data);
-
-                    if current_chunk.len() >= chunk_size {
-                        chunks.push(current_chunk);
-                        current_chunk = Vec::new();
-                    }
-                }
-                Err(e) => {
-                    return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                        e.to_string(),
-                    ));
-                }
-            }
-        }
+    ) -> PyResult<Vec<PyObject>> {
+        let binder = self.binder();
+        let (sql, arguments) = binder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
+        let mut guard = transaction.lock().await;
+        let transaction = active(&mut guard)?;
+        self.arm(transaction).await?;
+        let rows = query_builder.fetch_all(&mut **transaction).await;
+        self.disarm(transaction).await;
+        rows.map_err(database_error)?
+            .iter()
+            .map(|row| binder.bind_result_tuple(py, row))
+            .collect()
+    }
 
-        if !current_chunk.is_empty() {
-            chunks.push(current_chunk);
-        }
-        Ok(chunks)
+    async fn describe(
+        &mut self,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
+        query: &str,
+    ) -> PyResult<Vec<ColumnDescription>> {
+        let mut guard = transaction.lock().await;
+        let describe = (&mut **active(&mut guard)?)
+            .describe(query)
+            .await
+            .map_err(database_error)?;
+        Ok(column_descriptions(&describe))
+    }
+
+    async fn fetch_one(
+        &mut self,
+        py: Python<'_>,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
+        query: &str,
+        params: Vec<&PyAny>,
+    ) -> PyResult<PyObject> {
+        let binder = self.binder();
+        let (sql, arguments) = binder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
+        let mut guard = transaction.lock().await;
+        let transaction = active(&mut guard)?;
+        self.arm(transaction).await?;
+        let row = query_builder.fetch_one(&mut **transaction).await;
+        self.disarm(transaction).await;
+        binder.bind_result(py, &row.map_err(fetch_one_error)?)
+    }
+
+    async fn fetch_optional(
+        &mut self,
+        py: Python<'_>,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
+        query: &str,
+        params: Vec<&PyAny>,
+    ) -> PyResult<Option<PyObject>> {
+        let binder = self.binder();
+        let (sql, arguments) = binder.bind_parameters(query, params)?;
+        let query_builder = sqlx::query_with(&sql, arguments);
+        let mut guard = transaction.lock().await;
+        let transaction = active(&mut guard)?;
+        self.arm(transaction).await?;
+        let row = query_builder.fetch_optional(&mut **transaction).await;
+        self.disarm(transaction).await;
+        row.map_err(database_error)?
+            .map(|row| binder.bind_result(py, &row))
+            .transpose()
     }
 
     async fn bulk_change(
         &mut self,
+        py: Python<'_>,
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, Self::DatabaseType>>>>,
         query: &str,
         params: Vec<Vec<&PyAny>>,
         batch_size: usize,
-    ) -> Result<u64, PyErr> {
-        let mut total_affected: u64 = 0;
+        returning: bool,
+    ) -> Result<BulkChanges, PyErr> {
+        let binder = self.binder();
+        let mut changes = BulkChanges::default();
         let mut guard = transaction.lock().await;
-        let tx = guard.as_mut().ok_or_else(|| {
-            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("No active transaction")
-        })?;
-
-        // Process in batches
-        for chunk in params.chunks(batch_size) {
-            for param_set in chunk {
-                // Build query with current parameters
-                let query_builder = SqliteParameterBinder.bind_parameters(query, param_set.to_vec())?;
-                
-                // Execute query and accumulate affected rows
-                let result = query_builder.execute(&mut **tx).await.map_err(|e| {
-                    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string())
-                })?;
+        let tx = active(&mut guard)?;
 
-                total_affected += result.rows_affected();
+        for (statement, values) in
+            bulk_statements(&DatabaseType::Sqlite, query, &params, batch_size)
+        {
+            let (sql, arguments) = binder.bind_parameters(&statement, values)?;
+            let query_builder = sqlx::query_with(&sql, arguments);
+            if returning {
+                self.arm(tx).await?;
+                let results = (&mut **tx).fetch_many(query_builder).try_collect().await;
+                self.disarm(tx).await;
+                let results = results.map_err(database_error)?;
+                changes.add_results(results, SqliteQueryResult::rows_affected, |row| {
+                    binder.bind_result(py, row)
+                })?;
+                continue;
             }
+
+            // Execute query and accumulate affected rows
+            self.arm(tx).await?;
+            let result = query_builder.execute(&mut **tx).await;
+            self.disarm(tx).await;
+            let result = result.map_err(database_error)?;
+
+            changes.statement_counts.push(result.rows_affected());
         }
-        Ok(total_affected)
+        Ok(changes)
     }
 }
@@ -1,12 +1,36 @@
-use pyo3::prelude::*;
-use std::sync::Arc;
+use dashmap::DashMap;
+use once_cell::sync::OnceCell;
+use pyo3::{
+    prelude::*,
+    types::{PyBytes, PyDict, PyString},
+};
+use sha2::{Digest, Sha256};
+use sqlx::Executor;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 use tokio::sync::Mutex;
 use tracing::error;
 
-use crate::context::get_sql_connect;
+use crate::{connection::DatabaseConnection, context::get_sql_connect};
 
 use super::{
-    db_trait::DatabaseOperations, mysql::MySqlDatabase, postgresql::PostgresDatabase,
+    awaitable, checksum,
+    config::DatabaseType,
+    db_trait::{active, visit_rows, ColumnDescription, DatabaseOperations, DynamicParameterBinder},
+    errors::database_error,
+    msgpack,
+    mysql::{supports_insert_returning, MySqlDatabase},
+    ndjson::NdjsonReader,
+    numpy,
+    postgresql::PostgresDatabase,
+    query_builder,
+    row_stream::{RowPump, RowStream, StreamBusy},
     sqlite::SqliteDatabase,
 };
 
@@ -26,11 +50,156 @@ pub enum DatabaseTransactionType {
     ),
 }
 
+impl DatabaseTransactionType {
+    fn driver(&self) -> DatabaseType {
+        match self {
+            DatabaseTransactionType::Postgres(..) => DatabaseType::Postgres,
+            DatabaseTransactionType::MySql(..) => DatabaseType::Mysql,
+            DatabaseTransactionType::SQLite(..) => DatabaseType::Sqlite,
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl IsolationLevel {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "read uncommitted" => Ok(IsolationLevel::ReadUncommitted),
+            "read committed" => Ok(IsolationLevel::ReadCommitted),
+            "repeatable read" => Ok(IsolationLevel::RepeatableRead),
+            "serializable" => Ok(IsolationLevel::Serializable),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid isolation level {:?}; expected 'read uncommitted', 'read committed', \
+                 'repeatable read' or 'serializable'",
+                name
+            ))),
+        }
+    }
+
+    // An `IsolationLevel` or its name, as `execute_batch` and `transaction` take either
+    pub fn from_py(value: &PyAny) -> PyResult<Self> {
+        if let Ok(level) = value.extract::<IsolationLevel>() {
+            return Ok(level);
+        }
+        match value.extract::<&str>() {
+            Ok(name) => Self::parse(name),
+            Err(_) => Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                "isolation must be an IsolationLevel or str, not {}",
+                value.get_type().name()?
+            ))),
+        }
+    }
+
+    fn sql(self) -> &'static str {
+        match self {
+            IsolationLevel::ReadUncommitted => "READ UNCOMMITTED",
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+        }
+    }
+}
+
+// Isolation level and access mode a transaction is started with
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TransactionMode {
+    pub isolation: Option<IsolationLevel>,
+    pub read_only: bool,
+}
+
+// SQL applying a `TransactionMode` on one backend: run on the connection before BEGIN, right
+// after BEGIN, and before COMMIT/ROLLBACK to undo connection-level settings.
+#[derive(Debug, Clone, Default)]
+pub struct ModeStatements {
+    pub before_begin: Option<String>,
+    pub after_begin: Option<String>,
+    pub before_end: Option<String>,
+}
+
+impl TransactionMode {
+    pub fn statements(&self, driver: DatabaseType) -> ModeStatements {
+        let mut characteristics = Vec::new();
+        if let Some(isolation) = self.isolation {
+            characteristics.push(format!("ISOLATION LEVEL {}", isolation.sql()));
+        }
+        if self.read_only {
+            characteristics.push("READ ONLY".to_string());
+        }
+        if characteristics.is_empty() {
+            return ModeStatements::default();
+        }
+        let set_transaction = format!("SET TRANSACTION {}", characteristics.join(", "));
+        match driver {
+            // Must be the first statement of the transaction
+            DatabaseType::Postgres => ModeStatements {
+                after_begin: Some(set_transaction),
+                ..Default::default()
+            },
+            // Applies to the next transaction started on the session
+            DatabaseType::Mysql => ModeStatements {
+                before_begin: Some(set_transaction),
+                ..Default::default()
+            },
+            // SQLite transactions are always SERIALIZABLE, which also gives the guarantees of
+            // the weaker levels; read-only is the connection's query_only pragma.
+            DatabaseType::Sqlite if self.read_only => ModeStatements {
+                after_begin: Some("PRAGMA query_only = ON".to_string()),
+                before_end: Some("PRAGMA query_only = OFF".to_string()),
+                ..Default::default()
+            },
+            DatabaseType::Sqlite => ModeStatements::default(),
+        }
+    }
+}
+
+// Cumulative counters for everything run through one transaction object
+#[derive(Debug, Default)]
+struct TransactionStats {
+    statements: AtomicU64,
+    rows_affected: AtomicU64,
+    rows_returned: AtomicU64,
+    elapsed_micros: AtomicU64,
+}
+
+impl TransactionStats {
+    fn record(&self, started: Instant, statements: u64, rows_affected: u64, rows_returned: u64) {
+        self.statements.fetch_add(statements, Ordering::Relaxed);
+        self.rows_affected
+            .fetch_add(rows_affected, Ordering::Relaxed);
+        self.rows_returned
+            .fetch_add(rows_returned, Ordering::Relaxed);
+        self.elapsed_micros
+            .fetch_add(started.elapsed().as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
 #[pyclass]
 #[derive(Clone, Debug)]
 pub struct DatabaseTransaction {
     transaction: DatabaseTransactionType,
     do_commit: bool,
+    // Connection the transaction was opened from; falls back to the global one when unset.
+    connection: Option<DatabaseConnection>,
+    // Whether a MySQL-protocol server takes INSERT ... RETURNING, detected on first use
+    mariadb_returning: Arc<OnceCell<bool>>,
+    // Applied to every transaction this object runs, including the ones commit and rollback
+    // open next
+    mode: Arc<ModeStatements>,
+    stats: Arc<TransactionStats>,
+    // Declared lengths of varchar(n)/char(n) columns by table, for `check_string_lengths`
+    column_lengths: Arc<DashMap<String, Arc<HashMap<String, usize>>>>,
+    // Names of the open savepoints, innermost last
+    savepoints: Arc<std::sync::Mutex<Vec<String>>>,
+    // Set while a row stream or NDJSON reader holds the connection
+    busy: StreamBusy,
 }
 
 impl DatabaseTransaction {
@@ -38,7 +207,145 @@ impl DatabaseTransaction {
         Self {
             transaction,
             do_commit: false,
+            connection: None,
+            mariadb_returning: Arc::new(OnceCell::new()),
+            mode: Arc::default(),
+            stats: Arc::default(),
+            column_lengths: Arc::default(),
+            savepoints: Arc::default(),
+            busy: StreamBusy::default(),
+        }
+    }
+
+    // The transaction, for a call that needs its connection; raises while a stream holds it
+    fn idle(&self) -> PyResult<DatabaseTransactionType> {
+        self.busy.check()?;
+        Ok(self.transaction.clone())
+    }
+
+    pub fn with_connection(mut self, connection: DatabaseConnection) -> Self {
+        self.connection = Some(connection);
+        self
+    }
+
+    pub fn with_mode(mut self, mode: ModeStatements) -> Self {
+        self.mode = Arc::new(mode);
+        self
+    }
+
+    // Declared lengths of the varchar(n)/char(n) columns of `table`, looked up once per table
+    fn column_lengths(&self, py: Python<'_>, table: &str) -> PyResult<Arc<HashMap<String, usize>>> {
+        if let Some(lengths) = self.column_lengths.get(table) {
+            return Ok(lengths.clone());
+        }
+        let query = match self.transaction.driver() {
+            DatabaseType::Postgres => {
+                "SELECT column_name::text AS name, character_maximum_length::int8 AS length \
+                 FROM information_schema.columns \
+                 WHERE table_name = $1 AND table_schema = COALESCE($2, current_schema()) \
+                 AND character_maximum_length IS NOT NULL"
+            }
+            DatabaseType::Mysql => {
+                "SELECT column_name AS name, CAST(character_maximum_length AS SIGNED) AS length \
+                 FROM information_schema.columns \
+                 WHERE table_name = $1 AND table_schema = COALESCE($2, DATABASE()) \
+                 AND data_type IN ('char', 'varchar')"
+            }
+            // SQLite does not enforce declared lengths
+            DatabaseType::Sqlite => return Ok(Arc::default()),
+        };
+        let (schema, name) = match table.rsplit_once('.') {
+            Some((schema, name)) => (Some(schema), name),
+            None => (None, table),
+        };
+        let params = vec![
+            PyString::new(py, name).as_ref(),
+            schema.into_py(py).into_ref(py),
+        ];
+        let mut lengths = HashMap::new();
+        for row in self.fetch_all(py, query, params, None, true, None)? {
+            let row = row.as_ref(py);
+            lengths.insert(
+                row.get_item("name")?.extract()?,
+                row.get_item("length")?.extract()?,
+            );
+        }
+        let lengths = Arc::new(lengths);
+        self.column_lengths
+            .insert(table.to_string(), lengths.clone());
+        Ok(lengths)
+    }
+
+    // With `check_string_lengths` set, reject a string longer than its column's varchar(n) or
+    // char(n) before it is sent: PostgreSQL and strict MySQL would fail the statement, and
+    // non-strict MySQL would silently truncate the value.
+    fn check_string_lengths(
+        &self,
+        py: Python<'_>,
+        table: &str,
+        columns: &[String],
+        values: &[&PyAny],
+    ) -> PyResult<()> {
+        if !self
+            .connection
+            .as_ref()
+            .is_some_and(DatabaseConnection::check_string_lengths)
+        {
+            return Ok(());
+        }
+        let lengths = self.column_lengths(py, table)?;
+        for (column, value) in columns.iter().zip(values) {
+            let (Some(limit), Ok(text)) = (lengths.get(column), value.downcast::<PyString>())
+            else {
+                continue;
+            };
+            let length = text.len()?;
+            if length > *limit {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Value for {}.{} is {} characters long, but the column holds at most {}",
+                    table, column, length, limit
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // Position of the innermost open savepoint called `name`
+    fn open_savepoint(&self, name: &str) -> PyResult<usize> {
+        let savepoints = self.savepoints.lock().unwrap();
+        savepoints
+            .iter()
+            .rposition(|open| open == name)
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "No open savepoint named {:?}",
+                    name
+                ))
+            })
+    }
+
+    fn mariadb_returning(&self, py: Python<'_>) -> PyResult<bool> {
+        if !matches!(self.transaction.driver(), DatabaseType::Mysql) {
+            return Ok(false);
+        }
+        if let Some(supported) = self.mariadb_returning.get() {
+            return Ok(*supported);
         }
+        let rows = self.fetch_all(
+            py,
+            "SELECT VERSION() AS version",
+            Vec::new(),
+            None,
+            false,
+            None,
+        )?;
+        let version: String = match rows.first() {
+            Some(row) => row.as_ref(py).get_item("version")?.extract()?,
+            None => String::new(),
+        };
+        Ok(*self
+            .mariadb_returning
+            .get_or_init(|| supports_insert_returning(&version)))
     }
 
     async fn renew_transaction<T>(
@@ -47,34 +354,50 @@ impl DatabaseTransaction {
     ) where
         T: sqlx::Database,
     {
-        match get_sql_connect() {
-            Some(connection) => {
-                let transaction = connection.begin_transaction().await;
-                let tx = transaction
-                    .unwrap()
-                    .downcast::<sqlx::Transaction<'static, T>>()
-                    .unwrap();
+        if let DatabaseTransactionType::MySql(db, _) = &self.transaction {
+            db.forget_connection_id();
+        }
+        if let Some(connection) = self.connection.as_ref().or(get_sql_connect()) {
+            // A failed BEGIN leaves the slot empty, so later calls raise instead of panicking
+            let transaction = connection.begin_transaction(&self.mode).await;
+            if let Some(tx) = transaction {
+                let tx = tx.downcast::<sqlx::Transaction<'static, T>>().unwrap();
                 guard.replace(*tx);
             }
-            None => {}
         }
     }
 
+    // A failed COMMIT (a deferred constraint, a serialization failure) raises; the transaction
+    // is over either way, and the next one starts on a fresh pooled connection.
     async fn commit_with_type<T>(
         &mut self,
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, T>>>>,
-    ) where
+    ) -> PyResult<()>
+    where
         T: sqlx::Database,
+        for<'e> &'e mut T::Connection: sqlx::Executor<'e, Database = T>,
     {
+        self.savepoints.lock().unwrap().clear();
         let mut guard = transaction.lock().await;
+        let Some(connection) = guard.as_mut() else {
+            self.renew_transaction(guard).await;
+            return Ok(());
+        };
+        let result = match restart_on_same_connection(connection, "COMMIT", &self.mode).await {
+            Ok(true) => return Ok(()),
+            Ok(false) => Ok(()),
+            Err(e) => Err(database_error(e)),
+        };
+        // SQLite keeps the transaction open when COMMIT fails; the other backends have ended it
         let transaction = guard.take().unwrap();
-        transaction.commit().await.ok();
+        transaction.rollback().await.ok();
 
         self.renew_transaction(guard).await;
+        result
     }
 
-    pub async fn commit_internal(&mut self) {
-        match self.transaction.clone() {
+    pub async fn commit_internal(&mut self) -> PyResult<()> {
+        match self.idle()? {
             DatabaseTransactionType::Postgres(_, transaction) => {
                 self.commit_with_type(transaction).await
             }
@@ -92,8 +415,17 @@ impl DatabaseTransaction {
         transaction: Arc<Mutex<Option<sqlx::Transaction<'static, T>>>>,
     ) where
         T: sqlx::Database,
+        for<'e> &'e mut T::Connection: sqlx::Executor<'e, Database = T>,
     {
+        self.savepoints.lock().unwrap().clear();
         let mut guard = transaction.lock().await;
+        let Some(connection) = guard.as_mut() else {
+            self.renew_transaction(guard).await;
+            return;
+        };
+        if let Ok(true) = restart_on_same_connection(connection, "ROLLBACK", &self.mode).await {
+            return;
+        }
         let transaction = guard.take().unwrap();
         transaction.rollback().await.ok();
         self.renew_transaction(guard).await;
@@ -103,6 +435,12 @@ impl DatabaseTransaction {
         if !self.do_commit {
             return;
         }
+        self.rollback_now().await;
+    }
+
+    // Unconditional, unlike `rollback_internal`: a failed or timed-out read leaves a
+    // PostgreSQL transaction aborted even though nothing was written
+    async fn rollback_now(&self) {
         match self.transaction.clone() {
             DatabaseTransactionType::Postgres(_, transaction) => {
                 self.rollback_with_type(transaction).await
@@ -115,95 +453,833 @@ impl DatabaseTransaction {
             }
         }
     }
+
+    // Commit or roll back without opening the next transaction, so the connection goes back to
+    // the pool
+    async fn finish_with_type<T>(
+        &self,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, T>>>>,
+        commit: bool,
+    ) -> Result<(), sqlx::Error>
+    where
+        T: sqlx::Database,
+    {
+        self.savepoints.lock().unwrap().clear();
+        let Some(transaction) = transaction.lock().await.take() else {
+            return Ok(());
+        };
+        match commit {
+            true => transaction.commit().await,
+            false => transaction.rollback().await,
+        }
+    }
+
+    async fn finish(&self, commit: bool) -> Result<(), sqlx::Error> {
+        match self.transaction.clone() {
+            DatabaseTransactionType::Postgres(_, transaction) => {
+                self.finish_with_type(transaction, commit).await
+            }
+            DatabaseTransactionType::MySql(_, transaction) => {
+                self.finish_with_type(transaction, commit).await
+            }
+            DatabaseTransactionType::SQLite(_, transaction) => {
+                self.finish_with_type(transaction, commit).await
+            }
+        }
+    }
+
+    // Begin a new transaction when a `with` block ended the previous one
+    async fn begin_if_finished_with_type<T>(
+        &self,
+        transaction: Arc<Mutex<Option<sqlx::Transaction<'static, T>>>>,
+    ) where
+        T: sqlx::Database,
+    {
+        let guard = transaction.lock().await;
+        if guard.is_none() {
+            self.renew_transaction(guard).await;
+        }
+    }
+
+    async fn enter_block(&self) {
+        match self.transaction.clone() {
+            DatabaseTransactionType::Postgres(_, transaction) => {
+                self.begin_if_finished_with_type(transaction).await
+            }
+            DatabaseTransactionType::MySql(_, transaction) => {
+                self.begin_if_finished_with_type(transaction).await
+            }
+            DatabaseTransactionType::SQLite(_, transaction) => {
+                self.begin_if_finished_with_type(transaction).await
+            }
+        }
+    }
+
+    // A failed commit raises; a failed rollback is only logged, so it does not replace the
+    // exception that is already propagating out of the block
+    async fn exit_block(&self, failed: bool) -> PyResult<()> {
+        match self.finish(!failed).await {
+            Err(e) if failed => {
+                error!("rollback on leaving the transaction block failed: {}", e);
+                Ok(())
+            }
+            result => result.map_err(database_error),
+        }
+    }
+}
+
+// End the current transaction with `statement` and open the next one on the same connection, so
+// session state (temp tables, session settings, prepared statements) survives commit and rollback.
+// Err when ending the transaction fails, Ok(false) when it ended but the next one could not be
+// opened; either way the caller then starts over on a fresh pooled connection.
+async fn restart_on_same_connection<T>(
+    transaction: &mut sqlx::Transaction<'static, T>,
+    statement: &str,
+    mode: &ModeStatements,
+) -> Result<bool, sqlx::Error>
+where
+    T: sqlx::Database,
+    for<'e> &'e mut T::Connection: sqlx::Executor<'e, Database = T>,
+{
+    let connection: &mut T::Connection = transaction;
+    let ending = mode
+        .before_end
+        .iter()
+        .map(String::as_str)
+        .chain([statement]);
+    for statement in ending {
+        connection.execute(sqlx::raw_sql(statement)).await?;
+    }
+    let beginning = mode
+        .before_begin
+        .as_deref()
+        .into_iter()
+        .chain(["BEGIN"])
+        .chain(mode.after_begin.as_deref());
+    for statement in beginning {
+        if connection.execute(sqlx::raw_sql(statement)).await.is_err() {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+// Per-call `timeout_secs` as a Duration; it must be positive and finite.
+fn call_timeout(timeout_secs: Option<f64>) -> PyResult<Option<Duration>> {
+    match timeout_secs {
+        None => Ok(None),
+        Some(secs) if secs.is_finite() && secs > 0.0 => Ok(Some(Duration::from_secs_f64(secs))),
+        Some(secs) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "timeout_secs must be a positive number of seconds, not {}",
+            secs
+        ))),
+    }
+}
+
+// Run `operation` under a PostgreSQL statement_timeout of `timeout`, set with SET LOCAL
+// semantics and restored afterwards. The server cancels the statement itself when it runs
+// over, so its work stops there too; the error surfaces as TimeoutError and, as for any
+// error, leaves the transaction aborted until it is rolled back.
+async fn with_pg_timeout<T>(
+    transaction: &Arc<Mutex<Option<sqlx::Transaction<'static, sqlx::Postgres>>>>,
+    timeout: Option<Duration>,
+    operation: impl std::future::Future<Output = PyResult<T>>,
+) -> PyResult<T> {
+    let Some(timeout) = timeout else {
+        return operation.await;
+    };
+    let previous: String = {
+        let mut guard = transaction.lock().await;
+        let connection = active(&mut guard)?;
+        let (previous, _): (String, String) = sqlx::query_as(
+            "SELECT current_setting('statement_timeout'), \
+             set_config('statement_timeout', $1, true)",
+        )
+        .bind(timeout.as_millis().max(1).to_string())
+        .fetch_one(&mut **connection)
+        .await
+        .map_err(database_error)?;
+        previous
+    };
+    let result = operation.await;
+    if let Some(connection) = transaction.lock().await.as_mut() {
+        // fails harmlessly when the statement aborted the transaction; ROLLBACK resets it
+        let _ = sqlx::query("SELECT set_config('statement_timeout', $1, true)")
+            .bind(previous)
+            .execute(&mut **connection)
+            .await;
+    }
+    result
+}
+
+async fn ping<DB: sqlx::Database>(
+    transaction: &Arc<Mutex<Option<sqlx::Transaction<'static, DB>>>>,
+) -> bool {
+    match transaction.lock().await.as_mut() {
+        Some(connection) => sqlx::Connection::ping(&mut **connection).await.is_ok(),
+        None => false,
+    }
+}
+
+async fn cached_statements<DB: sqlx::Database + sqlx::database::HasStatementCache>(
+    transaction: &Arc<Mutex<Option<sqlx::Transaction<'static, DB>>>>,
+) -> usize {
+    match transaction.lock().await.as_ref() {
+        Some(connection) => sqlx::Connection::cached_statements_size(&**connection),
+        None => 0,
+    }
+}
+
+impl DatabaseTransaction {
+    // Pass each row of `query` to `visit` as it is fetched, without collecting them
+    fn visit_rows(
+        &self,
+        py: Python<'_>,
+        query: &str,
+        params: Vec<&PyAny>,
+        visit: &mut dyn FnMut(&PyAny) -> PyResult<()>,
+    ) -> PyResult<u64> {
+        let started = Instant::now();
+        let result = futures::executor::block_on(async move {
+            match self.idle()? {
+                DatabaseTransactionType::Postgres(db, transaction) => {
+                    let binder = db.binder();
+                    let (sql, arguments) = binder.bind_parameters(query, params)?;
+                    let query = sqlx::query_with(&sql, arguments);
+                    visit_rows(py, transaction, &binder, query, visit).await
+                }
+                DatabaseTransactionType::MySql(db, transaction) => {
+                    let binder = db.binder();
+                    let (sql, arguments) = binder.bind_parameters(query, params)?;
+                    let query = sqlx::query_with(&sql, arguments);
+                    visit_rows(py, transaction, &binder, query, visit).await
+                }
+                DatabaseTransactionType::SQLite(db, transaction) => {
+                    let binder = db.binder();
+                    let (sql, arguments) = binder.bind_parameters(query, params)?;
+                    let query = sqlx::query_with(&sql, arguments);
+                    visit_rows(py, transaction, &binder, query, visit).await
+                }
+            }
+        });
+        self.stats
+            .record(started, 1, 0, *result.as_ref().unwrap_or(&0));
+        result
+    }
 }
 
 #[pymethods]
 impl DatabaseTransaction {
-    fn execute(&self, query: &str, params: Vec<&PyAny>) -> PyResult<u64> {
-        let transaction = self.transaction.clone();
+    // timeout_secs bounds this statement alone and raises TimeoutError when it runs over.
+    #[pyo3(signature = (query, params, prepared=true, timeout_secs=None))]
+    fn execute(
+        &self,
+        query: &str,
+        params: Vec<&PyAny>,
+        prepared: bool,
+        timeout_secs: Option<f64>,
+    ) -> PyResult<u64> {
+        let timeout = call_timeout(timeout_secs)?;
+        let transaction = self.idle()?;
+        let started = Instant::now();
         let result = futures::executor::block_on(async move {
             match transaction {
                 DatabaseTransactionType::Postgres(mut db, transaction) => {
-                    db.execute(transaction, query, params).await
+                    let operation = db.execute(transaction.clone(), query, params, prepared);
+                    with_pg_timeout(&transaction, timeout, operation).await
                 }
-                DatabaseTransactionType::MySql(mut db, transaction) => {
-                    db.execute(transaction, query, params).await
+                DatabaseTransactionType::MySql(db, transaction) => {
+                    db.with_statement_timeout(timeout)
+                        .execute(transaction, query, params, prepared)
+                        .await
                 }
-                DatabaseTransactionType::SQLite(mut db, transaction) => {
-                    db.execute(transaction, query, params).await
+                DatabaseTransactionType::SQLite(db, transaction) => {
+                    db.with_statement_timeout(timeout)
+                        .execute(transaction, query, params, prepared)
+                        .await
                 }
             }
-        })?;
-        Ok(result)
+        });
+        let affected = *result.as_ref().unwrap_or(&0);
+        self.stats.record(started, 1, affected, 0);
+        result
     }
 
+    #[pyo3(signature = (query, params, fetch_size=None, prepared=true, timeout_secs=None))]
     fn fetch_all(
         &self,
         py: Python<'_>,
         query: &str,
         params: Vec<&PyAny>,
+        fetch_size: Option<usize>,
+        prepared: bool,
+        timeout_secs: Option<f64>,
     ) -> Result<Vec<PyObject>, PyErr> {
+        let timeout = call_timeout(timeout_secs)?;
+        let started = Instant::now();
         let result = futures::executor::block_on(async move {
-            match self.transaction.clone() {
+            match self.idle()? {
                 DatabaseTransactionType::Postgres(mut db, transaction) => {
-                    db.fetch_all(py, transaction, query, params).await
+                    let operation =
+                        db.fetch_all(py, transaction.clone(), query, params, fetch_size, prepared);
+                    with_pg_timeout(&transaction, timeout, operation).await
+                }
+                DatabaseTransactionType::MySql(db, transaction) => {
+                    db.with_statement_timeout(timeout)
+                        .fetch_all(py, transaction, query, params, fetch_size, prepared)
+                        .await
+                }
+                DatabaseTransactionType::SQLite(db, transaction) => {
+                    db.with_statement_timeout(timeout)
+                        .fetch_all(py, transaction, query, params, fetch_size, prepared)
+                        .await
+                }
+            }
+        });
+        let returned = result.as_ref().map_or(0, |rows| rows.len() as u64);
+        self.stats.record(started, 1, 0, returned);
+        result
+    }
+
+    // execute with `:name` placeholders bound from the `params` dict; a name used twice binds
+    // one value
+    #[pyo3(signature = (query, params, prepared=true, timeout_secs=None))]
+    fn execute_named(
+        &self,
+        query: &str,
+        params: &PyDict,
+        prepared: bool,
+        timeout_secs: Option<f64>,
+    ) -> PyResult<u64> {
+        let driver = self.transaction.driver();
+        let (query, params) = query_builder::bind_named_parameters(&driver, query, params)?;
+        self.execute(&query, params, prepared, timeout_secs)
+    }
+
+    // fetch_all with `:name` placeholders bound from the `params` dict
+    #[pyo3(signature = (query, params, fetch_size=None, prepared=true, timeout_secs=None))]
+    fn fetch_all_named(
+        &self,
+        py: Python<'_>,
+        query: &str,
+        params: &PyDict,
+        fetch_size: Option<usize>,
+        prepared: bool,
+        timeout_secs: Option<f64>,
+    ) -> PyResult<Vec<PyObject>> {
+        let driver = self.transaction.driver();
+        let (query, params) = query_builder::bind_named_parameters(&driver, query, params)?;
+        self.fetch_all(py, &query, params, fetch_size, prepared, timeout_secs)
+    }
+
+    // (name, type_name, nullable) for each column `query` returns, without running it, so it
+    // works for queries that return no rows. nullable is None when the driver cannot tell.
+    fn describe(&self, query: &str) -> PyResult<Vec<ColumnDescription>> {
+        futures::executor::block_on(async move {
+            match self.idle()? {
+                DatabaseTransactionType::Postgres(mut db, transaction) => {
+                    db.describe(transaction, query).await
                 }
                 DatabaseTransactionType::MySql(mut db, transaction) => {
-                    db.fetch_all(py, transaction, query, params).await
+                    db.describe(transaction, query).await
                 }
                 DatabaseTransactionType::SQLite(mut db, transaction) => {
-                    db.fetch_all(py, transaction, query, params).await
+                    db.describe(transaction, query).await
                 }
             }
-        })?;
+        })
+    }
 
-        Ok(result)
+    // Rows as tuples in column order, so columns sharing a name (`a.id`, `b.id`) are all kept
+    #[pyo3(signature = (query, params, timeout_secs=None))]
+    fn fetch_all_tuples(
+        &self,
+        py: Python<'_>,
+        query: &str,
+        params: Vec<&PyAny>,
+        timeout_secs: Option<f64>,
+    ) -> PyResult<Vec<PyObject>> {
+        let timeout = call_timeout(timeout_secs)?;
+        let started = Instant::now();
+        let result = futures::executor::block_on(async move {
+            match self.idle()? {
+                DatabaseTransactionType::Postgres(mut db, transaction) => {
+                    let operation = db.fetch_all_tuples(py, transaction.clone(), query, params);
+                    with_pg_timeout(&transaction, timeout, operation).await
+                }
+                DatabaseTransactionType::MySql(db, transaction) => {
+                    db.with_statement_timeout(timeout)
+                        .fetch_all_tuples(py, transaction, query, params)
+                        .await
+                }
+                DatabaseTransactionType::SQLite(db, transaction) => {
+                    db.with_statement_timeout(timeout)
+                        .fetch_all_tuples(py, transaction, query, params)
+                        .await
+                }
+            }
+        });
+        let returned = result.as_ref().map_or(0, |rows| rows.len() as u64);
+        self.stats.record(started, 1, 0, returned);
+        result
     }
 
-    fn stream_data(
+    #[pyo3(signature = (query, params, timeout_secs=None))]
+    fn fetch_one(
         &self,
         py: Python<'_>,
         query: &str,
         params: Vec<&PyAny>,
-        chunk_size: usize,
-    ) -> PyResult<Vec<Vec<PyObject>>> {
+        timeout_secs: Option<f64>,
+    ) -> PyResult<PyObject> {
+        let timeout = call_timeout(timeout_secs)?;
+        let started = Instant::now();
         let result = futures::executor::block_on(async move {
-            match self.transaction.clone() {
+            match self.idle()? {
                 DatabaseTransactionType::Postgres(mut db, transaction) => {
-                    db.stream_data(py, transaction, query, params, chunk_size)
+                    let operation = db.fetch_one(py, transaction.clone(), query, params);
+                    with_pg_timeout(&transaction, timeout, operation).await
+                }
+                DatabaseTransactionType::MySql(db, transaction) => {
+                    db.with_statement_timeout(timeout)
+                        .fetch_one(py, transaction, query, params)
                         .await
                 }
-                DatabaseTransactionType::MySql(mut db, transaction) => {
-                    db.stream_data(py, transaction, query, params, chunk_size)
+                DatabaseTransactionType::SQLite(db, transaction) => {
+                    db.with_statement_timeout(timeout)
+                        .fetch_one(py, transaction, query, params)
                         .await
                 }
-                DatabaseTransactionType::SQLite(mut db, transaction) => {
-                    db.stream_data(py, transaction, query, params, chunk_size)
+            }
+        });
+        self.stats.record(started, 1, 0, result.is_ok() as u64);
+        result
+    }
+
+    // Awaitables for asyncio code: `await tx.execute_async(...)` runs the statement without
+    // blocking the event loop, which must be running in the calling thread. Parameters are
+    // bound at the call; statements of one transaction still run one at a time.
+    fn execute_async<'py>(
+        &self,
+        py: Python<'py>,
+        query: &str,
+        params: Vec<&PyAny>,
+    ) -> PyResult<&'py PyAny> {
+        let stats = self.stats.clone();
+        let started = Instant::now();
+        match self.idle()? {
+            DatabaseTransactionType::Postgres(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                pyo3_asyncio::async_std::future_into_py(py, async move {
+                    let result = awaitable::execute(transaction, sql, arguments)
+                        .await
+                        .map(|done| done.rows_affected());
+                    stats.record(started, 1, *result.as_ref().unwrap_or(&0), 0);
+                    result
+                })
+            }
+            DatabaseTransactionType::MySql(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                pyo3_asyncio::async_std::future_into_py(py, async move {
+                    let operation = awaitable::execute(transaction.clone(), sql, arguments);
+                    let result = db
+                        .run_bounded(&transaction, operation)
+                        .await
+                        .map(|done| done.rows_affected());
+                    stats.record(started, 1, *result.as_ref().unwrap_or(&0), 0);
+                    result
+                })
+            }
+            DatabaseTransactionType::SQLite(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                pyo3_asyncio::async_std::future_into_py(py, async move {
+                    let result = awaitable::execute(transaction, sql, arguments)
+                        .await
+                        .map(|done| done.rows_affected());
+                    stats.record(started, 1, *result.as_ref().unwrap_or(&0), 0);
+                    result
+                })
+            }
+        }
+    }
+
+    fn fetch_all_async<'py>(
+        &self,
+        py: Python<'py>,
+        query: &str,
+        params: Vec<&PyAny>,
+    ) -> PyResult<&'py PyAny> {
+        let stats = self.stats.clone();
+        let started = Instant::now();
+        match self.idle()? {
+            DatabaseTransactionType::Postgres(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                pyo3_asyncio::async_std::future_into_py(py, async move {
+                    let result = awaitable::fetch_all(transaction, binder, sql, arguments).await;
+                    let returned = result.as_ref().map_or(0, |rows| rows.len() as u64);
+                    stats.record(started, 1, 0, returned);
+                    result
+                })
+            }
+            DatabaseTransactionType::MySql(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                pyo3_asyncio::async_std::future_into_py(py, async move {
+                    let operation =
+                        awaitable::fetch_all(transaction.clone(), binder, sql, arguments);
+                    let result = db.run_bounded(&transaction, operation).await;
+                    let returned = result.as_ref().map_or(0, |rows| rows.len() as u64);
+                    stats.record(started, 1, 0, returned);
+                    result
+                })
+            }
+            DatabaseTransactionType::SQLite(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                pyo3_asyncio::async_std::future_into_py(py, async move {
+                    let result = awaitable::fetch_all(transaction, binder, sql, arguments).await;
+                    let returned = result.as_ref().map_or(0, |rows| rows.len() as u64);
+                    stats.record(started, 1, 0, returned);
+                    result
+                })
+            }
+        }
+    }
+
+    fn fetch_one_async<'py>(
+        &self,
+        py: Python<'py>,
+        query: &str,
+        params: Vec<&PyAny>,
+    ) -> PyResult<&'py PyAny> {
+        let stats = self.stats.clone();
+        let started = Instant::now();
+        match self.idle()? {
+            DatabaseTransactionType::Postgres(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                pyo3_asyncio::async_std::future_into_py(py, async move {
+                    let result = awaitable::fetch_one(transaction, binder, sql, arguments).await;
+                    stats.record(started, 1, 0, result.is_ok() as u64);
+                    result
+                })
+            }
+            DatabaseTransactionType::MySql(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                pyo3_asyncio::async_std::future_into_py(py, async move {
+                    let operation =
+                        awaitable::fetch_one(transaction.clone(), binder, sql, arguments);
+                    let result = db.run_bounded(&transaction, operation).await;
+                    stats.record(started, 1, 0, result.is_ok() as u64);
+                    result
+                })
+            }
+            DatabaseTransactionType::SQLite(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                pyo3_asyncio::async_std::future_into_py(py, async move {
+                    let result = awaitable::fetch_one(transaction, binder, sql, arguments).await;
+                    stats.record(started, 1, 0, result.is_ok() as u64);
+                    result
+                })
+            }
+        }
+    }
+
+    #[pyo3(signature = (query, params, timeout_secs=None))]
+    fn fetch_optional(
+        &self,
+        py: Python<'_>,
+        query: &str,
+        params: Vec<&PyAny>,
+        timeout_secs: Option<f64>,
+    ) -> PyResult<Option<PyObject>> {
+        let timeout = call_timeout(timeout_secs)?;
+        let started = Instant::now();
+        let result = futures::executor::block_on(async move {
+            match self.idle()? {
+                DatabaseTransactionType::Postgres(mut db, transaction) => {
+                    let operation = db.fetch_optional(py, transaction.clone(), query, params);
+                    with_pg_timeout(&transaction, timeout, operation).await
+                }
+                DatabaseTransactionType::MySql(db, transaction) => {
+                    db.with_statement_timeout(timeout)
+                        .fetch_optional(py, transaction, query, params)
+                        .await
+                }
+                DatabaseTransactionType::SQLite(db, transaction) => {
+                    db.with_statement_timeout(timeout)
+                        .fetch_optional(py, transaction, query, params)
+                        .await
+                }
+            }
+        });
+        let returned = matches!(result, Ok(Some(_))) as u64;
+        self.stats.record(started, 1, 0, returned);
+        result
+    }
+
+    // Rows produced by an INSERT/UPDATE/DELETE ... RETURNING, as dicts. MySQL has no RETURNING
+    // (MariaDB 10.5+ has it for INSERT and DELETE).
+    #[pyo3(signature = (query, params, timeout_secs=None))]
+    fn execute_returning(
+        &self,
+        py: Python<'_>,
+        query: &str,
+        params: Vec<&PyAny>,
+        timeout_secs: Option<f64>,
+    ) -> PyResult<Vec<PyObject>> {
+        let timeout = call_timeout(timeout_secs)?;
+        if matches!(self.transaction.driver(), DatabaseType::Mysql)
+            && !self.mariadb_returning(py)?
+        {
+            return Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                "MySQL does not support RETURNING (MariaDB 10.5+ does for INSERT and DELETE)",
+            ));
+        }
+        let started = Instant::now();
+        let result = futures::executor::block_on(async move {
+            match self.idle()? {
+                DatabaseTransactionType::Postgres(mut db, transaction) => {
+                    let operation = db.execute_returning(py, transaction.clone(), query, params);
+                    with_pg_timeout(&transaction, timeout, operation).await
+                }
+                DatabaseTransactionType::MySql(db, transaction) => {
+                    db.with_statement_timeout(timeout)
+                        .execute_returning(py, transaction, query, params)
+                        .await
+                }
+                DatabaseTransactionType::SQLite(db, transaction) => {
+                    db.with_statement_timeout(timeout)
+                        .execute_returning(py, transaction, query, params)
                         .await
                 }
             }
+        });
+        let returned = result.as_ref().map_or(0, |rows| rows.len() as u64);
+        self.stats.record(started, 1, returned, returned);
+        result
+    }
+
+    #[pyo3(signature = (query, params, prepared=true))]
+    fn fetch_all_msgpack<'py>(
+        &self,
+        py: Python<'py>,
+        query: &str,
+        params: Vec<&PyAny>,
+        prepared: bool,
+    ) -> PyResult<&'py PyBytes> {
+        let rows = self.fetch_all(py, query, params, None, prepared, None)?;
+        msgpack::rows_to_msgpack(py, &rows)
+    }
+
+    // One page of `query` plus the total row count, both read in this transaction. A page past
+    // the end is empty but still reports the total.
+    #[pyo3(signature = (query, params, limit, offset=0, prepared=true))]
+    fn fetch_page(
+        &self,
+        py: Python<'_>,
+        query: &str,
+        params: Vec<&PyAny>,
+        limit: u64,
+        offset: u64,
+        prepared: bool,
+    ) -> PyResult<(Vec<PyObject>, i64)> {
+        let (count_query, page_query) = query_builder::build_page(query, limit, offset);
+        let counted = self.fetch_all(py, &count_query, params.clone(), None, prepared, None)?;
+        let total = match counted.first() {
+            Some(row) => row.as_ref(py).get_item("total")?.extract::<i64>()?,
+            None => 0,
+        };
+        if limit == 0 || offset >= total as u64 {
+            return Ok((Vec::new(), total));
+        }
+        let rows = self.fetch_all(py, &page_query, params, None, prepared, None)?;
+        Ok((rows, total))
+    }
+
+    // File-like reader yielding the rows as newline-delimited JSON, encoded as it is read
+    fn stream_ndjson(&self, query: &str, params: Vec<&PyAny>) -> PyResult<NdjsonReader> {
+        Ok(match self.idle()? {
+            DatabaseTransactionType::Postgres(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                NdjsonReader::new(transaction, &self.busy, binder, sql, arguments)
+            }
+            DatabaseTransactionType::MySql(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                NdjsonReader::new(transaction, &self.busy, binder, sql, arguments)
+            }
+            DatabaseTransactionType::SQLite(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                NdjsonReader::new(transaction, &self.busy, binder, sql, arguments)
+            }
+        })
+    }
+
+    // Streaming reduce: `reducer(accumulator, row)` is called for each row as it is fetched,
+    // starting from `initial`, and the last accumulator is returned.
+    fn fold(
+        &self,
+        py: Python<'_>,
+        query: &str,
+        params: Vec<&PyAny>,
+        initial: PyObject,
+        reducer: &PyAny,
+    ) -> PyResult<PyObject> {
+        let mut accumulator = initial;
+        self.visit_rows(py, query, params, &mut |row| {
+            accumulator = reducer.call1((accumulator.as_ref(py), row))?.into();
+            Ok(())
         })?;
+        Ok(accumulator)
+    }
 
-        Ok(result)
+    // Call `callback(row)` for each row as it is fetched, holding one decoded row at a time;
+    // returns how many rows were passed. An exception from the callback stops the query and
+    // propagates, leaving the transaction open and usable.
+    fn for_each(
+        &self,
+        py: Python<'_>,
+        query: &str,
+        params: Vec<&PyAny>,
+        callback: &PyAny,
+    ) -> PyResult<u64> {
+        self.visit_rows(py, query, params, &mut |row| {
+            callback.call1((row,))?;
+            Ok(())
+        })
+    }
+
+    // Hex SHA-256 over every row of `query` in result order (see `checksum::encode_row`); give
+    // the query an ORDER BY to compare result sets across databases.
+    fn checksum(&self, py: Python<'_>, query: &str, params: Vec<&PyAny>) -> PyResult<String> {
+        let mut hasher = Sha256::new();
+        self.visit_rows(py, query, params, &mut |row| {
+            hasher.update(checksum::encode_row(row)?);
+            Ok(())
+        })?;
+        Ok(checksum::to_hex(&hasher.finalize()))
+    }
+
+    // Hex SHA-256 of each row of `query`, in result order
+    fn row_hashes(
+        &self,
+        py: Python<'_>,
+        query: &str,
+        params: Vec<&PyAny>,
+    ) -> PyResult<Vec<String>> {
+        let mut hashes = Vec::new();
+        self.visit_rows(py, query, params, &mut |row| {
+            hashes.push(checksum::to_hex(&Sha256::digest(checksum::encode_row(
+                row,
+            )?)));
+            Ok(())
+        })?;
+        Ok(hashes)
+    }
+
+    // Iterator over the rows in lists of up to `chunk_size`, fetched as it advances
+    fn stream_data(
+        &self,
+        query: &str,
+        params: Vec<&PyAny>,
+        chunk_size: usize,
+    ) -> PyResult<RowStream> {
+        if chunk_size == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "chunk_size must be at least 1",
+            ));
+        }
+        let rows = match self.idle()? {
+            DatabaseTransactionType::Postgres(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                RowPump::new(transaction, &self.busy, binder, sql, arguments, |_, row| {
+                    Ok(row)
+                })
+            }
+            DatabaseTransactionType::MySql(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                RowPump::new(transaction, &self.busy, binder, sql, arguments, |_, row| {
+                    Ok(row)
+                })
+            }
+            DatabaseTransactionType::SQLite(db, transaction) => {
+                let binder = db.binder();
+                let (sql, arguments) = binder.bind_parameters(query, params)?;
+                let sql = sql.into_owned();
+                RowPump::new(transaction, &self.busy, binder, sql, arguments, |_, row| {
+                    Ok(row)
+                })
+            }
+        };
+        Ok(RowStream::new(rows, chunk_size))
     }
 
+    // timeout_secs bounds each statement the batch runs, not the batch as a whole. Returns the
+    // total affected row count; with `returning`, a dict that adds the count of each statement
+    // and the rows they returned (their RETURNING output).
+    #[pyo3(signature = (query, params, batch_size, timeout_secs=None, returning=false))]
     fn bulk_change(
         &mut self,
+        py: Python<'_>,
         query: &str,
-        params: Vec<Vec<&PyAny>>,
+        params: &PyAny,
         batch_size: usize,
-    ) -> PyResult<u64> {
-        let transaction = self.transaction.clone();
+        timeout_secs: Option<f64>,
+        returning: bool,
+    ) -> PyResult<PyObject> {
+        let timeout = call_timeout(timeout_secs)?;
+        let params = numpy::param_rows(params)?;
+        let transaction = self.idle()?;
+        let statements = params.len() as u64;
+        let stats = self.stats.clone();
+        let started = Instant::now();
         let result = futures::executor::block_on(async move {
             let row_effect = match transaction {
                 DatabaseTransactionType::Postgres(mut db, transaction) => {
-                    db.bulk_change(transaction, query, params, batch_size).await
+                    let operation = db.bulk_change(
+                        py,
+                        transaction.clone(),
+                        query,
+                        params,
+                        batch_size,
+                        returning,
+                    );
+                    with_pg_timeout(&transaction, timeout, operation).await
                 }
-                DatabaseTransactionType::MySql(mut db, transaction) => {
-                    db.bulk_change(transaction, query, params, batch_size).await
+                DatabaseTransactionType::MySql(db, transaction) => {
+                    db.with_statement_timeout(timeout)
+                        .bulk_change(py, transaction, query, params, batch_size, returning)
+                        .await
                 }
-                DatabaseTransactionType::SQLite(mut db, transaction) => {
-                    db.bulk_change(transaction, query, params, batch_size).await
+                DatabaseTransactionType::SQLite(db, transaction) => {
+                    db.with_statement_timeout(timeout)
+                        .bulk_change(py, transaction, query, params, batch_size, returning)
+                        .await
                 }
             };
             Ok(match row_effect {
@@ -214,27 +1290,437 @@ impl DatabaseTransaction {
                 Err(e) => {
                     self.rollback_internal().await;
                     error!("Error in bulk_change: {:?}", e);
-                    return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                        e.to_string(),
-                    ));
+                    return Err(e);
                 }
             })
-        })?;
+        });
+        let affected = result.as_ref().map_or(0, |changes| changes.rows_affected());
+        let returned = result
+            .as_ref()
+            .map_or(0, |changes| changes.rows.len() as u64);
+        stats.record(started, statements, affected, returned);
+        let changes = result?;
+        if !returning {
+            return Ok(affected.into_py(py));
+        }
+        let details = PyDict::new(py);
+        details.set_item("rows_affected", affected)?;
+        details.set_item("statement_counts", changes.statement_counts)?;
+        details.set_item("rows", changes.rows)?;
+        Ok(details.into())
+    }
+
+    // Insert one row built from a column -> value dict. Returns the affected row count, or the
+    // inserted row (None if nothing was inserted) when `returning` is set.
+    #[pyo3(signature = (table, data, returning=false))]
+    fn insert(
+        &self,
+        py: Python<'_>,
+        table: &str,
+        data: &PyDict,
+        returning: bool,
+    ) -> PyResult<PyObject> {
+        let columns = data
+            .keys()
+            .iter()
+            .map(|key| key.extract::<String>())
+            .collect::<PyResult<Vec<_>>>()?;
+        let params: Vec<&PyAny> = data.values().iter().collect();
+        self.check_string_lengths(py, table, &columns, &params)?;
+        let mariadb_returning = returning && self.mariadb_returning(py)?;
+        let query = query_builder::build_insert(
+            &self.transaction.driver(),
+            table,
+            &columns,
+            returning,
+            mariadb_returning,
+        )?;
 
-        Ok(result)
+        if returning {
+            let rows = self.fetch_all(py, &query, params, None, true, None)?;
+            Ok(rows.into_iter().next().unwrap_or_else(|| py.None()))
+        } else {
+            Ok(self.execute(&query, params, true, None)?.into_py(py))
+        }
     }
 
-    fn commit(&mut self) -> PyResult<()> {
-        let _ = futures::executor::block_on(async move {
-            self.commit_internal().await;
-        });
+    // Update the rows matching every `where_` column (None matches NULL). Returns the affected
+    // row count, or the updated rows when `returning` is set.
+    #[pyo3(signature = (table, values, where_, returning=false))]
+    fn update(
+        &self,
+        py: Python<'_>,
+        table: &str,
+        values: &PyDict,
+        where_: &PyDict,
+        returning: bool,
+    ) -> PyResult<PyObject> {
+        let set_columns = values
+            .keys()
+            .iter()
+            .map(|key| key.extract::<String>())
+            .collect::<PyResult<Vec<_>>>()?;
+        let mut params: Vec<&PyAny> = values.values().iter().collect();
+        self.check_string_lengths(py, table, &set_columns, &params)?;
+        let mut where_columns = Vec::with_capacity(where_.len());
+        for (key, value) in where_.iter() {
+            where_columns.push((key.extract::<String>()?, value.is_none()));
+            if !value.is_none() {
+                params.push(value);
+            }
+        }
+        let query = query_builder::build_update(
+            &self.transaction.driver(),
+            table,
+            &set_columns,
+            &where_columns,
+            returning,
+        )?;
+
+        if returning {
+            Ok(self
+                .fetch_all(py, &query, params, None, true, None)?
+                .into_py(py))
+        } else {
+            Ok(self.execute(&query, params, true, None)?.into_py(py))
+        }
+    }
+
+    fn bulk_upsert(
+        &mut self,
+        py: Python<'_>,
+        table: &str,
+        columns: Vec<String>,
+        conflict_columns: Vec<String>,
+        update_columns: Vec<String>,
+        rows: Vec<Vec<&PyAny>>,
+    ) -> PyResult<u64> {
+        let driver = self.transaction.driver();
+        // Rejects empty columns or conflict_columns up front; the batch size divides by the
+        // column count
+        query_builder::build_upsert(
+            &driver,
+            table,
+            &columns,
+            &conflict_columns,
+            &update_columns,
+            1,
+        )?;
+        if let Some(row) = rows.iter().find(|row| row.len() != columns.len()) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "bulk_upsert row has {} values but {} columns were given",
+                row.len(),
+                columns.len()
+            )));
+        }
+        if rows.is_empty() {
+            return Ok(0);
+        }
+        for row in &rows {
+            self.check_string_lengths(py, table, &columns, row)?;
+        }
+
+        let rows_per_statement =
+            (query_builder::max_bind_parameters(&driver) / columns.len()).max(1);
+        let transaction = self.idle()?;
+        let stats = self.stats.clone();
+        futures::executor::block_on(async move {
+            let mut total_affected: u64 = 0;
+            for chunk in rows.chunks(rows_per_statement) {
+                let started = Instant::now();
+                let query = query_builder::build_upsert(
+                    &driver,
+                    table,
+                    &columns,
+                    &conflict_columns,
+                    &update_columns,
+                    chunk.len(),
+                )?;
+                let params: Vec<&PyAny> = chunk.iter().flatten().copied().collect();
+                let row_effect = match transaction.clone() {
+                    DatabaseTransactionType::Postgres(mut db, transaction) => {
+                        db.execute(transaction, &query, params, true).await
+                    }
+                    DatabaseTransactionType::MySql(mut db, transaction) => {
+                        db.execute(transaction, &query, params, true).await
+                    }
+                    DatabaseTransactionType::SQLite(mut db, transaction) => {
+                        db.execute(transaction, &query, params, true).await
+                    }
+                };
+                stats.record(started, 1, *row_effect.as_ref().unwrap_or(&0), 0);
+                match row_effect {
+                    Ok(row) => {
+                        self.do_commit = true;
+                        total_affected += row;
+                    }
+                    Err(e) => {
+                        self.rollback_internal().await;
+                        error!("Error in bulk_upsert: {:?}", e);
+                        return Err(e);
+                    }
+                }
+            }
+            Ok(total_affected)
+        })
+    }
+
+    #[pyo3(signature = (table, file_obj, columns=None, options=None, chunk_size=65536))]
+    fn copy_from_csv(
+        &mut self,
+        table: &str,
+        file_obj: &PyAny,
+        columns: Option<Vec<String>>,
+        options: Option<&PyDict>,
+        chunk_size: usize,
+    ) -> PyResult<u64> {
+        let statement = query_builder::build_copy_from_csv(table, columns.as_deref(), options)?;
+        let transaction = self.idle()?;
+        let started = Instant::now();
+        futures::executor::block_on(async move {
+            let row_effect = match transaction {
+                DatabaseTransactionType::Postgres(mut db, transaction) => {
+                    db.copy_from_reader(transaction, &statement, file_obj, chunk_size)
+                        .await
+                }
+                _ => {
+                    return Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                        "copy_from_csv is only supported on PostgreSQL",
+                    ))
+                }
+            };
+            let affected = *row_effect.as_ref().unwrap_or(&0);
+            self.stats.record(started, 1, affected, 0);
+            match row_effect {
+                Ok(row) => {
+                    self.do_commit = true;
+                    Ok(row)
+                }
+                Err(e) => {
+                    self.rollback_internal().await;
+                    error!("Error in copy_from_csv: {:?}", e);
+                    Err(e)
+                }
+            }
+        })
+    }
+
+    // (name, collation) for each result column of `query`; PostgreSQL only
+    fn column_collations(&self, query: &str) -> PyResult<Vec<(String, Option<String>)>> {
+        futures::executor::block_on(async move {
+            match self.idle()? {
+                DatabaseTransactionType::Postgres(mut db, transaction) => {
+                    db.column_collations(transaction, query).await
+                }
+                _ => Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                    "column_collations is only supported on PostgreSQL",
+                )),
+            }
+        })
+    }
+
+    // Server-side id of the connection this transaction runs on (PostgreSQL backend pid, MySQL
+    // connection id; None on SQLite). It stays the same across commit and rollback, so temp
+    // tables and session settings persist for the life of the object.
+    fn connection_id(&self, py: Python<'_>) -> PyResult<Option<i64>> {
+        let query = match self.transaction.driver() {
+            DatabaseType::Postgres => "SELECT pg_backend_pid()::int8 AS id",
+            DatabaseType::Mysql => "SELECT CAST(CONNECTION_ID() AS SIGNED) AS id",
+            DatabaseType::Sqlite => return Ok(None),
+        };
+        let rows = self.fetch_all(py, query, Vec::new(), None, false, None)?;
+        match rows.first() {
+            Some(row) => row.as_ref(py).get_item("id")?.extract(),
+            None => Ok(None),
+        }
+    }
+
+    // Auto-increment id of the last row this transaction's connection inserted (MySQL's
+    // LAST_INSERT_ID(), the first id of a multi-row insert; SQLite's last_insert_rowid()), or
+    // None before any insert. PostgreSQL has no equivalent: use INSERT ... RETURNING.
+    fn last_insert_id(&self, py: Python<'_>) -> PyResult<Option<i64>> {
+        let query = match self.transaction.driver() {
+            DatabaseType::Postgres => {
+                return Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                    "last_insert_id is not available on PostgreSQL; use INSERT ... RETURNING",
+                ))
+            }
+            DatabaseType::Mysql => "SELECT CAST(LAST_INSERT_ID() AS SIGNED) AS id",
+            DatabaseType::Sqlite => "SELECT last_insert_rowid() AS id",
+        };
+        let rows = self.fetch_all(py, query, Vec::new(), None, false, None)?;
+        match rows.first() {
+            Some(row) => Ok(row
+                .as_ref(py)
+                .get_item("id")?
+                .extract::<Option<i64>>()?
+                .filter(|id| *id != 0)),
+            None => Ok(None),
+        }
+    }
+
+    // Whether this transaction's connection is still alive; False once the server has closed it
+    // or the transaction could not be renewed after commit or rollback
+    fn ping(&self) -> PyResult<bool> {
+        let transaction = self.idle()?;
+        Ok(futures::executor::block_on(async {
+            match transaction {
+                DatabaseTransactionType::Postgres(_, transaction) => ping(&transaction).await,
+                DatabaseTransactionType::MySql(_, transaction) => ping(&transaction).await,
+                DatabaseTransactionType::SQLite(_, transaction) => ping(&transaction).await,
+            }
+        }))
+    }
+
+    // Prepared statements held by this transaction's connection. sqlx keeps them per connection,
+    // keyed by query text and evicting the least recently used past statement_cache_capacity,
+    // so a repeated query is prepared once per connection and dropped with it.
+    fn cached_statements(&self) -> PyResult<usize> {
+        let transaction = self.idle()?;
+        Ok(futures::executor::block_on(async {
+            match transaction {
+                DatabaseTransactionType::Postgres(_, transaction) => {
+                    cached_statements(&transaction).await
+                }
+                DatabaseTransactionType::MySql(_, transaction) => {
+                    cached_statements(&transaction).await
+                }
+                DatabaseTransactionType::SQLite(_, transaction) => {
+                    cached_statements(&transaction).await
+                }
+            }
+        }))
+    }
+
+    // Counters accumulated over the life of this object (across commits): statements sent,
+    // rows affected by writes, rows returned by reads and time spent waiting on the database.
+    fn stats(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let stats = PyDict::new(py);
+        stats.set_item("statements", self.stats.statements.load(Ordering::Relaxed))?;
+        stats.set_item(
+            "rows_affected",
+            self.stats.rows_affected.load(Ordering::Relaxed),
+        )?;
+        stats.set_item(
+            "rows_returned",
+            self.stats.rows_returned.load(Ordering::Relaxed),
+        )?;
+        stats.set_item(
+            "elapsed",
+            self.stats.elapsed_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0,
+        )?;
+        Ok(stats.into())
+    }
+
+    #[pyo3(signature = (deferred, constraints=None))]
+    fn set_constraints(&self, deferred: bool, constraints: Option<Vec<String>>) -> PyResult<()> {
+        let query = query_builder::build_set_constraints(
+            &self.transaction.driver(),
+            deferred,
+            constraints.as_deref(),
+        )?;
+        self.execute(&query, Vec::new(), false, None)?;
         Ok(())
     }
 
+    fn commit(&mut self) -> PyResult<()> {
+        futures::executor::block_on(self.commit_internal())
+    }
+
     fn rollback(&mut self) -> PyResult<()> {
-        let _ = futures::executor::block_on(async move {
-            self.rollback_internal().await;
+        self.busy.check()?;
+        futures::executor::block_on(async move {
+            self.rollback_now().await;
         });
         Ok(())
     }
+
+    // Mark a point `rollback_to` can return to without discarding the whole transaction.
+    // Savepoints nest; reusing a name shadows the earlier one until it is released.
+    fn savepoint(&self, name: &str) -> PyResult<()> {
+        let quoted = query_builder::quote_savepoint(&self.transaction.driver(), name)?;
+        self.execute(&format!("SAVEPOINT {}", quoted), Vec::new(), false, None)?;
+        self.savepoints.lock().unwrap().push(name.to_string());
+        Ok(())
+    }
+
+    // Undo everything since savepoint `name`, which stays open; savepoints set after it are
+    // discarded
+    fn rollback_to(&self, name: &str) -> PyResult<()> {
+        let index = self.open_savepoint(name)?;
+        let quoted = query_builder::quote_savepoint(&self.transaction.driver(), name)?;
+        self.execute(
+            &format!("ROLLBACK TO SAVEPOINT {}", quoted),
+            Vec::new(),
+            false,
+            None,
+        )?;
+        self.savepoints.lock().unwrap().truncate(index + 1);
+        Ok(())
+    }
+
+    // Keep the work done since savepoint `name` and forget it, along with the savepoints set
+    // after it
+    fn release_savepoint(&self, name: &str) -> PyResult<()> {
+        let index = self.open_savepoint(name)?;
+        let quoted = query_builder::quote_savepoint(&self.transaction.driver(), name)?;
+        self.execute(
+            &format!("RELEASE SAVEPOINT {}", quoted),
+            Vec::new(),
+            false,
+            None,
+        )?;
+        self.savepoints.lock().unwrap().truncate(index);
+        Ok(())
+    }
+
+    // How many savepoints are open
+    #[getter]
+    fn savepoint_depth(&self) -> usize {
+        self.savepoints.lock().unwrap().len()
+    }
+
+    // `with tx:` commits when the block completes and rolls back when it raises, without
+    // suppressing the exception. Either way the transaction ends and its connection returns to
+    // the pool; entering the block again begins a new one.
+    fn __enter__<'py>(slf: PyRef<'py, Self>, py: Python<'py>) -> PyResult<PyRef<'py, Self>> {
+        slf.busy.check()?;
+        // Beginning may wait for a pooled connection, so without the GIL (see transaction())
+        let transaction = slf.clone();
+        py.allow_threads(|| futures::executor::block_on(transaction.enter_block()));
+        Ok(slf)
+    }
+
+    fn __exit__(&self, exc_type: &PyAny, _exc_value: &PyAny, _traceback: &PyAny) -> PyResult<bool> {
+        self.busy.check()?;
+        futures::executor::block_on(self.exit_block(!exc_type.is_none()))?;
+        Ok(false)
+    }
+
+    // `async with tx:`, the same without blocking the event loop
+    fn __aenter__<'py>(slf: PyRef<'py, Self>, py: Python<'py>) -> PyResult<&'py PyAny> {
+        slf.busy.check()?;
+        let transaction = slf.clone();
+        let this: Py<Self> = slf.into();
+        pyo3_asyncio::async_std::future_into_py(py, async move {
+            transaction.enter_block().await;
+            Ok(this)
+        })
+    }
+
+    fn __aexit__<'py>(
+        &self,
+        py: Python<'py>,
+        exc_type: &PyAny,
+        _exc_value: &PyAny,
+        _traceback: &PyAny,
+    ) -> PyResult<&'py PyAny> {
+        self.busy.check()?;
+        let transaction = self.clone();
+        let failed = !exc_type.is_none();
+        pyo3_asyncio::async_std::future_into_py(py, async move {
+            transaction.exit_block(failed).await?;
+            Ok(false)
+        })
+    }
 }
@@ -0,0 +1,48 @@
+"""Connections to the databases the integration tests run against.
+
+SQLite always runs, in memory. PostgreSQL and MySQL run when SQLRUSTLER_POSTGRES_URL or
+SQLRUSTLER_MYSQL_URL name a database the tests may create temporary tables in, and are
+skipped otherwise. Build the extension first (`maturin develop`), then run
+`python -m unittest discover tests`.
+"""
+
+import os
+import unittest
+from contextlib import contextmanager
+
+from sqlrustler.sqlrustler import DatabaseConfig, DatabaseConnection, DatabaseType
+
+URLS = {
+    "postgres": os.environ.get("SQLRUSTLER_POSTGRES_URL"),
+    "mysql": os.environ.get("SQLRUSTLER_MYSQL_URL"),
+    "sqlite": "sqlite::memory:",
+}
+
+DRIVERS = {
+    "postgres": DatabaseType.Postgres,
+    "mysql": DatabaseType.Mysql,
+    "sqlite": DatabaseType.Sqlite,
+}
+
+
+def connect(backend, **options):
+    # A single connection by default, so every statement sees the same in-memory SQLite
+    # database
+    options = {"max_connections": 1, "min_connections": 1, **options}
+    return DatabaseConnection(DatabaseConfig(DRIVERS[backend], URLS[backend], **options))
+
+
+class BackendTestCase(unittest.TestCase):
+    backends = ("postgres", "mysql", "sqlite")
+
+    @contextmanager
+    def transaction(self, backend, **options):
+        """A transaction on `backend`, rolled back afterwards; skips the (sub)test when the
+        backend is not configured."""
+        if URLS[backend] is None:
+            self.skipTest(f"set SQLRUSTLER_{backend.upper()}_URL to run")
+        transaction = connect(backend, **options).transaction()
+        try:
+            yield transaction
+        finally:
+            transaction.rollback()
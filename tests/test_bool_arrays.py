@@ -0,0 +1,38 @@
+"""Lists of bools bind as boolean[], never as the integer arrays bool would pass for."""
+
+import unittest
+
+from backends import BackendTestCase
+
+
+class BoolArrayTest(BackendTestCase):
+    backends = ("postgres",)
+
+    def bound_type(self, value):
+        with self.transaction("postgres") as transaction:
+            [row] = transaction.fetch_all("SELECT pg_typeof($1)::text AS t, $1 AS v", [value])
+            return row["t"], row["v"]
+
+    def test_bools_bind_as_boolean_array(self):
+        kind, value = self.bound_type([True, False])
+        self.assertEqual(kind, "boolean[]")
+        self.assertEqual([type(v) for v in value], [bool, bool])
+
+    def test_bools_with_nulls_bind_as_boolean_array(self):
+        self.assertEqual(self.bound_type([None, True]), ("boolean[]", [None, True]))
+
+    def test_ints_that_look_like_bools_bind_as_bigint_array(self):
+        kind, value = self.bound_type([1, 0])
+        self.assertEqual(kind, "bigint[]")
+        self.assertEqual([type(v) for v in value], [int, int])
+
+    def test_bool_scalar_binds_as_boolean(self):
+        self.assertEqual(self.bound_type(True), ("boolean", True))
+
+    def test_bools_mixed_with_ints_are_not_an_array(self):
+        # no element type fits both, so the list goes as JSON
+        self.assertEqual(self.bound_type([True, 1])[0], "jsonb")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,71 @@
+"""bulk_change batches where some rows hold NULL for a typed column."""
+
+import unittest
+import uuid
+from datetime import date, timedelta
+from decimal import Decimal
+
+from backends import BackendTestCase
+from sqlrustler.sqlrustler import Range
+
+UUID_COLUMN = {"postgres": "UUID", "mysql": "CHAR(36)", "sqlite": "TEXT"}
+
+
+class BulkNullTest(BackendTestCase):
+    def test_mixed_null_batches(self):
+        ids = [uuid.uuid4() for _ in range(3)]
+        rows = [[1, None], [2, ids[0]], [3, None], [4, ids[1]], [5, ids[2]], [6, None]]
+        for backend in self.backends:
+            create = f"CREATE TEMPORARY TABLE bulk_nulls (n INTEGER, id {UUID_COLUMN[backend]})"
+            expected = [
+                {"n": n, "id": id if backend == "postgres" or id is None else str(id)}
+                for n, id in rows
+            ]
+            # one row per statement, one multi-row INSERT, and batches starting with a NULL
+            for batch_size in (1, 6, 2):
+                with self.subTest(backend=backend, batch_size=batch_size):
+                    with self.transaction(backend) as transaction:
+                        transaction.execute(create, [])
+                        affected = transaction.bulk_change(
+                            "INSERT INTO bulk_nulls (n, id) VALUES ($1, $2)", rows, batch_size
+                        )
+                        self.assertEqual(affected, len(rows))
+                        stored = transaction.fetch_all(
+                            "SELECT n, id FROM bulk_nulls ORDER BY n", []
+                        )
+                        self.assertEqual(stored, expected)
+
+    def test_typed_nulls_beyond_scalars(self):
+        # (column type, a value binding as that type, as it reads back)
+        cases = [
+            ("BYTEA[]", [b"a", None], [b"a", None]),
+            ("INTERVAL[]", [timedelta(hours=1)], [timedelta(hours=1)]),
+            ("INT4RANGE", Range(1, 5), Range(1, 5)),
+            ("INT4RANGE", range(1, 5), Range(1, 5)),
+            ("INT8RANGE", Range(1, 2**40), Range(1, 2**40)),
+            ("NUMRANGE", Range(Decimal("1.5"), None), Range(Decimal("1.5"), None)),
+            ("DATERANGE", Range(date(2024, 1, 1), date(2024, 2, 1)), None),
+            ("DOUBLE PRECISION[]", [1.5, None], [1.5, None]),
+            ("BIGINT[][]", [[1, 2], [3, 4]], [[1, 2], [3, 4]]),
+            ("TEXT[]", frozenset(["a"]), ["a"]),
+        ]
+        for column, value, stored in cases:
+            create = f"CREATE TEMPORARY TABLE typed_nulls (n INTEGER, v {column})"
+            # the NULL first, in one multi-row INSERT and in a statement of its own
+            for batch_size in (2, 1):
+                with self.subTest(column=column, value=value, batch_size=batch_size):
+                    with self.transaction("postgres") as transaction:
+                        transaction.execute(create, [])
+                        transaction.bulk_change(
+                            "INSERT INTO typed_nulls (n, v) VALUES ($1, $2)",
+                            [[1, None], [2, value]],
+                            batch_size,
+                        )
+                        rows = transaction.fetch_all("SELECT v FROM typed_nulls ORDER BY n", [])
+                        self.assertIsNone(rows[0]["v"])
+                        if stored is not None:
+                            self.assertEqual(repr(rows[1]["v"]), repr(stored))
+
+
+if __name__ == "__main__":
+    unittest.main()
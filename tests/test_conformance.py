@@ -0,0 +1,68 @@
+"""The same parameter matrix bound and read back on every backend."""
+
+import unittest
+import uuid
+from datetime import date, datetime, time, timedelta, timezone
+from decimal import Decimal
+
+from backends import BackendTestCase
+
+ID = uuid.UUID("12345678-1234-5678-1234-567812345678")
+WHEN = datetime(2024, 2, 29, 12, 30, 45, 123456)
+
+# (column type per backend, value bound, value read back where it differs: MySQL and SQLite
+# have no uuid type, SQLite no decimal type, and JSON reads back as its text everywhere)
+MATRIX = [
+    ("BOOLEAN", True, {}),
+    ("BOOLEAN", False, {}),
+    ("BIGINT", 0, {}),
+    ("BIGINT", 2**40, {}),
+    ("BIGINT", -(2**40), {}),
+    ({"postgres": "DOUBLE PRECISION", "mysql": "DOUBLE", "sqlite": "REAL"}, 1.5, {}),
+    ("TEXT", "héllo", {}),
+    ({"postgres": "BYTEA", "mysql": "BLOB", "sqlite": "BLOB"}, b"\x00\xff", {}),
+    ("DATE", date(2024, 2, 29), {}),
+    ({"postgres": "TIMESTAMP", "mysql": "DATETIME(6)", "sqlite": "DATETIME"}, WHEN, {}),
+    (
+        {"postgres": "TIMESTAMPTZ", "mysql": "TIMESTAMP(6)", "sqlite": "DATETIME"},
+        WHEN.replace(tzinfo=timezone(timedelta(hours=2))),
+        {},
+    ),
+    ({"postgres": "TIME", "mysql": "TIME(6)", "sqlite": "TIME"}, time(12, 30, 45, 123456), {}),
+    (
+        {"postgres": "NUMERIC(12, 4)", "mysql": "DECIMAL(12, 4)", "sqlite": "TEXT"},
+        Decimal("12.25"),
+        {"sqlite": "12.25"},
+    ),
+    (
+        {"postgres": "UUID", "mysql": "CHAR(36)", "sqlite": "TEXT"},
+        ID,
+        {"mysql": str(ID), "sqlite": str(ID)},
+    ),
+    (
+        {"postgres": "JSONB", "mysql": "JSON", "sqlite": "TEXT"},
+        {"a": [1, 2]},
+        {backend: '{"a":[1,2]}' for backend in ("postgres", "mysql", "sqlite")},
+    ),
+    # a NULL bound on its own is text on PostgreSQL, so only a text column accepts it
+    ("TEXT", None, {}),
+]
+
+
+class ConformanceTest(BackendTestCase):
+    def test_round_trip(self):
+        for backend in self.backends:
+            with self.subTest(backend=backend), self.transaction(backend) as transaction:
+                for i, (column, value, stored) in enumerate(MATRIX):
+                    column = column if isinstance(column, str) else column[backend]
+                    expected = stored.get(backend, value)
+                    table = f"conformance_{i}"
+                    transaction.execute(f"CREATE TEMPORARY TABLE {table} (v {column})", [])
+                    transaction.execute(f"INSERT INTO {table} (v) VALUES ($1)", [value])
+                    [row] = transaction.fetch_all(f"SELECT v FROM {table}", [])
+                    with self.subTest(column=column, value=value):
+                        self.assertEqual(row["v"], expected)
+                        self.assertIs(type(row["v"]), type(expected))
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,35 @@
+"""A transaction keeps one connection, so session state persists across its statements."""
+
+import unittest
+
+from backends import BackendTestCase
+
+
+# With other connections in the pool, a statement that went elsewhere would not find the table
+POOL = {"max_connections": 4, "min_connections": 4}
+
+
+class ConnectionAffinityTest(BackendTestCase):
+    def test_temp_table_is_visible_to_later_statements(self):
+        for backend in self.backends:
+            with self.subTest(backend=backend), self.transaction(backend, **POOL) as transaction:
+                transaction.execute("CREATE TEMPORARY TABLE affinity (n INTEGER)", [])
+                transaction.execute("INSERT INTO affinity (n) VALUES ($1)", [1])
+                transaction.commit()
+                transaction.execute("INSERT INTO affinity (n) VALUES ($1)", [2])
+                rows = transaction.fetch_all("SELECT n FROM affinity ORDER BY n", [])
+                self.assertEqual(rows, [{"n": 1}, {"n": 2}])
+
+    def test_connection_survives_commit_and_rollback(self):
+        for backend in ("postgres", "mysql"):
+            with self.subTest(backend=backend), self.transaction(backend, **POOL) as transaction:
+                connection_id = transaction.connection_id()
+                self.assertIsNotNone(connection_id)
+                transaction.commit()
+                self.assertEqual(transaction.connection_id(), connection_id)
+                transaction.rollback()
+                self.assertEqual(transaction.connection_id(), connection_id)
+
+
+if __name__ == "__main__":
+    unittest.main()
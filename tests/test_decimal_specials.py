@@ -0,0 +1,47 @@
+"""Decimal NaN, infinities and signaling NaN: which backends take them and which raise."""
+
+import unittest
+from decimal import Decimal
+
+from backends import BackendTestCase
+
+REJECTED = ["Infinity", "-Infinity", "sNaN"]
+
+
+class DecimalSpecialTest(BackendTestCase):
+    backends = ("postgres", "mysql")
+
+    def test_postgres_nan_round_trips(self):
+        with self.transaction("postgres") as transaction:
+            transaction.execute("CREATE TEMPORARY TABLE specials (v NUMERIC)", [])
+            transaction.execute("INSERT INTO specials (v) VALUES ($1)", [Decimal("NaN")])
+            [row] = transaction.fetch_all(
+                "SELECT v, v = 'NaN'::numeric AS is_nan FROM specials", []
+            )
+            self.assertIsInstance(row["v"], Decimal)
+            self.assertTrue(row["v"].is_nan())
+            self.assertTrue(row["is_nan"])
+
+    def test_infinities_and_signaling_nan_raise(self):
+        for backend in self.backends:
+            for text in REJECTED:
+                with self.subTest(backend=backend, value=text):
+                    with self.transaction(backend) as transaction, self.assertRaises(ValueError):
+                        transaction.fetch_all("SELECT $1 AS v", [Decimal(text)])
+
+    def test_mysql_rejects_nan(self):
+        with self.transaction("mysql") as transaction:
+            with self.assertRaisesRegex(ValueError, "MySQL DECIMAL has no NaN"):
+                transaction.fetch_all("SELECT $1 AS v", [Decimal("NaN")])
+
+    def test_finite_values_keep_their_digits(self):
+        for backend in self.backends:
+            with self.subTest(backend=backend), self.transaction(backend) as transaction:
+                [row] = transaction.fetch_all(
+                    "SELECT CAST($1 AS DECIMAL(20, 4)) AS v", [Decimal("-12.5000")]
+                )
+                self.assertEqual(str(row["v"]), "-12.5000")
+
+
+if __name__ == "__main__":
+    unittest.main()
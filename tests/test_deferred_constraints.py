@@ -0,0 +1,48 @@
+"""A constraint deferred with set_constraints is checked, and raises, at commit."""
+
+import unittest
+
+from backends import BackendTestCase
+
+
+class DeferredConstraintTest(BackendTestCase):
+    backends = ("postgres", "sqlite")
+
+    def test_violation_raises_at_commit(self):
+        for backend in self.backends:
+            with self.subTest(backend=backend), self.transaction(backend) as transaction:
+                transaction.execute("CREATE TEMPORARY TABLE parents (id INTEGER PRIMARY KEY)", [])
+                transaction.execute(
+                    "CREATE TEMPORARY TABLE children (parent INTEGER REFERENCES parents (id) "
+                    "DEFERRABLE INITIALLY IMMEDIATE)",
+                    [],
+                )
+                transaction.commit()
+                transaction.set_constraints(True)
+                # deferred, so the statement itself succeeds
+                transaction.execute("INSERT INTO children (parent) VALUES ($1)", [1])
+                with self.assertRaisesRegex(RuntimeError, "(?i)foreign key"):
+                    transaction.commit()
+                # the failed transaction is over and a new one has begun
+                self.assertEqual(transaction.fetch_all("SELECT 1 AS one", []), [{"one": 1}])
+
+    def test_satisfied_constraint_commits(self):
+        for backend in self.backends:
+            with self.subTest(backend=backend), self.transaction(backend) as transaction:
+                transaction.execute("CREATE TEMPORARY TABLE parents (id INTEGER PRIMARY KEY)", [])
+                transaction.execute(
+                    "CREATE TEMPORARY TABLE children (parent INTEGER REFERENCES parents (id) "
+                    "DEFERRABLE INITIALLY IMMEDIATE)",
+                    [],
+                )
+                transaction.commit()
+                transaction.set_constraints(True)
+                transaction.execute("INSERT INTO children (parent) VALUES ($1)", [1])
+                transaction.execute("INSERT INTO parents (id) VALUES ($1)", [1])
+                transaction.commit()
+                rows = transaction.fetch_all("SELECT parent FROM children", [])
+                self.assertEqual(rows, [{"parent": 1}])
+
+
+if __name__ == "__main__":
+    unittest.main()
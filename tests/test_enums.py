@@ -0,0 +1,40 @@
+"""User-defined PostgreSQL enums bind from EnumValue and read back as their str labels."""
+
+import unittest
+
+from backends import BackendTestCase
+from sqlrustler.sqlrustler import EnumValue
+
+
+class EnumTest(BackendTestCase):
+    backends = ("postgres",)
+
+    def test_enum_round_trip(self):
+        with self.transaction("postgres") as transaction:
+            transaction.execute("CREATE TYPE mood AS ENUM ('sad', 'ok', 'happy')", [])
+            transaction.execute("CREATE TEMPORARY TABLE moods (n INTEGER, m mood)", [])
+            for n, label in enumerate(["happy", "sad"]):
+                transaction.execute(
+                    "INSERT INTO moods (n, m) VALUES ($1, $2)", [n, EnumValue("mood", label)]
+                )
+            # a plain str works where the SQL casts it
+            transaction.execute("INSERT INTO moods (n, m) VALUES ($1, $2::mood)", [2, "ok"])
+            rows = transaction.fetch_all("SELECT m FROM moods ORDER BY n", [])
+            self.assertEqual(rows, [{"m": "happy"}, {"m": "sad"}, {"m": "ok"}])
+            self.assertEqual([type(row["m"]) for row in rows], [str, str, str])
+
+            # enums compare by declaration order, not as text
+            rows = transaction.fetch_all(
+                "SELECT m FROM moods WHERE m > $1 ORDER BY m", [EnumValue("mood", "sad")]
+            )
+            self.assertEqual(rows, [{"m": "ok"}, {"m": "happy"}])
+
+            [row] = transaction.fetch_all(
+                "SELECT array_agg(m ORDER BY m) AS ms, pg_typeof($1)::text AS t FROM moods",
+                [EnumValue("mood", "ok")],
+            )
+            self.assertEqual(row, {"ms": ["sad", "ok", "happy"], "t": "mood"})
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,50 @@
+"""timedelta binds as interval and interval reads back as timedelta, a month as 30 days."""
+
+import unittest
+from datetime import timedelta
+
+from backends import BackendTestCase
+
+
+class IntervalTest(BackendTestCase):
+    backends = ("postgres",)
+
+    def test_day_and_time_intervals_round_trip(self):
+        values = [
+            timedelta(days=3, hours=4, minutes=5, seconds=6, microseconds=7),
+            timedelta(0),
+            timedelta(seconds=-5),
+            timedelta(days=-2, hours=1),
+            timedelta(days=999_999_999),
+        ]
+        with self.transaction("postgres") as transaction:
+            transaction.execute("CREATE TEMPORARY TABLE intervals (n INTEGER, v INTERVAL)", [])
+            for n, value in enumerate(values):
+                transaction.execute("INSERT INTO intervals (n, v) VALUES ($1, $2)", [n, value])
+            rows = transaction.fetch_all("SELECT v FROM intervals ORDER BY n", [])
+            self.assertEqual([row["v"] for row in rows], values)
+
+    def test_timedelta_binds_as_interval(self):
+        with self.transaction("postgres") as transaction:
+            [row] = transaction.fetch_all(
+                "SELECT pg_typeof($1)::text AS t, pg_typeof($2)::text AS ts",
+                [timedelta(hours=1), [timedelta(hours=1), None]],
+            )
+            self.assertEqual(row, {"t": "interval", "ts": "interval[]"})
+
+    def test_months_count_as_30_days(self):
+        with self.transaction("postgres") as transaction:
+            [row] = transaction.fetch_all(
+                "SELECT '1 mon 2 days 03:00'::interval AS a, '-1 year'::interval AS b", []
+            )
+            self.assertEqual(row["a"], timedelta(days=32, hours=3))
+            self.assertEqual(row["b"], timedelta(days=-360))
+
+    def test_interval_past_timedelta_range_raises(self):
+        with self.transaction("postgres") as transaction:
+            with self.assertRaises(OverflowError):
+                transaction.fetch_all("SELECT '3000000 years'::interval AS v", [])
+
+
+if __name__ == "__main__":
+    unittest.main()
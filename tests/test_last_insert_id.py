@@ -0,0 +1,36 @@
+"""last_insert_id returns the key of the row just inserted; PostgreSQL points to RETURNING."""
+
+import unittest
+
+from backends import BackendTestCase
+
+CREATE = {
+    "mysql": "CREATE TEMPORARY TABLE items (id BIGINT AUTO_INCREMENT PRIMARY KEY, name TEXT)",
+    "sqlite": "CREATE TEMPORARY TABLE items (id INTEGER PRIMARY KEY, name TEXT)",
+}
+
+
+class LastInsertIdTest(BackendTestCase):
+    backends = ("mysql", "sqlite")
+
+    def test_returns_the_new_primary_key(self):
+        for backend in self.backends:
+            with self.subTest(backend=backend), self.transaction(backend) as transaction:
+                self.assertIsNone(transaction.last_insert_id())
+                transaction.execute(CREATE[backend], [])
+                transaction.execute("INSERT INTO items (name) VALUES ($1)", ["a"])
+                first = transaction.last_insert_id()
+                transaction.execute("INSERT INTO items (name) VALUES ($1)", ["b"])
+                second = transaction.last_insert_id()
+                rows = transaction.fetch_all("SELECT id, name FROM items ORDER BY id", [])
+                self.assertEqual(rows, [{"id": first, "name": "a"}, {"id": second, "name": "b"}])
+                self.assertEqual(second, first + 1)
+
+    def test_postgres_points_to_returning(self):
+        with self.transaction("postgres") as transaction:
+            with self.assertRaisesRegex(NotImplementedError, "INSERT ... RETURNING"):
+                transaction.last_insert_id()
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,38 @@
+"""`:name` placeholders bind from a dict, each distinct name once."""
+
+import unittest
+
+from backends import BackendTestCase
+from sqlrustler.sqlrustler import DatabaseType, named_parameters
+
+
+class NamedParameterTest(BackendTestCase):
+    def test_repeated_names_bind_once(self):
+        query, params = named_parameters(
+            "SELECT :a, ':a', :b, :a", {"a": 1, "b": "x", "unused": None}
+        )
+        self.assertEqual(query, "SELECT $1, ':a', $2, $1")
+        self.assertEqual(params, [1, "x"])
+
+    def test_driver_decides_what_is_a_literal(self):
+        query = "SELECT `:a`, :b"
+        mysql, _ = named_parameters(query, {"b": 1}, DatabaseType.Mysql)
+        self.assertEqual(mysql, "SELECT `:a`, $1")
+        with self.assertRaises(KeyError):
+            named_parameters(query, {"b": 1}, DatabaseType.Postgres)
+
+    def test_missing_name_raises_key_error(self):
+        with self.assertRaisesRegex(KeyError, "Named parameter :b has no value"):
+            named_parameters("SELECT :a, :b", {"a": 1})
+
+    def test_fetch_all_named(self):
+        for backend in self.backends:
+            with self.subTest(backend=backend), self.transaction(backend) as transaction:
+                rows = transaction.fetch_all_named(
+                    "SELECT :n AS a, :n + 1 AS b, ':n' AS c", {"n": 1}
+                )
+                self.assertEqual(rows, [{"a": 1, "b": 2, "c": ":n"}])
+
+
+if __name__ == "__main__":
+    unittest.main()
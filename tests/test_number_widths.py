@@ -0,0 +1,44 @@
+"""Each integer and float width reads back as its value, the narrow ones included."""
+
+import unittest
+
+from backends import BackendTestCase
+
+
+class NumberWidthTest(BackendTestCase):
+    backends = ("postgres",)
+
+    def read_back(self, column, values):
+        with self.transaction("postgres") as transaction:
+            transaction.execute(f"CREATE TEMPORARY TABLE widths (n INTEGER, v {column})", [])
+            for n, value in enumerate(values):
+                transaction.execute(
+                    f"INSERT INTO widths (n, v) VALUES ($1, $2::{column})", [n, value]
+                )
+            rows = transaction.fetch_all("SELECT v FROM widths ORDER BY n", [])
+            return [row["v"] for row in rows]
+
+    def assert_round_trip(self, column, values, kind):
+        stored = self.read_back(column, values)
+        self.assertEqual(stored, values)
+        self.assertEqual([type(v) for v in stored], [kind] * len(values))
+
+    def test_smallint(self):
+        self.assert_round_trip("SMALLINT", [-(2**15), 0, 7, 2**15 - 1], int)
+
+    def test_integer(self):
+        self.assert_round_trip("INTEGER", [-(2**31), 0, 2**31 - 1], int)
+
+    def test_bigint(self):
+        self.assert_round_trip("BIGINT", [-(2**63), 0, 2**63 - 1], int)
+
+    def test_real(self):
+        # values a float32 holds exactly, so widening to a Python float changes nothing
+        self.assert_round_trip("REAL", [-1.5, 0.0, 0.25, 16777216.0], float)
+
+    def test_double_precision(self):
+        self.assert_round_trip("DOUBLE PRECISION", [-1.5, 0.1, 1e300], float)
+
+
+if __name__ == "__main__":
+    unittest.main()
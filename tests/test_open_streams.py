@@ -0,0 +1,55 @@
+"""Calls on a transaction with an open stream raise instead of waiting for the stream."""
+
+import unittest
+
+from backends import BackendTestCase
+
+ROWS = "SELECT 1 AS n UNION ALL SELECT 2 UNION ALL SELECT 3 ORDER BY n"
+BUSY = "transaction is busy with an open stream"
+
+
+class OpenStreamTest(BackendTestCase):
+    backends = ("postgres", "sqlite")
+
+    def test_calls_during_a_row_stream_raise(self):
+        for backend in self.backends:
+            with self.subTest(backend=backend), self.transaction(backend) as transaction:
+                stream = transaction.stream_data(ROWS, [], 1)
+                self.assertEqual(next(stream), [{"n": 1}])
+                with self.assertRaisesRegex(RuntimeError, BUSY):
+                    transaction.fetch_all("SELECT 1 AS one", [])
+                with self.assertRaisesRegex(RuntimeError, BUSY):
+                    transaction.stream_ndjson(ROWS, [])
+                with self.assertRaisesRegex(RuntimeError, BUSY):
+                    transaction.commit()
+                # the stream itself is unaffected
+                self.assertEqual(list(stream), [[{"n": 2}], [{"n": 3}]])
+                self.assertEqual(transaction.fetch_all("SELECT 1 AS one", []), [{"one": 1}])
+
+    def test_calls_during_an_ndjson_reader_raise(self):
+        for backend in self.backends:
+            with self.subTest(backend=backend), self.transaction(backend) as transaction:
+                reader = transaction.stream_ndjson(ROWS, [])
+                self.assertEqual(reader.readline(), b'{"n":1}\n')
+                with self.assertRaisesRegex(RuntimeError, BUSY):
+                    transaction.execute("SELECT 1", [])
+                with self.assertRaisesRegex(RuntimeError, BUSY):
+                    transaction.stream_data(ROWS, [], 1)
+                reader.close()
+                self.assertTrue(transaction.ping())
+
+    def test_closing_or_dropping_a_stream_frees_the_transaction(self):
+        for backend in self.backends:
+            with self.subTest(backend=backend), self.transaction(backend) as transaction:
+                stream = transaction.stream_data(ROWS, [], 1)
+                next(stream)
+                stream.close()
+                self.assertEqual(transaction.fetch_all("SELECT 1 AS one", []), [{"one": 1}])
+                stream = transaction.stream_data(ROWS, [], 1)
+                next(stream)
+                del stream
+                self.assertEqual(transaction.fetch_all("SELECT 1 AS one", []), [{"one": 1}])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,31 @@
+"""A parameter list that does not match the query's placeholders fails before anything runs."""
+
+import unittest
+
+from backends import BackendTestCase
+
+
+class ParameterCountTest(BackendTestCase):
+    def test_too_many_and_too_few(self):
+        for backend in self.backends:
+            with self.subTest(backend=backend), self.transaction(backend) as transaction:
+                with self.assertRaisesRegex(
+                    ValueError, r"^Query expects 1 parameter but 2 were provided$"
+                ):
+                    transaction.fetch_all("SELECT $1 AS a", [1, 2])
+                with self.assertRaisesRegex(
+                    ValueError, r"^Query expects 2 parameters but 1 was provided$"
+                ):
+                    transaction.fetch_all("SELECT $1 AS a, $2 AS b", [1])
+                # nothing was sent, so the transaction is still usable
+                self.assertEqual(transaction.fetch_all("SELECT $1 AS a", [1]), [{"a": 1}])
+
+    def test_placeholders_in_literals_and_comments_do_not_count(self):
+        for backend in self.backends:
+            with self.subTest(backend=backend), self.transaction(backend) as transaction:
+                rows = transaction.fetch_all("SELECT '$2' AS a, $1 AS b /* $3 */", [1])
+                self.assertEqual(rows, [{"a": "$2", "b": 1}])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,40 @@
+"""Pool sizes are checked when they are set, and a full pool times out instead of waiting."""
+
+import unittest
+
+from backends import BackendTestCase, connect
+from sqlrustler.sqlrustler import DatabaseConfig, DatabaseType
+
+
+class PoolLimitTest(BackendTestCase):
+    backends = ("sqlite",)
+
+    def test_min_connections_may_not_exceed_max(self):
+        with self.assertRaisesRegex(ValueError, r"min_connections \(5\) must not exceed"):
+            DatabaseConfig(
+                DatabaseType.Sqlite, "sqlite::memory:", max_connections=2, min_connections=5
+            )
+        config = DatabaseConfig(
+            DatabaseType.Sqlite, "sqlite::memory:", max_connections=2, min_connections=2
+        )
+        with self.assertRaises(ValueError):
+            config.min_connections = 3
+        with self.assertRaises(ValueError):
+            config.max_connections = 1
+        self.assertEqual((config.max_connections, config.min_connections), (2, 2))
+        config.min_connections = 1
+        config.max_connections = 1
+        self.assertEqual((config.max_connections, config.min_connections), (1, 1))
+
+    def test_acquire_times_out_when_the_pool_is_full(self):
+        connection = connect("sqlite", acquire_timeout_secs=0.2)
+        held = connection.transaction()
+        try:
+            with self.assertRaisesRegex(RuntimeError, "pool timed out"):
+                connection.transaction()
+        finally:
+            held.rollback()
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,69 @@
+"""SQLite columns declared DATETIME, DATE or TIME read back as the Python types bound to them."""
+
+import unittest
+from datetime import date, datetime, time, timedelta, timezone
+
+from backends import BackendTestCase
+
+CREATE = "CREATE TEMPORARY TABLE events (n INTEGER, at DATETIME, day DATE, clock TIME, note TEXT)"
+INSERT = "INSERT INTO events (n, at, day, clock, note) VALUES ($1, $2, $3, $4, $5)"
+NAIVE = datetime(2024, 1, 2, 3, 4, 5, 678000)
+AWARE = datetime(2024, 1, 2, 3, 4, 5, tzinfo=timezone(timedelta(hours=-5)))
+
+
+class SqliteTypeTest(BackendTestCase):
+    backends = ("sqlite",)
+
+    def test_bound_values_read_back_typed(self):
+        with self.transaction("sqlite") as transaction:
+            transaction.execute(CREATE, [])
+            transaction.execute(INSERT, [1, NAIVE, date(2024, 1, 2), time(3, 4, 5), "x"])
+            transaction.execute(INSERT, [2, AWARE, None, None, None])
+            rows = transaction.fetch_all("SELECT at, day, clock, note FROM events ORDER BY n", [])
+            self.assertEqual(
+                rows[0],
+                {"at": NAIVE, "day": date(2024, 1, 2), "clock": time(3, 4, 5), "note": "x"},
+            )
+            self.assertIsNone(rows[0]["at"].tzinfo)
+            self.assertEqual(rows[1]["at"], AWARE)
+            self.assertEqual(rows[1]["at"].tzinfo, timezone.utc)
+
+    def test_other_stored_forms(self):
+        with self.transaction("sqlite") as transaction:
+            transaction.execute(CREATE, [])
+            for n, stored in enumerate(["2024-01-02T03:04:05Z", 1704164645, "not a date"]):
+                transaction.execute("INSERT INTO events (n, at) VALUES ($1, $2)", [n, stored])
+            rows = transaction.fetch_all("SELECT at FROM events ORDER BY n", [])
+            instant = datetime(2024, 1, 2, 3, 4, 5, tzinfo=timezone.utc)
+            # a value that does not parse keeps its text
+            self.assertEqual([row["at"] for row in rows], [instant, instant, "not a date"])
+
+    def test_expressions_and_opt_out_keep_text(self):
+        for options in ({}, {"sqlite_datetimes": False}):
+            with self.subTest(**options), self.transaction("sqlite", **options) as transaction:
+                transaction.execute(CREATE, [])
+                transaction.execute(INSERT, [1, NAIVE, date(2024, 1, 2), None, None])
+                [row] = transaction.fetch_all(
+                    "SELECT at, day, CAST(at AS TEXT) AS at_text FROM events", []
+                )
+                self.assertEqual(row["at_text"], "2024-01-02 03:04:05.678")
+                if options:
+                    self.assertEqual(row["at"], "2024-01-02 03:04:05.678")
+                    self.assertEqual(row["day"], "2024-01-02")
+                else:
+                    self.assertEqual((row["at"], row["day"]), (NAIVE, date(2024, 1, 2)))
+
+    def test_json_parameters_are_stored_as_json_text(self):
+        with self.transaction("sqlite") as transaction:
+            transaction.execute("CREATE TEMPORARY TABLE docs (body TEXT)", [])
+            transaction.execute("INSERT INTO docs (body) VALUES ($1)", [{"a": [1, 2]}])
+            [row] = transaction.fetch_all(
+                "SELECT json_extract(body, '$.a[1]') AS second, json_valid(body) AS valid "
+                "FROM docs",
+                [],
+            )
+            self.assertEqual(row, {"second": 2, "valid": 1})
+
+
+if __name__ == "__main__":
+    unittest.main()
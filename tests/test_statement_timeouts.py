@@ -0,0 +1,39 @@
+"""MySQL statement timeouts look up the connection id once per connection, not per statement."""
+
+import unittest
+
+from backends import BackendTestCase
+
+TIMEOUT = {"default_statement_timeout_ms": 5000}
+
+
+def selects(transaction):
+    [row] = transaction.fetch_all("SHOW SESSION STATUS LIKE 'Com_select'", [], prepared=False)
+    return int(row["Value"])
+
+
+class MySqlStatementTimeoutTest(BackendTestCase):
+    backends = ("mysql",)
+
+    def test_connection_id_is_read_once(self):
+        with self.transaction("mysql", **TIMEOUT) as transaction:
+            before = selects(transaction)
+            for n in range(3):
+                transaction.fetch_all("SELECT $1 AS n", [n])
+            transaction.fetch_all("SELECT $1 AS n", [n], timeout_secs=2.0)
+            transaction.commit()
+            transaction.fetch_all("SELECT $1 AS n", [n])
+            # only the five SELECTs above; SHOW does not count as one
+            self.assertEqual(selects(transaction) - before, 5)
+
+    def test_timeout_after_a_timeout_kills_the_new_connection(self):
+        with self.transaction("mysql", **TIMEOUT) as transaction:
+            for _ in range(2):
+                with self.assertRaises(TimeoutError):
+                    transaction.fetch_all("SELECT SLEEP(5) AS s", [], timeout_secs=0.2)
+                transaction.rollback()
+                self.assertEqual(transaction.fetch_all("SELECT 1 AS n", []), [{"n": 1}])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,47 @@
+"""timestamp columns read back naive and timestamptz ones UTC-aware."""
+
+import unittest
+from datetime import datetime, timedelta, timezone
+
+from backends import BackendTestCase
+
+
+class TimestampTest(BackendTestCase):
+    backends = ("postgres",)
+
+    def test_timestamp_is_naive(self):
+        with self.transaction("postgres") as transaction:
+            [row] = transaction.fetch_all(
+                "SELECT '2024-01-02 03:04:05.678'::timestamp AS v", []
+            )
+            self.assertEqual(row["v"], datetime(2024, 1, 2, 3, 4, 5, 678000))
+            self.assertIsNone(row["v"].tzinfo)
+
+    def test_timestamptz_is_utc_aware(self):
+        with self.transaction("postgres") as transaction:
+            transaction.execute("SET LOCAL TIME ZONE 'Asia/Tokyo'", [])
+            [row] = transaction.fetch_all(
+                "SELECT '2024-01-02 03:04:05+02'::timestamptz AS v", []
+            )
+            self.assertEqual(row["v"], datetime(2024, 1, 2, 1, 4, 5, tzinfo=timezone.utc))
+            self.assertEqual(row["v"].utcoffset(), timedelta(0))
+
+    def test_naive_and_aware_parameters_keep_their_kind(self):
+        naive = datetime(2024, 1, 2, 3, 4, 5)
+        aware = datetime(2024, 1, 2, 3, 4, 5, tzinfo=timezone(timedelta(hours=-5)))
+        with self.transaction("postgres") as transaction:
+            [row] = transaction.fetch_all(
+                "SELECT $1 AS naive, pg_typeof($1)::text AS naive_type, "
+                "$2 AS aware, pg_typeof($2)::text AS aware_type",
+                [naive, aware],
+            )
+            self.assertEqual(row["naive_type"], "timestamp without time zone")
+            self.assertEqual(row["aware_type"], "timestamp with time zone")
+            self.assertEqual(row["naive"], naive)
+            self.assertIsNone(row["naive"].tzinfo)
+            self.assertEqual(row["aware"], aware)
+            self.assertEqual(row["aware"].tzinfo, timezone.utc)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,56 @@
+"""With uuid_strings, PostgreSQL str parameters that parse as a UUID bind as uuid."""
+
+import unittest
+import uuid
+
+from backends import BackendTestCase
+
+ID = uuid.UUID("12345678-1234-5678-1234-567812345678")
+
+
+class UuidStringTest(BackendTestCase):
+    backends = ("postgres",)
+
+    def test_uuid_strings_bind_as_uuid(self):
+        with self.transaction("postgres", uuid_strings=True) as transaction:
+            transaction.execute("CREATE TEMPORARY TABLE items (id uuid, name TEXT)", [])
+            transaction.execute("INSERT INTO items (id, name) VALUES ($1, $2)", [str(ID), "a"])
+            for form in (str(ID), ID.hex, "{%s}" % ID, ID.urn, str(ID).upper()):
+                with self.subTest(form=form):
+                    rows = transaction.fetch_all(
+                        "SELECT id, name FROM items WHERE id = $1", [form]
+                    )
+                    self.assertEqual(rows, [{"id": ID, "name": "a"}])
+            # other strings still bind as text
+            [row] = transaction.fetch_all(
+                "SELECT pg_typeof($1)::text AS u, pg_typeof($2)::text AS t", [str(ID), "a"]
+            )
+            self.assertEqual(row, {"u": "uuid", "t": "text"})
+
+    def test_text_then_uuid_string_in_the_same_statement(self):
+        # the cast keeps the two from sharing a cached statement prepared for text
+        with self.transaction("postgres", uuid_strings=True) as transaction:
+            for value, expected in (("abc", "text"), (str(ID), "uuid"), ("abc", "text")):
+                [row] = transaction.fetch_all("SELECT pg_typeof($1)::text AS t", [value])
+                self.assertEqual(row, {"t": expected})
+
+    def test_batch_nulls_take_the_uuid_type(self):
+        with self.transaction("postgres", uuid_strings=True) as transaction:
+            transaction.execute("CREATE TEMPORARY TABLE items (n INTEGER, id uuid)", [])
+            for batch_size in (1, 2):
+                transaction.bulk_change(
+                    "INSERT INTO items (n, id) VALUES ($1, $2)",
+                    [[batch_size, None], [batch_size, str(ID)]],
+                    batch_size,
+                )
+            rows = transaction.fetch_all("SELECT id FROM items ORDER BY n, id NULLS FIRST", [])
+            self.assertEqual([row["id"] for row in rows], [None, ID, None, ID])
+
+    def test_strings_stay_text_by_default(self):
+        with self.transaction("postgres") as transaction:
+            [row] = transaction.fetch_all("SELECT pg_typeof($1)::text AS t", [str(ID)])
+            self.assertEqual(row, {"t": "text"})
+
+
+if __name__ == "__main__":
+    unittest.main()
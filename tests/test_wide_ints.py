@@ -0,0 +1,38 @@
+"""Python ints past the 64-bit range bind as exact numerics instead of failing."""
+
+import unittest
+from decimal import Decimal
+
+from backends import BackendTestCase
+
+NUMERIC_COLUMN = {"postgres": "NUMERIC", "mysql": "DECIMAL(30, 0)"}
+VALUES = [2**70, -(2**70), 2**63, -(2**63) - 1, 2**63 - 1, 7]
+
+
+class WideIntTest(BackendTestCase):
+    backends = ("postgres", "mysql")
+
+    def test_numeric_round_trip(self):
+        for backend in self.backends:
+            with self.subTest(backend=backend), self.transaction(backend) as transaction:
+                transaction.execute(
+                    f"CREATE TEMPORARY TABLE wide (n INTEGER, v {NUMERIC_COLUMN[backend]})", []
+                )
+                for n, value in enumerate(VALUES):
+                    transaction.execute("INSERT INTO wide (n, v) VALUES ($1, $2)", [n, value])
+                rows = transaction.fetch_all("SELECT v FROM wide ORDER BY n", [])
+                stored = [row["v"] for row in rows]
+                self.assertEqual(stored, [Decimal(value) for value in VALUES])
+                self.assertEqual([int(value) for value in stored], VALUES)
+
+    def test_only_ints_past_bigint_bind_as_numeric(self):
+        with self.transaction("postgres") as transaction:
+            [row] = transaction.fetch_all(
+                "SELECT pg_typeof($1)::text AS small, pg_typeof($2)::text AS wide, $2 AS v",
+                [2**63 - 1, 2**70],
+            )
+            self.assertEqual(row, {"small": "bigint", "wide": "numeric", "v": Decimal(2**70)})
+
+
+if __name__ == "__main__":
+    unittest.main()